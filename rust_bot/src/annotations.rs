@@ -0,0 +1,209 @@
+/// Grafana annotation export for key bot events
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::Serialize;
+use tokio::fs::OpenOptions;
+use tokio::io::AsyncWriteExt;
+use tracing::warn;
+
+use crate::models::ExitReason;
+use crate::notify::{BotEvent, NotificationSink};
+
+/// A Grafana annotation, as accepted by `POST /api/annotations`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Annotation {
+    pub time: i64, // Unix milliseconds
+    pub tags: Vec<String>,
+    pub text: String,
+}
+
+impl Annotation {
+    /// Build the annotation for an event that happened at `time`
+    ///
+    /// Entries, exits, rotations and halts are marked; fills and the shutdown
+    /// summary aren't.
+    pub fn from_event(event: &BotEvent, time: i64) -> Option<Self> {
+        let tags = |tags: &[&str]| {
+            std::iter::once("vulture")
+                .chain(tags.iter().copied())
+                .map(str::to_string)
+                .collect()
+        };
+
+        let annotation = match event {
+            BotEvent::OrderPlaced { token_id, price, size } => Self {
+                time,
+                tags: tags(&["entry"]),
+                text: format!(
+                    "BUY {} @ {:.4} ({}...)",
+                    size,
+                    price,
+                    &token_id[..8.min(token_id.len())]
+                ),
+            },
+            BotEvent::Exited { trade, .. } => Self {
+                // Exits carry their own timestamp
+                time: trade.exit_time,
+                tags: tags(&["exit", exit_tag(trade.exit_reason)]),
+                text: format!(
+                    "SELL {} @ {:.4} ({:?}) | P&L ${:.2}",
+                    trade.size, trade.exit_price, trade.exit_reason, trade.pnl
+                ),
+            },
+            BotEvent::Rotated { slug, .. } => Self {
+                time,
+                tags: tags(&["rotation"]),
+                text: format!("Rotated out of {}", slug),
+            },
+            BotEvent::Halted { reason } => Self {
+                time,
+                tags: tags(&["breaker"]),
+                text: reason.to_string(),
+            },
+            BotEvent::Filled { .. } | BotEvent::Shutdown { .. } => return None,
+        };
+        Some(annotation)
+    }
+}
+
+fn exit_tag(reason: ExitReason) -> &'static str {
+    match reason {
+        ExitReason::TakeProfit => "take_profit",
+        ExitReason::StopLoss => "stop_loss",
+        ExitReason::TrailingStop => "trailing_stop",
+        ExitReason::FairValueBand => "fair_value_band",
+        ExitReason::ExpiryExit => "expiry_exit",
+        ExitReason::MaxHold => "max_hold",
+        ExitReason::DeadMan => "dead_man",
+        ExitReason::SpreadCapture => "spread_capture",
+        ExitReason::Rotation => "rotation",
+        ExitReason::Manual => "manual",
+    }
+}
+
+/// Where annotations are sent
+#[derive(Debug, Clone, PartialEq)]
+enum Target {
+    Disabled,
+    File(String),          // appended as JSON lines
+    Http { url: String },  // Grafana annotations API
+}
+
+/// Emits annotations to the configured target
+///
+/// `ANNOTATION_TARGET` is empty (disabled), a file path, or a Grafana
+/// `http(s)://.../api/annotations` URL. Failures are logged, never fatal.
+pub struct AnnotationExporter {
+    target: Target,
+    api_key: String,
+    client: reqwest::Client,
+}
+
+impl AnnotationExporter {
+    pub fn new(target: &str, api_key: &str, client: reqwest::Client) -> Self {
+        let target = target.trim();
+        let target = if target.is_empty() {
+            Target::Disabled
+        } else if target.starts_with("http://") || target.starts_with("https://") {
+            Target::Http { url: target.to_string() }
+        } else {
+            Target::File(target.to_string())
+        };
+
+        Self {
+            target,
+            api_key: api_key.to_string(),
+            client,
+        }
+    }
+
+    async fn send(&self, annotation: &Annotation) -> Result<()> {
+        match &self.target {
+            Target::Disabled => Ok(()),
+            Target::File(path) => {
+                let mut line = serde_json::to_string(annotation)?;
+                line.push('\n');
+                let mut file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .await
+                    .with_context(|| format!("Failed to open {}", path))?;
+                file.write_all(line.as_bytes()).await?;
+                Ok(())
+            }
+            Target::Http { url } => {
+                let mut request = self.client.post(url).json(annotation);
+                if !self.api_key.is_empty() {
+                    request = request.bearer_auth(&self.api_key);
+                }
+                request.send().await?.error_for_status()?;
+                Ok(())
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl NotificationSink for AnnotationExporter {
+    /// Export an event happening now
+    async fn notify(&self, event: &BotEvent<'_>) {
+        if self.target == Target::Disabled {
+            return;
+        }
+
+        let Some(annotation) = Annotation::from_event(event, chrono::Utc::now().timestamp_millis()) else {
+            return;
+        };
+        if let Err(e) = self.send(&annotation).await {
+            warn!("⚠️ Failed to export annotation: {:#}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ClosedTrade;
+    use rust_decimal::Decimal;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_exit_annotation() {
+        let trade = ClosedTrade {
+            token_id: "token".to_string(),
+            entry_price: Decimal::from_str("0.40").unwrap(),
+            exit_price: Decimal::from_str("0.45").unwrap(),
+            size: Decimal::from(10),
+            pnl: Decimal::from_str("0.50").unwrap(),
+            entry_time: 1_700_000_000_000,
+            exit_time: 1_700_000_060_000,
+            exit_reason: ExitReason::TakeProfit,
+            committed_time: 1_700_000_000_000,
+        };
+
+        let event = BotEvent::Exited { trade: &trade, cash: Decimal::from(100) };
+        let annotation = Annotation::from_event(&event, 1_700_000_099_999).unwrap();
+        assert_eq!(
+            serde_json::to_value(&annotation).unwrap(),
+            serde_json::json!({
+                "time": 1_700_000_060_000i64,
+                "tags": ["vulture", "exit", "take_profit"],
+                "text": "SELL 10 @ 0.4500 (TakeProfit) | P&L $0.50"
+            })
+        );
+    }
+
+    #[test]
+    fn test_target_parsing() {
+        assert_eq!(AnnotationExporter::new("", "", reqwest::Client::new()).target, Target::Disabled);
+        assert_eq!(
+            AnnotationExporter::new("annotations.jsonl", "", reqwest::Client::new()).target,
+            Target::File("annotations.jsonl".to_string())
+        );
+        assert_eq!(
+            AnnotationExporter::new("http://localhost:3000/api/annotations", "key", reqwest::Client::new()).target,
+            Target::Http { url: "http://localhost:3000/api/annotations".to_string() }
+        );
+    }
+}
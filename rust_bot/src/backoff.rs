@@ -0,0 +1,58 @@
+/// Exponential backoff with jitter, shared by the price feeds' reconnect and poll loops
+use rand::Rng;
+use tokio::time::Duration;
+
+/// First delay after a failure; doubles on each failure in a row
+pub const BASE_DELAY: Duration = Duration::from_secs(1);
+
+pub struct Backoff {
+    max: Duration,
+    failures: u32, // consecutive failures since the last reset
+}
+
+impl Backoff {
+    pub fn new(max: Duration) -> Self {
+        Self { max, failures: 0 }
+    }
+
+    /// Delay before the next attempt
+    ///
+    /// Doubles from `BASE_DELAY` up to `max`, with jitter in `[d/2, d]` so
+    /// loops that fail together don't retry in lockstep.
+    pub fn next_delay<R: Rng>(&mut self, rng: &mut R) -> Duration {
+        let delay = BASE_DELAY
+            .saturating_mul(2u32.saturating_pow(self.failures))
+            .min(self.max);
+        self.failures = self.failures.saturating_add(1);
+        delay.mul_f64(rng.gen_range(0.5..=1.0))
+    }
+
+    /// Start over from the base delay after a success
+    pub fn reset(&mut self) {
+        self.failures = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_backoff_grows_and_resets() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let mut backoff = Backoff::new(Duration::from_secs(10));
+
+        // Each delay is jittered into [d/2, d] of 1s, 2s, 4s, 8s, then the 10s cap
+        for full in [1, 2, 4, 8, 10, 10] {
+            let delay = backoff.next_delay(&mut rng);
+            let full = Duration::from_secs(full);
+            assert!(delay >= full / 2 && delay <= full, "{:?} outside [{:?}/2, {:?}]", delay, full, full);
+        }
+
+        backoff.reset();
+        assert!(backoff.next_delay(&mut rng) <= BASE_DELAY);
+        let delay = backoff.next_delay(&mut rng);
+        assert!(delay >= BASE_DELAY && delay <= BASE_DELAY * 2);
+    }
+}
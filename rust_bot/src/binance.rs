@@ -1,19 +1,36 @@
 /// Binance WebSocket client for real-time BTC/USDT price streaming
 use anyhow::{Context, Result};
-use futures_util::{SinkExt, StreamExt};
+use async_trait::async_trait;
+use futures_util::{Sink, SinkExt, Stream, StreamExt};
+use rand::{Rng, SeedableRng};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 use std::sync::Arc;
 use tokio::sync::RwLock;
-use tokio::time::{Duration, interval};
-use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tokio::time::{Duration, Instant, interval};
+use tokio_tungstenite::{connect_async, tungstenite, tungstenite::Message};
 use tracing::{error, info, warn};
 
+use crate::backoff::Backoff;
+use crate::price_source::{PriceSource, ReferenceBook};
+
 const BINANCE_WS_URL: &str = "wss://stream.binance.com:9443/ws/btcusdt@trade";
+const BINANCE_DEPTH_WS_URL: &str = "wss://stream.binance.com:9443/ws/btcusdt@depth5@100ms";
 const BINANCE_REST_URL: &str = "https://api.binance.com/api/v3/ticker/price?symbol=BTCUSDT";
 const REST_FALLBACK_INTERVAL_SECS: u64 = 5;
 
+/// A connection that stays up this long resets the backoff
+const STABLE_CONNECTION: Duration = Duration::from_secs(60);
+
+/// Delay before reconnecting, given how long the last connection stayed up
+fn reconnect_delay<R: Rng>(backoff: &mut Backoff, uptime: Duration, rng: &mut R) -> Duration {
+    if uptime >= STABLE_CONNECTION {
+        backoff.reset();
+    }
+    backoff.next_delay(rng)
+}
+
 /// Binance trade stream message
 #[derive(Debug, Deserialize)]
 struct BinanceTradeMessage {
@@ -21,6 +38,13 @@ struct BinanceTradeMessage {
     price: String,
 }
 
+/// Binance partial book depth message: `[price, quantity]` levels, best first
+#[derive(Debug, Deserialize)]
+struct BinanceDepthMessage {
+    bids: Vec<(String, String)>,
+    asks: Vec<(String, String)>,
+}
+
 /// Binance REST API response
 #[derive(Debug, Deserialize)]
 struct BinancePriceResponse {
@@ -31,60 +55,126 @@ struct BinancePriceResponse {
 pub struct BinanceService {
     price: Arc<RwLock<Option<Decimal>>>,
     is_ready: Arc<RwLock<bool>>,
+    depth: bool, // also stream the order book
+    book: Arc<RwLock<Option<ReferenceBook>>>,
+    max_backoff: Duration, // cap on the reconnect delay
+    stale_timeout: Duration, // silence after which a stream counts as dead
+    client: reqwest::Client,
 }
 
 impl BinanceService {
-    /// Create a new Binance service
-    pub fn new() -> Self {
+    /// Create a new Binance service, optionally streaming the BTC/USDT book too
+    pub fn new(depth: bool, max_backoff: Duration, stale_timeout: Duration, client: reqwest::Client) -> Self {
         Self {
             price: Arc::new(RwLock::new(None)),
             is_ready: Arc::new(RwLock::new(false)),
+            depth,
+            book: Arc::new(RwLock::new(None)),
+            max_backoff,
+            stale_timeout,
+            client,
         }
     }
 
-    /// Start the WebSocket connection and REST fallback
-    pub async fn start(&self) -> Result<()> {
-        let price_clone = self.price.clone();
-        let ready_clone = self.is_ready.clone();
+    /// Parse a depth message into the top of book
+    fn parse_depth_message(text: &str) -> Option<ReferenceBook> {
+        let depth: BinanceDepthMessage = serde_json::from_str(text).ok()?;
+        let level = |levels: &[(String, String)]| -> Option<(Decimal, Decimal)> {
+            let (price, size) = levels.first()?;
+            Some((Decimal::from_str(price).ok()?, Decimal::from_str(size).ok()?))
+        };
 
-        // Spawn WebSocket task
-        let ws_price = price_clone.clone();
-        let ws_ready = ready_clone.clone();
-        tokio::spawn(async move {
-            loop {
-                match Self::websocket_task(ws_price.clone(), ws_ready.clone()).await {
-                    Ok(_) => {
-                        info!("WebSocket connection closed, reconnecting in 5s...");
+        let (best_bid, bid_size) = level(&depth.bids)?;
+        let (best_ask, ask_size) = level(&depth.asks)?;
+        Some(ReferenceBook {
+            best_bid,
+            best_ask,
+            bid_size,
+            ask_size,
+        })
+    }
+
+    /// Cache the top of book from a depth message, ignoring anything unparseable
+    async fn on_depth_message(book: &RwLock<Option<ReferenceBook>>, text: &str) {
+        if let Some(top) = Self::parse_depth_message(text) {
+            *book.write().await = Some(top);
+        }
+    }
+
+    /// Feed text frames to `on_text` until the stream closes or goes silent
+    ///
+    /// Pings go out on the write half every third of `stale_timeout`, so even a
+    /// quiet market gets pongs back. No frame at all for `stale_timeout` means a
+    /// half-open connection, returned as an error so the caller reconnects.
+    async fn pump<R, W, F, Fut>(
+        stream: &str,
+        mut read: R,
+        mut write: W,
+        stale_timeout: Duration,
+        mut on_text: F,
+    ) -> Result<()>
+    where
+        R: Stream<Item = Result<Message, tungstenite::Error>> + Unpin,
+        W: Sink<Message> + Unpin,
+        F: FnMut(String) -> Fut,
+        Fut: std::future::Future<Output = ()>,
+    {
+        let mut ping = interval(stale_timeout / 3);
+        ping.tick().await; // the first tick is immediate
+        let mut last_seen = Instant::now();
+
+        loop {
+            tokio::select! {
+                msg = read.next() => {
+                    last_seen = Instant::now();
+                    match msg {
+                        Some(Ok(Message::Text(text))) => on_text(text).await,
+                        Some(Ok(Message::Close(_))) => {
+                            warn!("{} closed by server", stream);
+                            return Ok(());
+                        }
+                        Some(Err(e)) => {
+                            error!("{} error: {}", stream, e);
+                            return Ok(());
+                        }
+                        None => return Ok(()),
+                        _ => {}
                     }
-                    Err(e) => {
-                        error!("WebSocket error: {}. Reconnecting in 5s...", e);
+                }
+                _ = ping.tick() => {
+                    if write.send(Message::Ping(Vec::new())).await.is_err() {
+                        warn!("{} ping failed", stream);
+                        return Ok(());
                     }
                 }
-                tokio::time::sleep(Duration::from_secs(5)).await;
-            }
-        });
-
-        // Spawn REST fallback task
-        let rest_price = price_clone.clone();
-        let rest_ready = ready_clone.clone();
-        tokio::spawn(async move {
-            let mut interval = interval(Duration::from_secs(REST_FALLBACK_INTERVAL_SECS));
-            loop {
-                interval.tick().await;
-                if let Err(e) = Self::rest_fallback_task(&rest_price, &rest_ready).await {
-                    warn!("REST fallback failed: {}", e);
+                _ = tokio::time::sleep_until(last_seen + stale_timeout) => {
+                    anyhow::bail!("{} silent for {:?}, assuming the connection is dead", stream, stale_timeout);
                 }
             }
-        });
+        }
+    }
 
-        info!("🌐 Binance service started (WebSocket + REST fallback)");
-        Ok(())
+    /// Depth WebSocket task - keeps the cached top of book current
+    async fn depth_task(book: Arc<RwLock<Option<ReferenceBook>>>, stale_timeout: Duration) -> Result<()> {
+        info!("🔌 Connecting to Binance depth stream: {}", BINANCE_DEPTH_WS_URL);
+
+        let (ws_stream, _) = connect_async(BINANCE_DEPTH_WS_URL)
+            .await
+            .context("Failed to connect to Binance depth stream")?;
+        let (write, read) = ws_stream.split();
+
+        Self::pump("Depth stream", read, write, stale_timeout, |text| {
+            let book = book.clone();
+            async move { Self::on_depth_message(&book, &text).await }
+        })
+        .await
     }
 
     /// WebSocket task - connects and processes price updates
     async fn websocket_task(
         price: Arc<RwLock<Option<Decimal>>>,
         is_ready: Arc<RwLock<bool>>,
+        stale_timeout: Duration,
     ) -> Result<()> {
         info!("🔌 Connecting to Binance WebSocket: {}", BINANCE_WS_URL);
 
@@ -94,39 +184,29 @@ impl BinanceService {
 
         info!("✅ Connected to Binance WebSocket");
 
-        let (mut _write, mut read) = ws_stream.split();
+        let (write, read) = ws_stream.split();
 
-        while let Some(msg) = read.next().await {
-            match msg {
-                Ok(Message::Text(text)) => {
-                    if let Ok(trade) = serde_json::from_str::<BinanceTradeMessage>(&text) {
-                        if let Ok(btc_price) = Decimal::from_str(&trade.price) {
-                            *price.write().await = Some(btc_price);
-                            *is_ready.write().await = true;
-                        }
+        Self::pump("WebSocket", read, write, stale_timeout, |text| {
+            let price = price.clone();
+            let is_ready = is_ready.clone();
+            async move {
+                if let Ok(trade) = serde_json::from_str::<BinanceTradeMessage>(&text) {
+                    if let Ok(btc_price) = Decimal::from_str(&trade.price) {
+                        *price.write().await = Some(btc_price);
+                        *is_ready.write().await = true;
                     }
                 }
-                Ok(Message::Close(_)) => {
-                    warn!("WebSocket closed by server");
-                    break;
-                }
-                Err(e) => {
-                    error!("WebSocket error: {}", e);
-                    break;
-                }
-                _ => {}
             }
-        }
-
-        Ok(())
+        })
+        .await
     }
 
     /// REST fallback task - periodically fetches price via REST API
     async fn rest_fallback_task(
+        client: &reqwest::Client,
         price: &Arc<RwLock<Option<Decimal>>>,
         is_ready: &Arc<RwLock<bool>>,
     ) -> Result<()> {
-        let client = reqwest::Client::new();
         let response: BinancePriceResponse = client
             .get(BINANCE_REST_URL)
             .send()
@@ -145,31 +225,113 @@ impl BinanceService {
         Ok(())
     }
 
+    /// Wait until the service is ready (has received first price)
+    pub async fn wait_until_ready(&self) {
+        while !self.is_ready().await {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    }
+}
+
+#[async_trait]
+impl PriceSource for BinanceService {
+    fn name(&self) -> &'static str {
+        "binance"
+    }
+
+    /// Start the WebSocket connection and REST fallback
+    async fn start(&self) -> Result<()> {
+        let price_clone = self.price.clone();
+        let ready_clone = self.is_ready.clone();
+
+        // Spawn WebSocket task
+        let ws_price = price_clone.clone();
+        let ws_ready = ready_clone.clone();
+        let mut ws_backoff = Backoff::new(self.max_backoff);
+        let stale_timeout = self.stale_timeout;
+        tokio::spawn(async move {
+            let mut rng = rand::rngs::StdRng::from_entropy();
+            loop {
+                let connected_at = Instant::now();
+                let result = Self::websocket_task(ws_price.clone(), ws_ready.clone(), stale_timeout).await;
+                let delay = reconnect_delay(&mut ws_backoff, connected_at.elapsed(), &mut rng);
+                match result {
+                    Ok(_) => {
+                        info!("WebSocket connection closed, reconnecting in {:.1}s...", delay.as_secs_f64());
+                    }
+                    Err(e) => {
+                        error!("WebSocket error: {}. Reconnecting in {:.1}s...", e, delay.as_secs_f64());
+                    }
+                }
+                tokio::time::sleep(delay).await;
+            }
+        });
+
+        // Spawn depth stream task
+        if self.depth {
+            let book = self.book.clone();
+            let mut depth_backoff = Backoff::new(self.max_backoff);
+            tokio::spawn(async move {
+                let mut rng = rand::rngs::StdRng::from_entropy();
+                loop {
+                    let connected_at = Instant::now();
+                    let result = Self::depth_task(book.clone(), stale_timeout).await;
+                    let delay = reconnect_delay(&mut depth_backoff, connected_at.elapsed(), &mut rng);
+                    if let Err(e) = result {
+                        error!("Depth stream error: {}. Reconnecting in {:.1}s...", e, delay.as_secs_f64());
+                    }
+                    *book.write().await = None; // don't serve a stale book
+                    tokio::time::sleep(delay).await;
+                }
+            });
+        }
+
+        // Spawn REST fallback task
+        let rest_price = price_clone.clone();
+        let rest_ready = ready_clone.clone();
+        let rest_client = self.client.clone();
+        tokio::spawn(async move {
+            let mut interval = interval(Duration::from_secs(REST_FALLBACK_INTERVAL_SECS));
+            loop {
+                interval.tick().await;
+                if let Err(e) = Self::rest_fallback_task(&rest_client, &rest_price, &rest_ready).await {
+                    warn!("REST fallback failed: {}", e);
+                }
+            }
+        });
+
+        info!("🌐 Binance service started (WebSocket + REST fallback)");
+        Ok(())
+    }
+
     /// Get the current BTC spot price
-    pub async fn get_price(&self) -> Option<Decimal> {
+    async fn get_price(&self) -> Option<Decimal> {
         *self.price.read().await
     }
 
     /// Check if the service has received at least one price update
-    pub async fn is_ready(&self) -> bool {
+    async fn is_ready(&self) -> bool {
         *self.is_ready.read().await
     }
 
-    /// Wait until the service is ready (has received first price)
-    pub async fn wait_until_ready(&self) {
-        while !self.is_ready().await {
-            tokio::time::sleep(Duration::from_millis(100)).await;
-        }
+    /// Binance streams BTC/USDT regardless of market
+    async fn set_market_slug(&self, _slug: String) {}
+
+    /// Latest BTC/USDT top of book, when the depth stream is enabled
+    async fn reference_book(&self) -> Option<ReferenceBook> {
+        *self.book.read().await
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::backoff::BASE_DELAY;
 
     #[tokio::test]
+    #[ignore] // Requires network access to Binance
     async fn test_binance_service() {
-        let service = BinanceService::new();
+        let service = BinanceService::new(false, Duration::from_secs(60), Duration::from_secs(30), reqwest::Client::new());
         service.start().await.unwrap();
 
         // Wait for first price
@@ -180,4 +342,81 @@ mod tests {
         assert!(price.is_some());
         println!("BTC Price: ${}", price.unwrap());
     }
+
+    #[tokio::test]
+    async fn test_depth_message_updates_book() {
+        let service = BinanceService::new(true, Duration::from_secs(60), Duration::from_secs(30), reqwest::Client::new());
+        assert_eq!(service.reference_book().await, None);
+
+        let message = r#"{"lastUpdateId":160,"bids":[["98500.10","2.5"],["98500.00","1.0"]],"asks":[["98500.20","0.5"],["98500.30","4.0"]]}"#;
+        BinanceService::on_depth_message(&service.book, message).await;
+
+        let book = service.reference_book().await.unwrap();
+        assert_eq!(book.best_bid, Decimal::from_str("98500.10").unwrap());
+        assert_eq!(book.best_ask, Decimal::from_str("98500.20").unwrap());
+        assert_eq!(book.bid_size, Decimal::from_str("2.5").unwrap());
+        assert_eq!(book.ask_size, Decimal::from_str("0.5").unwrap());
+        assert_eq!(book.mid(), Decimal::from_str("98500.15").unwrap());
+
+        // An empty side or a non-depth message leaves the cached book alone
+        BinanceService::on_depth_message(&service.book, r#"{"lastUpdateId":161,"bids":[],"asks":[["1","1"]]}"#).await;
+        BinanceService::on_depth_message(&service.book, r#"{"e":"trade","p":"98500.00"}"#).await;
+        assert_eq!(service.reference_book().await, Some(book));
+    }
+
+    #[test]
+    fn test_reconnect_backoff_resets_after_stable_connection() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let mut backoff = Backoff::new(Duration::from_secs(10));
+        let failed = Duration::from_millis(200);
+
+        // Short-lived connections keep growing the delay
+        for _ in 0..3 {
+            reconnect_delay(&mut backoff, failed, &mut rng);
+        }
+        let delay = reconnect_delay(&mut backoff, failed, &mut rng);
+        assert!(delay >= Duration::from_secs(4));
+
+        // A connection that held up starts over from the base delay
+        let delay = reconnect_delay(&mut backoff, STABLE_CONNECTION, &mut rng);
+        assert!(delay <= BASE_DELAY);
+        let delay = reconnect_delay(&mut backoff, failed, &mut rng);
+        assert!(delay >= BASE_DELAY && delay <= BASE_DELAY * 2);
+    }
+
+    #[tokio::test]
+    async fn test_silent_stream_forces_reconnect() {
+        let pings = Arc::new(std::sync::Mutex::new(0));
+        let sink = |pings: Arc<std::sync::Mutex<usize>>| {
+            Box::pin(futures_util::sink::unfold((), move |(), msg: Message| {
+                if let Message::Ping(_) = msg {
+                    *pings.lock().unwrap() += 1;
+                }
+                async { Ok::<_, tungstenite::Error>(()) }
+            }))
+        };
+        let timeout = Duration::from_millis(150);
+
+        // Two trades, then nothing: the watchdog gives up after the timeout, having pinged
+        let trades = vec![Ok(Message::Text("a".to_string())), Ok(Message::Text("b".to_string()))];
+        let silent = futures_util::stream::iter(trades).chain(futures_util::stream::pending());
+        let mut received = Vec::new();
+        let started = Instant::now();
+        let result = BinanceService::pump("WebSocket", silent, sink(pings.clone()), timeout, |text| {
+            received.push(text);
+            async {}
+        })
+        .await;
+        assert!(result.is_err());
+        assert!(started.elapsed() >= timeout);
+        assert_eq!(received, ["a", "b"]);
+        assert!(*pings.lock().unwrap() >= 2);
+
+        // A stream the server closes ends cleanly, without waiting out the timeout
+        let closed = futures_util::stream::iter(vec![Ok(Message::Close(None))]);
+        let started = Instant::now();
+        let result = BinanceService::pump("WebSocket", closed, sink(pings), timeout, |_| async {}).await;
+        assert!(result.is_ok());
+        assert!(started.elapsed() < timeout);
+    }
 }
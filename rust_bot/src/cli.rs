@@ -0,0 +1,111 @@
+/// Command-line flags, layered over the file and environment configuration
+use clap::Parser;
+use std::collections::HashMap;
+
+/// Polymarket vulture bot
+///
+/// Flags override the config file and environment; with none, those alone apply.
+#[derive(Debug, Default, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    /// Config file to load (default: bot.toml, if present)
+    #[arg(long, value_name = "PATH")]
+    pub config: Option<String>,
+
+    /// Print every config key with its default as a .env template, then exit
+    #[arg(long)]
+    pub dump_config_template: bool,
+
+    /// Paper trade (PAPER_TRADE=true)
+    #[arg(long, conflicts_with = "live")]
+    pub paper: bool,
+
+    /// Trade live (PAPER_TRADE=false)
+    #[arg(long)]
+    pub live: bool,
+
+    /// Maximum USD per trade (MAX_CAPITAL_PER_TRADE)
+    #[arg(long, value_name = "USD")]
+    pub max_capital: Option<String>,
+
+    /// Milliseconds between ticks (TICK_INTERVAL)
+    #[arg(long, value_name = "MS")]
+    pub tick_interval: Option<u64>,
+
+    /// Trading strategy (STRATEGY)
+    #[arg(long)]
+    pub strategy: Option<String>,
+
+    /// BTC price feed (PRICE_SOURCE)
+    #[arg(long)]
+    pub price_source: Option<String>,
+
+    /// Any other config key, e.g. --set SCALP_PROFIT=0.02 (repeatable)
+    #[arg(long = "set", value_name = "KEY=VALUE", value_parser = parse_key_value)]
+    pub set: Vec<(String, String)>,
+}
+
+impl Cli {
+    /// The config keys these flags override, keyed by env var name
+    pub fn overrides(&self) -> HashMap<String, String> {
+        let mut overrides: HashMap<String, String> = self.set.iter().cloned().collect();
+        if self.paper || self.live {
+            overrides.insert("PAPER_TRADE".to_string(), self.paper.to_string());
+        }
+        let flags = [
+            ("MAX_CAPITAL_PER_TRADE", self.max_capital.clone()),
+            ("TICK_INTERVAL", self.tick_interval.map(|ms| ms.to_string())),
+            ("STRATEGY", self.strategy.clone()),
+            ("PRICE_SOURCE", self.price_source.clone()),
+        ];
+        for (key, value) in flags {
+            if let Some(value) = value {
+                overrides.insert(key.to_string(), value);
+            }
+        }
+        overrides
+    }
+}
+
+/// Parse `KEY=VALUE`, uppercasing the key to its env var name
+fn parse_key_value(arg: &str) -> Result<(String, String), String> {
+    let (key, value) = arg
+        .split_once('=')
+        .ok_or_else(|| format!("expected KEY=VALUE, got '{}'", arg))?;
+    Ok((key.trim().to_uppercase(), value.trim().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::BotConfig;
+    use rust_decimal::Decimal;
+
+    #[test]
+    fn test_flags_override_env() {
+        let cli = Cli::parse_from([
+            "bot",
+            "--paper",
+            "--max-capital",
+            "50",
+            "--set",
+            "min_order_size=7",
+        ]);
+        assert_eq!(cli.overrides()["PAPER_TRADE"], "true");
+        assert_eq!(cli.overrides()["MIN_ORDER_SIZE"], "7");
+
+        let env = [("MAX_CAPITAL_PER_TRADE", "30")];
+        let from_env = BotConfig::load_in_memory("", &env, HashMap::new()).unwrap();
+        assert_eq!(from_env.max_capital_per_trade, Decimal::from(30));
+
+        let config = BotConfig::load_in_memory("", &env, cli.overrides()).unwrap();
+        assert_eq!(config.max_capital_per_trade, Decimal::from(50));
+        assert_eq!(config.min_order_size, Decimal::from(7));
+        assert!(config.paper_trade);
+
+        // Misspelled keys are rejected rather than ignored
+        let cli = Cli::parse_from(["bot", "--set", "MAX_CAPITAL=50"]);
+        assert!(BotConfig::load_in_memory("", &[], cli.overrides()).is_err());
+        assert!(Cli::try_parse_from(["bot", "--paper", "--live"]).is_err());
+    }
+}
@@ -1,94 +1,392 @@
-/// Configuration management with environment variable loading
+/// Configuration management with environment variable and TOML file loading
 use anyhow::{Context, Result};
+use chrono_tz::Tz;
 use rust_decimal::Decimal;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::env;
+use std::path::Path;
 use std::str::FromStr;
 
+use crate::models::{
+    ExecutionMode, ForceDirection, LogFormat, MarketOverlap, ReconcileMode, SelfTradePrevention, SizingMode,
+    Strategy, TakeProfitTrigger, TpSlMode, VolSource,
+};
+use crate::price_source::PriceSourceKind;
+
 /// Main bot configuration
 #[derive(Debug, Clone)]
 pub struct BotConfig {
     // Master switch
     pub paper_trade: bool,
+    pub paper_starting_cash: Decimal, // simulated bankroll in paper mode
 
     // Authentication (live mode only)
     pub signer_private_key: String,
     pub proxy_address: String,
     pub polygon_rpc_url: String,
+    pub clob_base_url: String, // CLOB REST endpoint, e.g. a local mock server in tests
+    pub clob_api_key: String,  // L2 credentials for order posting and the user channel (empty = no live fill feed)
+    pub clob_api_secret: String,
+    pub clob_api_passphrase: String,
+
+    // Price feed
+    pub price_source: PriceSourceKind,
+    pub scrape_timeout_ms: u64, // PRICE_SOURCE=scraper: bound on one scrape attempt
+    pub chrome_path: String,    // PRICE_SOURCE=scraper: Chrome binary (empty = auto-detect)
+    pub chrome_args: String,    // PRICE_SOURCE=scraper: extra whitespace-separated Chrome flags
+    pub binance_depth: bool, // stream the Binance BTC/USDT book and warn when spot strays from it
+    pub binance_max_backoff: u64, // seconds, cap on the Binance WebSocket reconnect backoff
+    pub binance_stale_timeout: u64, // seconds without a frame before a Binance stream is reconnected
+    pub spot_blend_binance_weight: Decimal, // weight of Binance blended into the PRICE_SOURCE spot (0 = off)
+    pub spot_ema_alpha: Decimal, // EMA weight of each new spot sample (1 = raw price)
+    pub http_price_max_backoff: u64, // seconds, cap on the CoinGecko 429 backoff
+    pub http_timeout_ms: u64,        // per-request timeout of the shared HTTP client
+    pub sim_seed: u64,               // PRICE_SOURCE=simulated: RNG seed of the path
+    pub sim_start_price: Decimal,    // PRICE_SOURCE=simulated: starting BTC price
+    pub sim_drift: Decimal,          // PRICE_SOURCE=simulated: annualized drift
+    pub sim_vol: Decimal,            // PRICE_SOURCE=simulated: annualized volatility
 
     // Market discovery
     pub auto_discover_markets: bool,
     pub market_rotation_threshold: i64, // seconds
+    pub recent_market_ttl: i64,         // seconds a left market is excluded from discovery (0 = disabled)
+    pub market_overlap: MarketOverlap,  // tie-break when several windows are active
+    pub discovery_concurrency: usize,   // Gamma lookups in flight at once during discovery
+    pub discovery_windows: i64,         // windows searched either side of the current one
+    pub strike_max_deviation_pct: Decimal, // warn when a fetched strike is this many percent off spot (0 = disabled)
+    pub max_markets_per_session: u64,   // end the session after this many markets (0 = unlimited)
+    pub gamma_api_url: String,          // market metadata endpoint used by discovery
+    pub crypto_price_api_url: String,   // window open price (strike) endpoint
 
     // Strategy parameters (populated by market discovery)
     pub token_id_up: String,
     pub token_id_down: String,
     pub strike_price: Decimal,
+    pub force_direction: ForceDirection,
 
     // Capital management
     pub max_capital_per_trade: Decimal,
+    pub sub_accounts: Vec<(String, Decimal)>, // (strategy name, allocated capital)
+    pub per_market_budget: Decimal,           // fresh capital per market, reset at rotation (0 = disabled)
+    pub min_cash_ratio: Decimal,              // cash kept per dollar of open positions after an entry (0 = disabled)
+    pub max_drawdown_pct: Decimal,            // halt entries this many percent below peak session equity (0 = disabled)
+    pub max_depth_levels: usize,              // ask levels an entry may take (0 = unlimited)
+    pub max_depth_shares: Decimal,            // shares an entry may take from the book (0 = unlimited)
+    pub sizing_mode: SizingMode,              // FIXED capital per entry or CONFIDENCE-scaled by edge
+    pub confidence_full_edge: Decimal,        // CONFIDENCE: edge at which an entry gets full MAX_CAPITAL_PER_TRADE
+    pub confidence_min_capital: Decimal,      // CONFIDENCE: smallest capital an entry is sized with
 
     // Quant settings
     pub panic_discount: Decimal,
+    pub discount_vol_coefficient: Decimal, // extra discount per unit of annualized realized vol (0 = fixed discount)
+    pub max_panic_discount: Decimal,       // cap on the vol-adjusted discount
+    pub discount_early_scale: Decimal,     // discount multiplier with the whole window left
+    pub discount_late_scale: Decimal,      // discount multiplier at expiry
+    pub min_edge: Decimal, // minimum fair value - ask to enter
+    pub min_strike_distance: Decimal, // dollars spot must be from the strike to enter (0 = disabled)
+    pub min_entry_price: Decimal, // never buy a token whose ask is below this (0 = no floor)
+    pub max_entry_price: Decimal, // never buy a token whose ask is above this (1 = no cap)
+    pub slippage_adjusted_edge: bool, // judge entries against the average fill for the order size, not the ask
     pub scalp_profit: Decimal,
+    pub take_profit_trigger: TakeProfitTrigger, // price compared to the take profit target
     pub stop_loss_threshold: Decimal,
+    pub tp_sl_mode: TpSlMode,              // RELATIVE offsets from entry or ABSOLUTE price levels
+    pub take_profit_price: Decimal,        // take profit level in absolute mode
+    pub stop_loss_price: Decimal,          // stop loss level in absolute mode
+    pub trailing_stop_distance: Decimal,   // trail the peak bid by this much instead of a fixed take profit (0 = disabled)
+    pub trailing_stop_activation: Decimal, // favorable move above entry before the trail arms
+    pub fair_value_exit_band: Decimal,     // exit once the held token's fair value reaches this (0 = disabled)
+    pub scale_in_step: Decimal,            // add to a position each time the ask falls this far below the average entry
+    pub max_scale_ins: u64,                // adds allowed per position (0 = disabled)
     pub max_spread: Decimal,
+    pub min_book_update_rate: Decimal, // top-of-book changes per second, 0 = disabled
+    pub book_rate_window: i64,         // seconds
+    pub vol_source: VolSource,
+    pub vol_window: usize, // spot samples used for realized vol
+
+    // Exit rules
+    pub force_exit_before_expiry_seconds: i64, // 0 = disabled
+    pub max_hold_ticks: u64,                   // ticks a position may sit without hitting TP/SL, 0 = disabled
+
+    // Diagnostics
+    pub agreement_window: usize,       // ticks, 0 = disabled
+    pub agreement_threshold: Decimal,  // mean |fair - mid| considered "too close"
+    pub debug_api: bool,               // log raw Gamma / crypto-price response bodies
+    pub log_format: LogFormat,
+    pub log_level: String,             // trace, debug, info, warn or error
+    pub quiet: bool,                   // demote the routine per-tick spot/book lines to debug
+    pub log_incremental: bool,         // append each tick to session_*.jsonl as it is logged
+    pub display_timezone: Tz,          // IANA zone for human-readable log timestamps (stored times stay UTC)
+    pub db_path: String,               // SQLite database for ticks, trades and summaries (empty = JSON files)
+    pub stage_latency: bool,           // per-phase tick latency histograms in the session summary
+    pub session_note: String,          // journal note saved in the session summary
+    pub annotation_target: String,     // Grafana annotations: file path or API URL, empty = disabled
+    pub grafana_api_key: String,
+    pub discord_webhook_url: String,   // Discord notifications for fills, exits, rotations, halts and shutdown (empty = disabled)
+    pub notify_stdout: bool,           // print each notification event as a line on stdout
 
     // Execution settings
+    pub strategy: Strategy,
+    pub quote_offset: Decimal,   // market making: distance of each quote from fair value
+    pub inventory_skew: Decimal, // market making: shift of both quotes at full inventory (0 = no skew)
+    pub execution_mode: ExecutionMode,
     pub snipe_cushion: Decimal,
     pub dump_cushion: Decimal,
     pub snipe_wait_time: u64, // milliseconds
+    pub verify_live_fills: bool,
+    pub fill_verify_attempts: u64,
+    pub fill_verify_interval: u64, // milliseconds between balance checks
+    pub reconcile_interval_ticks: u64, // ticks between live position checks against the wallet (0 = disabled)
+    pub reconcile_mode: ReconcileMode, // log a drifted share count, or also correct it
+    pub max_open_orders: u64,      // resting orders allowed at once (0 = unlimited)
+    pub prevent_self_trade: SelfTradePrevention, // live orders that would cross our own resting orders
+    pub order_ttl_seconds: u64,    // live limit orders expire on the CLOB after this long (0 = never)
+    pub paper_fill_latency_ticks: u64, // ticks before a paper limit order can fill (0 = same tick)
+    pub validate_paper_fills: bool,    // check each paper fill's price and size against the live book depth
+    pub order_dedup_window_ms: u64,    // identical submissions refused within this window (0 = disabled)
+    pub price_tick_size: Decimal,      // CLOB price grid orders are snapped to (0 = no rounding)
+    pub size_step: Decimal,            // order sizes are rounded down to a multiple of this
+    pub min_order_size: Decimal,       // entries smaller than this are skipped (0 = no minimum)
 
     // Timing
     pub market_expiry_timestamp: i64, // Unix milliseconds
     pub tick_interval: u64,           // milliseconds
+    pub tick_jitter_ms: u64,          // ± randomization of each tick's sleep, 0 = fixed cadence
+    pub warmup_ticks: u64,            // ticks with a spot price observed after startup before any order is placed (0 = none)
+    pub http_rate_limit: u64,         // Polymarket requests per second, 0 = unlimited
+
+    // Service
+    pub health_port: u64,         // /health endpoint port (0 = disabled)
+    pub health_max_tick_age: u64, // seconds since the last tick before /health reports 503
+    pub operator_token: String,   // bearer token the operator control routes require (empty = those routes disabled)
+    pub dead_man_interval: u64,   // seconds allowed between operator check-ins before flatten-and-halt (0 = disabled)
+    pub dead_man_file: String,    // touching this file counts as a check-in (empty = POST /checkin only)
 }
 
 impl BotConfig {
-    /// Load configuration from environment variables
-    pub fn from_env() -> Result<Self> {
-        dotenv::dotenv().ok(); // Load .env file if present
+    /// Load configuration in layers: defaults, then the config file, then the
+    /// environment, then `overrides` (command-line flags, keyed by env var name)
+    ///
+    /// The file is `path`, or `bot.toml` if present.
+    pub fn load(path: Option<&str>, overrides: HashMap<String, String>) -> Result<Self> {
+        let path = path.or_else(|| Path::new(DEFAULT_CONFIG_FILE).exists().then_some(DEFAULT_CONFIG_FILE));
+        let mut source = match path {
+            Some(path) => ConfigSource::read_file(path)?,
+            None => ConfigSource::default(),
+        };
+        source.overrides = overrides;
+        Self::load_source(&source, path.unwrap_or("command line"))
+    }
+
+    /// Load from in-memory file contents and environment, leaving the process's own untouched
+    #[cfg(test)]
+    pub(crate) fn load_in_memory(
+        toml: &str,
+        env: &[(&str, &str)],
+        overrides: HashMap<String, String>,
+    ) -> Result<Self> {
+        let mut source = ConfigSource::from_toml(toml)?;
+        source.env = Some(env.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect());
+        source.overrides = overrides;
+        Self::load_source(&source, "config")
+    }
+
+    fn load_source(source: &ConfigSource, origin: &str) -> Result<Self> {
+        let config = Self::from_source(source)?;
+        source.check_unused().with_context(|| format!("Invalid {}", origin))?;
+        Ok(config)
+    }
+
+    /// Render a `.env` template listing every key with its default and description
+    ///
+    /// Built by loading the defaults through the same reads as `load`, so
+    /// a new field shows up here as soon as it is loaded.
+    pub fn template() -> Result<String> {
+        let source = ConfigSource {
+            defaults_only: true,
+            ..ConfigSource::default()
+        };
+        Self::from_source(&source)?;
+
+        let descriptions = key_descriptions();
+        let mut template = String::from("# Generated by --dump-config-template: every key with its default\n");
+        for (key, default) in source.defaults.borrow().iter() {
+            match descriptions.get(key.as_str()) {
+                Some(description) => template.push_str(&format!("{}={}  # {}\n", key, default, description)),
+                None => template.push_str(&format!("{}={}\n", key, default)),
+            }
+        }
+        Ok(template)
+    }
+
+    fn from_source(source: &ConfigSource) -> Result<Self> {
+        if source.env.is_none() {
+            dotenv::dotenv().ok(); // Load .env file if present
+        }
+
+        let defaults = Self::default();
 
         let config = Self {
             // Master switch
-            paper_trade: get_env_bool("PAPER_TRADE", true),
+            paper_trade: source.get_bool("PAPER_TRADE", defaults.paper_trade),
+            paper_starting_cash: source.get_decimal("PAPER_STARTING_CASH", defaults.paper_starting_cash),
 
             // Authentication
-            signer_private_key: env::var("SIGNER_PRIVATE_KEY")
-                .unwrap_or_else(|_| "0x0000000000000000000000000000000000000000000000000000000000000000".to_string()),
-            proxy_address: env::var("PROXY_ADDRESS")
-                .unwrap_or_else(|_| "0x0000000000000000000000000000000000000000".to_string()),
-            polygon_rpc_url: env::var("POLYGON_RPC_URL")
-                .unwrap_or_else(|_| "https://polygon-rpc.com".to_string()),
+            signer_private_key: source.get_string("SIGNER_PRIVATE_KEY", defaults.signer_private_key),
+            proxy_address: source.get_string("PROXY_ADDRESS", defaults.proxy_address),
+            polygon_rpc_url: source.get_string("POLYGON_RPC_URL", defaults.polygon_rpc_url),
+            clob_base_url: source.get_string("CLOB_BASE_URL", defaults.clob_base_url),
+            clob_api_key: source.get_string("CLOB_API_KEY", defaults.clob_api_key),
+            clob_api_secret: source.get_string("CLOB_API_SECRET", defaults.clob_api_secret),
+            clob_api_passphrase: source.get_string("CLOB_API_PASSPHRASE", defaults.clob_api_passphrase),
+
+            // Price feed
+            price_source: source.get_parsed("PRICE_SOURCE", defaults.price_source)?,
+            scrape_timeout_ms: source.get_u64("SCRAPE_TIMEOUT_MS", defaults.scrape_timeout_ms),
+            chrome_path: source.get_string("CHROME_PATH", defaults.chrome_path),
+            chrome_args: source.get_string("CHROME_ARGS", defaults.chrome_args),
+            binance_depth: source.get_bool("BINANCE_DEPTH", defaults.binance_depth),
+            binance_max_backoff: source.get_u64("BINANCE_MAX_BACKOFF", defaults.binance_max_backoff),
+            binance_stale_timeout: source.get_u64("BINANCE_STALE_TIMEOUT", defaults.binance_stale_timeout),
+            spot_blend_binance_weight: source
+                .get_decimal("SPOT_BLEND_BINANCE_WEIGHT", defaults.spot_blend_binance_weight),
+            spot_ema_alpha: source.get_decimal("SPOT_EMA_ALPHA", defaults.spot_ema_alpha),
+            http_price_max_backoff: source.get_u64("HTTP_PRICE_MAX_BACKOFF", defaults.http_price_max_backoff),
+            http_timeout_ms: source.get_u64("HTTP_TIMEOUT_MS", defaults.http_timeout_ms),
+            sim_seed: source.get_u64("SIM_SEED", defaults.sim_seed),
+            sim_start_price: source.get_decimal("SIM_START_PRICE", defaults.sim_start_price),
+            sim_drift: source.get_decimal("SIM_DRIFT", defaults.sim_drift),
+            sim_vol: source.get_decimal("SIM_VOL", defaults.sim_vol),
 
             // Market discovery
-            auto_discover_markets: get_env_bool("AUTO_DISCOVER_MARKETS", true),
-            market_rotation_threshold: get_env_i64("MARKET_ROTATION_THRESHOLD", 30),
+            auto_discover_markets: source.get_bool("AUTO_DISCOVER_MARKETS", defaults.auto_discover_markets),
+            market_rotation_threshold: source.get_i64("MARKET_ROTATION_THRESHOLD", defaults.market_rotation_threshold),
+            recent_market_ttl: source.get_i64("RECENT_MARKET_TTL", defaults.recent_market_ttl),
+            market_overlap: source.get_parsed("MARKET_OVERLAP", defaults.market_overlap)?,
+            discovery_concurrency: source.get_u64("DISCOVERY_CONCURRENCY", defaults.discovery_concurrency as u64) as usize,
+            discovery_windows: source.get_i64("DISCOVERY_WINDOWS", defaults.discovery_windows),
+            strike_max_deviation_pct: source
+                .get_decimal("STRIKE_MAX_DEVIATION_PCT", defaults.strike_max_deviation_pct),
+            max_markets_per_session: source.get_u64("MAX_MARKETS_PER_SESSION", defaults.max_markets_per_session),
+            gamma_api_url: source.get_string("GAMMA_API_URL", defaults.gamma_api_url),
+            crypto_price_api_url: source.get_string("CRYPTO_PRICE_API_URL", defaults.crypto_price_api_url),
 
             // Strategy parameters
-            token_id_up: env::var("TOKEN_ID_UP").unwrap_or_default(),
-            token_id_down: env::var("TOKEN_ID_DOWN").unwrap_or_default(),
-            strike_price: get_env_decimal("STRIKE_PRICE", Decimal::ZERO),
+            token_id_up: source.get_string("TOKEN_ID_UP", defaults.token_id_up),
+            token_id_down: source.get_string("TOKEN_ID_DOWN", defaults.token_id_down),
+            strike_price: source.get_decimal("STRIKE_PRICE", defaults.strike_price),
+            force_direction: source.get_parsed("FORCE_DIRECTION", defaults.force_direction)?,
 
             // Capital management
-            max_capital_per_trade: get_env_decimal("MAX_CAPITAL_PER_TRADE", Decimal::from(20)),
+            max_capital_per_trade: source.get_decimal("MAX_CAPITAL_PER_TRADE", defaults.max_capital_per_trade),
+            sub_accounts: parse_allocations(&source.get_string("SUB_ACCOUNTS", format_allocations(&defaults.sub_accounts)))
+                .context("Invalid SUB_ACCOUNTS")?,
+            per_market_budget: source.get_decimal("PER_MARKET_BUDGET", defaults.per_market_budget),
+            min_cash_ratio: source.get_decimal("MIN_CASH_RATIO", defaults.min_cash_ratio),
+            max_drawdown_pct: source.get_decimal("MAX_DRAWDOWN_PCT", defaults.max_drawdown_pct),
+            max_depth_levels: source.get_u64("MAX_DEPTH_LEVELS", defaults.max_depth_levels as u64) as usize,
+            max_depth_shares: source.get_decimal("MAX_DEPTH_SHARES", defaults.max_depth_shares),
+            sizing_mode: source.get_parsed("SIZING_MODE", defaults.sizing_mode)?,
+            confidence_full_edge: source.get_decimal("CONFIDENCE_FULL_EDGE", defaults.confidence_full_edge),
+            confidence_min_capital: source.get_decimal("CONFIDENCE_MIN_CAPITAL", defaults.confidence_min_capital),
 
             // Quant settings
-            panic_discount: get_env_decimal("PANIC_DISCOUNT", Decimal::from_str("0.08").unwrap()),
-            scalp_profit: get_env_decimal("SCALP_PROFIT", Decimal::from_str("0.01").unwrap()),
-            stop_loss_threshold: get_env_decimal("STOP_LOSS_THRESHOLD", Decimal::from_str("0.10").unwrap()),
-            max_spread: get_env_decimal("MAX_SPREAD", Decimal::from_str("0.50").unwrap()),
+            panic_discount: source.get_decimal("PANIC_DISCOUNT", defaults.panic_discount),
+            discount_vol_coefficient: source.get_decimal("DISCOUNT_VOL_COEFFICIENT", defaults.discount_vol_coefficient),
+            max_panic_discount: source.get_decimal("MAX_PANIC_DISCOUNT", defaults.max_panic_discount),
+            discount_early_scale: source.get_decimal("DISCOUNT_EARLY_SCALE", defaults.discount_early_scale),
+            discount_late_scale: source.get_decimal("DISCOUNT_LATE_SCALE", defaults.discount_late_scale),
+            min_edge: source.get_decimal("MIN_EDGE", defaults.min_edge),
+            min_strike_distance: source.get_decimal("MIN_STRIKE_DISTANCE", defaults.min_strike_distance),
+            min_entry_price: source.get_decimal("MIN_ENTRY_PRICE", defaults.min_entry_price),
+            max_entry_price: source.get_decimal("MAX_ENTRY_PRICE", defaults.max_entry_price),
+            slippage_adjusted_edge: source.get_bool("SLIPPAGE_ADJUSTED_EDGE", defaults.slippage_adjusted_edge),
+            scalp_profit: source.get_decimal("SCALP_PROFIT", defaults.scalp_profit),
+            take_profit_trigger: source.get_parsed("TAKE_PROFIT_TRIGGER", defaults.take_profit_trigger)?,
+            stop_loss_threshold: source.get_decimal("STOP_LOSS_THRESHOLD", defaults.stop_loss_threshold),
+            tp_sl_mode: source.get_parsed("TP_SL_MODE", defaults.tp_sl_mode)?,
+            take_profit_price: source.get_decimal("TAKE_PROFIT_PRICE", defaults.take_profit_price),
+            stop_loss_price: source.get_decimal("STOP_LOSS_PRICE", defaults.stop_loss_price),
+            trailing_stop_distance: source.get_decimal("TRAILING_STOP_DISTANCE", defaults.trailing_stop_distance),
+            trailing_stop_activation: source.get_decimal("TRAILING_STOP_ACTIVATION", defaults.trailing_stop_activation),
+            fair_value_exit_band: source.get_decimal("FAIR_VALUE_EXIT_BAND", defaults.fair_value_exit_band),
+            scale_in_step: source.get_decimal("SCALE_IN_STEP", defaults.scale_in_step),
+            max_scale_ins: source.get_u64("MAX_SCALE_INS", defaults.max_scale_ins),
+            max_spread: source.get_decimal("MAX_SPREAD", defaults.max_spread),
+            min_book_update_rate: source.get_decimal("MIN_BOOK_UPDATE_RATE", defaults.min_book_update_rate),
+            book_rate_window: source.get_i64("BOOK_RATE_WINDOW", defaults.book_rate_window),
+            vol_source: source.get_parsed("VOL_SOURCE", defaults.vol_source)?,
+            vol_window: source.get_u64("VOL_WINDOW", defaults.vol_window as u64) as usize,
+
+            // Exit rules
+            force_exit_before_expiry_seconds: source.get_i64(
+                "FORCE_EXIT_BEFORE_EXPIRY_SECONDS",
+                defaults.force_exit_before_expiry_seconds,
+            ),
+            max_hold_ticks: source.get_u64("MAX_HOLD_TICKS", defaults.max_hold_ticks),
+
+            // Diagnostics
+            agreement_window: source.get_u64("AGREEMENT_WINDOW", defaults.agreement_window as u64) as usize,
+            agreement_threshold: source.get_decimal("AGREEMENT_THRESHOLD", defaults.agreement_threshold),
+            debug_api: source.get_bool("DEBUG_API", defaults.debug_api),
+            log_format: source.get_parsed("LOG_FORMAT", defaults.log_format)?,
+            log_level: source.get_string("LOG_LEVEL", defaults.log_level),
+            quiet: source.get_bool("QUIET", defaults.quiet),
+            log_incremental: source.get_bool("LOG_INCREMENTAL", defaults.log_incremental),
+            display_timezone: source
+                .get_string("DISPLAY_TIMEZONE", defaults.display_timezone.to_string())
+                .trim()
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid DISPLAY_TIMEZONE: {}", e))?,
+            db_path: source.get_string("DB_PATH", defaults.db_path),
+            stage_latency: source.get_bool("STAGE_LATENCY", defaults.stage_latency),
+            session_note: match source.get_string("SESSION_NOTE_FILE", String::new()) {
+                path if !path.trim().is_empty() => std::fs::read_to_string(path.trim())
+                    .with_context(|| format!("Failed to read SESSION_NOTE_FILE {}", path.trim()))?,
+                _ => source.get_string("SESSION_NOTE", defaults.session_note),
+            },
+            annotation_target: source.get_string("ANNOTATION_TARGET", defaults.annotation_target),
+            grafana_api_key: source.get_string("GRAFANA_API_KEY", defaults.grafana_api_key),
+            discord_webhook_url: source.get_string("DISCORD_WEBHOOK_URL", defaults.discord_webhook_url),
+            notify_stdout: source.get_bool("NOTIFY_STDOUT", defaults.notify_stdout),
 
             // Execution
-            snipe_cushion: get_env_decimal("SNIPE_CUSHION", Decimal::from_str("0.02").unwrap()),
-            dump_cushion: get_env_decimal("DUMP_CUSHION", Decimal::from_str("0.02").unwrap()),
-            snipe_wait_time: get_env_u64("SNIPE_WAIT_TIME", 2000),
+            strategy: source.get_parsed("STRATEGY", defaults.strategy)?,
+            quote_offset: source.get_decimal("QUOTE_OFFSET", defaults.quote_offset),
+            inventory_skew: source.get_decimal("INVENTORY_SKEW", defaults.inventory_skew),
+            execution_mode: source.get_parsed("EXECUTION_MODE", defaults.execution_mode)?,
+            snipe_cushion: source.get_decimal("SNIPE_CUSHION", defaults.snipe_cushion),
+            dump_cushion: source.get_decimal("DUMP_CUSHION", defaults.dump_cushion),
+            snipe_wait_time: source.get_u64("SNIPE_WAIT_TIME", defaults.snipe_wait_time),
+            verify_live_fills: source.get_bool("VERIFY_LIVE_FILLS", defaults.verify_live_fills),
+            fill_verify_attempts: source.get_u64("FILL_VERIFY_ATTEMPTS", defaults.fill_verify_attempts),
+            fill_verify_interval: source.get_u64("FILL_VERIFY_INTERVAL", defaults.fill_verify_interval),
+            reconcile_interval_ticks: source.get_u64("RECONCILE_INTERVAL_TICKS", defaults.reconcile_interval_ticks),
+            reconcile_mode: source.get_parsed("RECONCILE_MODE", defaults.reconcile_mode)?,
+            max_open_orders: source.get_u64("MAX_OPEN_ORDERS", defaults.max_open_orders),
+            prevent_self_trade: source.get_parsed("PREVENT_SELF_TRADE", defaults.prevent_self_trade)?,
+            order_ttl_seconds: source.get_u64("ORDER_TTL_SECONDS", defaults.order_ttl_seconds),
+            paper_fill_latency_ticks: source.get_u64("PAPER_FILL_LATENCY_TICKS", defaults.paper_fill_latency_ticks),
+            validate_paper_fills: source.get_bool("VALIDATE_PAPER_FILLS", defaults.validate_paper_fills),
+            order_dedup_window_ms: source.get_u64("ORDER_DEDUP_WINDOW_MS", defaults.order_dedup_window_ms),
+            price_tick_size: source.get_decimal("PRICE_TICK_SIZE", defaults.price_tick_size),
+            size_step: source.get_decimal("SIZE_STEP", defaults.size_step),
+            min_order_size: source.get_decimal("MIN_ORDER_SIZE", defaults.min_order_size),
 
             // Timing
-            market_expiry_timestamp: get_env_i64(
-                "MARKET_EXPIRY_TIMESTAMP",
-                chrono::Utc::now().timestamp_millis() + 15 * 60 * 1000,
-            ),
-            tick_interval: get_env_u64("TICK_INTERVAL", 500),
+            market_expiry_timestamp: source.get_i64("MARKET_EXPIRY_TIMESTAMP", defaults.market_expiry_timestamp),
+            tick_interval: source.get_u64("TICK_INTERVAL", defaults.tick_interval),
+            tick_jitter_ms: source.get_u64("TICK_JITTER_MS", defaults.tick_jitter_ms),
+            warmup_ticks: source.get_u64("WARMUP_TICKS", defaults.warmup_ticks),
+            http_rate_limit: source.get_u64("HTTP_RATE_LIMIT", defaults.http_rate_limit),
+
+            // Service
+            health_port: source.get_u64("HEALTH_PORT", defaults.health_port),
+            health_max_tick_age: source.get_u64("HEALTH_MAX_TICK_AGE", defaults.health_max_tick_age),
+            operator_token: source.get_string("OPERATOR_TOKEN", defaults.operator_token),
+            dead_man_interval: source.get_u64("DEAD_MAN_INTERVAL", defaults.dead_man_interval),
+            dead_man_file: source.get_string("DEAD_MAN_FILE", defaults.dead_man_file),
         };
 
         config.validate()?;
@@ -107,6 +405,16 @@ impl BotConfig {
             if self.proxy_address == "0x0000000000000000000000000000000000000000" {
                 errors.push("PROXY_ADDRESS is required for live trading");
             }
+            if !is_http_url(&self.clob_base_url) {
+                errors.push("CLOB_BASE_URL must be an http(s) URL");
+            }
+            if !self.clob_api_key.is_empty() && (self.clob_api_secret.is_empty() || self.clob_api_passphrase.is_empty()) {
+                errors.push("CLOB_API_SECRET and CLOB_API_PASSPHRASE are required with CLOB_API_KEY");
+            }
+            // Live books come from the CLOB client without a last trade, so LAST would quietly mean BID
+            if self.take_profit_trigger == TakeProfitTrigger::Last {
+                errors.push("TAKE_PROFIT_TRIGGER=LAST is paper-only; use BID or MID for live trading");
+            }
         }
 
         // Validate manual market mode requirements
@@ -123,21 +431,205 @@ impl BotConfig {
         }
 
         // Validate numeric ranges
+        if self.paper_trade && self.paper_starting_cash <= Decimal::ZERO {
+            errors.push("PAPER_STARTING_CASH must be positive in paper mode");
+        }
         if self.max_capital_per_trade <= Decimal::ZERO {
             errors.push("MAX_CAPITAL_PER_TRADE must be positive");
         }
+        if self.verify_live_fills && self.fill_verify_attempts == 0 {
+            errors.push("FILL_VERIFY_ATTEMPTS must be at least 1 when VERIFY_LIVE_FILLS is enabled");
+        }
+        if self.min_book_update_rate < Decimal::ZERO {
+            errors.push("MIN_BOOK_UPDATE_RATE must not be negative");
+        }
+        if self.min_book_update_rate > Decimal::ZERO && self.book_rate_window <= 0 {
+            errors.push("BOOK_RATE_WINDOW must be positive when MIN_BOOK_UPDATE_RATE is set");
+        }
+        if self.spot_ema_alpha <= Decimal::ZERO || self.spot_ema_alpha > Decimal::ONE {
+            errors.push("SPOT_EMA_ALPHA must be in (0, 1]");
+        }
+        if self.vol_source == VolSource::Realized && self.vol_window < 3 {
+            errors.push("VOL_WINDOW must be at least 3 when VOL_SOURCE is REALIZED");
+        }
+        if self.agreement_threshold < Decimal::ZERO {
+            errors.push("AGREEMENT_THRESHOLD must not be negative");
+        }
+        if self.per_market_budget < Decimal::ZERO {
+            errors.push("PER_MARKET_BUDGET must not be negative");
+        }
+        if self.min_cash_ratio < Decimal::ZERO {
+            errors.push("MIN_CASH_RATIO must not be negative");
+        }
+        if self.max_drawdown_pct < Decimal::ZERO || self.max_drawdown_pct > Decimal::ONE_HUNDRED {
+            errors.push("MAX_DRAWDOWN_PCT must be between 0 and 100");
+        }
+        if self.confidence_full_edge <= Decimal::ZERO || self.confidence_full_edge > Decimal::ONE {
+            errors.push("CONFIDENCE_FULL_EDGE must be greater than 0 and at most 1");
+        }
+        if self.confidence_min_capital < Decimal::ZERO || self.confidence_min_capital > self.max_capital_per_trade {
+            errors.push("CONFIDENCE_MIN_CAPITAL must be between 0 and MAX_CAPITAL_PER_TRADE");
+        }
+        if self.max_depth_shares < Decimal::ZERO {
+            errors.push("MAX_DEPTH_SHARES must not be negative");
+        }
+        if self.sub_accounts.iter().any(|(_, capital)| *capital <= Decimal::ZERO) {
+            errors.push("SUB_ACCOUNTS allocations must be positive");
+        }
         if self.panic_discount < Decimal::ZERO || self.panic_discount > Decimal::ONE {
             errors.push("PANIC_DISCOUNT must be between 0 and 1");
         }
+        if self.discount_vol_coefficient < Decimal::ZERO {
+            errors.push("DISCOUNT_VOL_COEFFICIENT must not be negative");
+        }
+        if self.max_panic_discount < self.panic_discount || self.max_panic_discount > Decimal::ONE {
+            errors.push("MAX_PANIC_DISCOUNT must be between PANIC_DISCOUNT and 1");
+        }
+        if self.discount_early_scale < Decimal::ZERO || self.discount_late_scale < Decimal::ZERO {
+            errors.push("DISCOUNT_EARLY_SCALE and DISCOUNT_LATE_SCALE must not be negative");
+        }
+        if self.min_edge < Decimal::ZERO || self.min_edge > Decimal::ONE {
+            errors.push("MIN_EDGE must be between 0 and 1");
+        }
+        if self.min_strike_distance < Decimal::ZERO {
+            errors.push("MIN_STRIKE_DISTANCE must not be negative");
+        }
+        if self.min_entry_price < Decimal::ZERO
+            || self.max_entry_price > Decimal::ONE
+            || self.min_entry_price >= self.max_entry_price
+        {
+            errors.push("MIN_ENTRY_PRICE and MAX_ENTRY_PRICE must satisfy 0 <= min < max <= 1");
+        }
         if self.scalp_profit < Decimal::ZERO || self.scalp_profit > Decimal::ONE {
             errors.push("SCALP_PROFIT must be between 0 and 1");
         }
         if self.stop_loss_threshold < Decimal::ZERO || self.stop_loss_threshold > Decimal::ONE {
             errors.push("STOP_LOSS_THRESHOLD must be between 0 and 1");
         }
+        if self.tp_sl_mode == TpSlMode::Absolute
+            && !(Decimal::ZERO < self.stop_loss_price
+                && self.stop_loss_price < self.take_profit_price
+                && self.take_profit_price < Decimal::ONE)
+        {
+            errors.push("TP_SL_MODE=absolute requires 0 < STOP_LOSS_PRICE < TAKE_PROFIT_PRICE < 1");
+        }
+        if self.trailing_stop_distance < Decimal::ZERO || self.trailing_stop_distance > Decimal::ONE {
+            errors.push("TRAILING_STOP_DISTANCE must be between 0 and 1");
+        }
+        if self.trailing_stop_activation < Decimal::ZERO || self.trailing_stop_activation > Decimal::ONE {
+            errors.push("TRAILING_STOP_ACTIVATION must be between 0 and 1");
+        }
+        if self.fair_value_exit_band < Decimal::ZERO || self.fair_value_exit_band > Decimal::ONE {
+            errors.push("FAIR_VALUE_EXIT_BAND must be between 0 and 1");
+        }
+        if self.max_scale_ins > 0 {
+            if self.scale_in_step <= Decimal::ZERO || self.scale_in_step >= Decimal::ONE {
+                errors.push("SCALE_IN_STEP must be between 0 and 1 (exclusive) when MAX_SCALE_INS is set");
+            } else if self.tp_sl_mode == TpSlMode::Relative && self.scale_in_step >= self.stop_loss_threshold {
+                errors.push("SCALE_IN_STEP must be less than STOP_LOSS_THRESHOLD or the stop fires first");
+            }
+        }
         if self.market_rotation_threshold < 10 || self.market_rotation_threshold > 300 {
             errors.push("MARKET_ROTATION_THRESHOLD must be between 10 and 300 seconds");
         }
+        if self.recent_market_ttl < 0 {
+            errors.push("RECENT_MARKET_TTL must not be negative");
+        }
+        if self.discovery_concurrency == 0 {
+            errors.push("DISCOVERY_CONCURRENCY must be at least 1");
+        }
+        if !(1..=96).contains(&self.discovery_windows) {
+            errors.push("DISCOVERY_WINDOWS must be between 1 and 96");
+        }
+        if self.strike_max_deviation_pct < Decimal::ZERO {
+            errors.push("STRIKE_MAX_DEVIATION_PCT cannot be negative");
+        }
+        if !is_http_url(&self.gamma_api_url) {
+            errors.push("GAMMA_API_URL must be an http(s) URL");
+        }
+        if !is_http_url(&self.crypto_price_api_url) {
+            errors.push("CRYPTO_PRICE_API_URL must be an http(s) URL");
+        }
+        if self.force_exit_before_expiry_seconds < 0 {
+            errors.push("FORCE_EXIT_BEFORE_EXPIRY_SECONDS must not be negative");
+        } else if self.force_exit_before_expiry_seconds > 0
+            && self.force_exit_before_expiry_seconds <= self.market_rotation_threshold
+        {
+            errors.push("FORCE_EXIT_BEFORE_EXPIRY_SECONDS must be greater than MARKET_ROTATION_THRESHOLD");
+        }
+        if self.http_price_max_backoff == 0 {
+            errors.push("HTTP_PRICE_MAX_BACKOFF must be at least 1 second");
+        }
+        if self.log_level.parse::<tracing::Level>().is_err() {
+            errors.push("LOG_LEVEL must be one of trace, debug, info, warn or error");
+        }
+        if !self.discord_webhook_url.is_empty() && !is_http_url(&self.discord_webhook_url) {
+            errors.push("DISCORD_WEBHOOK_URL must be an http(s) URL");
+        }
+        if self.scrape_timeout_ms == 0 {
+            errors.push("SCRAPE_TIMEOUT_MS must be greater than 0");
+        }
+        if self.http_timeout_ms == 0 {
+            errors.push("HTTP_TIMEOUT_MS must be greater than 0");
+        }
+        if self.binance_max_backoff == 0 {
+            errors.push("BINANCE_MAX_BACKOFF must be at least 1 second");
+        }
+        if self.binance_stale_timeout == 0 {
+            errors.push("BINANCE_STALE_TIMEOUT must be at least 1 second");
+        }
+        if self.spot_blend_binance_weight < Decimal::ZERO || self.spot_blend_binance_weight > Decimal::ONE {
+            errors.push("SPOT_BLEND_BINANCE_WEIGHT must be between 0 and 1");
+        }
+        if self.binance_depth && self.price_source != PriceSourceKind::Binance && !self.blends_binance() {
+            errors.push("BINANCE_DEPTH requires PRICE_SOURCE=binance or SPOT_BLEND_BINANCE_WEIGHT");
+        }
+        if self.sim_start_price <= Decimal::ZERO {
+            errors.push("SIM_START_PRICE must be greater than 0");
+        }
+        if self.sim_vol < Decimal::ZERO {
+            errors.push("SIM_VOL must not be negative");
+        }
+        if self.health_port > u16::MAX as u64 {
+            errors.push("HEALTH_PORT must be a valid TCP port");
+        }
+        if self.health_port > 0 && self.health_max_tick_age == 0 {
+            errors.push("HEALTH_MAX_TICK_AGE must be positive when HEALTH_PORT is set");
+        }
+        if self.dead_man_interval > 0
+            && (self.health_port == 0 || self.operator_token.is_empty())
+            && self.dead_man_file.is_empty()
+        {
+            errors.push("DEAD_MAN_INTERVAL needs HEALTH_PORT with OPERATOR_TOKEN, or DEAD_MAN_FILE, to receive check-ins");
+        }
+        if self.tick_jitter_ms > self.tick_interval {
+            errors.push("TICK_JITTER_MS must not exceed TICK_INTERVAL");
+        }
+        let max_cushion = Decimal::from_str("0.5").unwrap();
+        if self.quote_offset <= Decimal::ZERO || self.quote_offset >= max_cushion {
+            errors.push("QUOTE_OFFSET must be between 0 and 0.5");
+        }
+        if self.inventory_skew < Decimal::ZERO || self.inventory_skew >= max_cushion {
+            errors.push("INVENTORY_SKEW must be between 0 and 0.5");
+        }
+        if self.snipe_cushion < Decimal::ZERO || self.snipe_cushion > max_cushion {
+            errors.push("SNIPE_CUSHION must be between 0 and 0.5");
+        }
+        if self.dump_cushion < Decimal::ZERO || self.dump_cushion > max_cushion {
+            errors.push("DUMP_CUSHION must be between 0 and 0.5");
+        }
+        if self.price_tick_size < Decimal::ZERO || self.price_tick_size > Decimal::new(1, 1) {
+            errors.push("PRICE_TICK_SIZE must be between 0 and 0.1");
+        }
+        if self.size_step <= Decimal::ZERO {
+            errors.push("SIZE_STEP must be positive");
+        }
+        if self.min_order_size < Decimal::ZERO {
+            errors.push("MIN_ORDER_SIZE must not be negative");
+        }
+        if self.snipe_wait_time >= self.tick_interval.saturating_mul(MAX_SNIPE_WAIT_TICKS) {
+            errors.push("SNIPE_WAIT_TIME must be less than 10 x TICK_INTERVAL");
+        }
 
         if !errors.is_empty() {
             anyhow::bail!("Configuration validation failed:\n{}", errors.join("\n"));
@@ -160,6 +652,11 @@ impl BotConfig {
         self.market_expiry_timestamp = expiry_timestamp;
     }
 
+    /// Whether the spot is a blend of PRICE_SOURCE and Binance
+    pub fn blends_binance(&self) -> bool {
+        self.spot_blend_binance_weight > Decimal::ZERO && self.price_source != PriceSourceKind::Binance
+    }
+
     /// Print configuration summary
     pub fn print_summary(&self) {
         println!("✅ Configuration loaded successfully");
@@ -171,6 +668,10 @@ impl BotConfig {
                 "⚠️ LIVE TRADING"
             }
         );
+        println!("🌐 Price Source: {:?}", self.price_source);
+        if self.blends_binance() {
+            println!("🔀 Spot Blend: {} Binance", self.spot_blend_binance_weight);
+        }
         println!(
             "🔄 Market Discovery: {}",
             if self.auto_discover_markets {
@@ -182,35 +683,520 @@ impl BotConfig {
         if !self.auto_discover_markets {
             println!("🎯 Strike Price: ${:.2}", self.strike_price);
         }
+        if self.max_markets_per_session > 0 {
+            println!("🔢 Max Markets Per Session: {}", self.max_markets_per_session);
+        }
+        if self.force_direction != ForceDirection::Auto {
+            println!("🧭 Forced Direction: {}", self.force_direction);
+        }
+        if self.strategy != Strategy::Vulture {
+            println!("🧭 Strategy: {} (quotes ±{:.2} around fair)", self.strategy, self.quote_offset);
+            if self.inventory_skew > Decimal::ZERO {
+                println!("⚖️ Inventory Skew: {:.2} at full inventory", self.inventory_skew);
+            }
+        }
+        if self.execution_mode != ExecutionMode::Limit {
+            println!("🎯 Execution Mode: {}", self.execution_mode);
+        }
+        if self.display_timezone != Tz::UTC {
+            println!("🕒 Display Timezone: {}", self.display_timezone);
+        }
+        if self.reconcile_interval_ticks > 0 && !self.paper_trade {
+            println!(
+                "🧮 Position Reconciliation: every {} ticks ({})",
+                self.reconcile_interval_ticks, self.reconcile_mode
+            );
+        }
+        if self.warmup_ticks > 0 {
+            println!("🌡️ Warmup: {} ticks of observation before trading", self.warmup_ticks);
+        }
+        if self.order_ttl_seconds > 0 {
+            println!("⏲️ Order TTL: {}s", self.order_ttl_seconds);
+        }
+        if self.prevent_self_trade != SelfTradePrevention::Cancel {
+            println!("🪞 Self-Trade Prevention: {}", self.prevent_self_trade);
+        }
+        if self.tp_sl_mode == TpSlMode::Absolute {
+            println!("🎯 Absolute TP/SL: {} / {}", self.take_profit_price, self.stop_loss_price);
+        }
+        if self.take_profit_trigger != TakeProfitTrigger::Bid {
+            println!("🎯 Take Profit Trigger: {}", self.take_profit_trigger);
+        }
+        if self.spot_ema_alpha < Decimal::ONE {
+            println!("〰️ Spot EMA Alpha: {}", self.spot_ema_alpha);
+        }
+        if self.vol_source != VolSource::Off {
+            println!("📈 Vol Source: {}", self.vol_source);
+        }
         println!("💰 Max Capital: ${:.2}", self.max_capital_per_trade);
+        if self.per_market_budget > Decimal::ZERO {
+            println!("🪙 Per-Market Budget: ${:.2}", self.per_market_budget);
+        }
+        if self.min_cash_ratio > Decimal::ZERO {
+            println!("🧯 Min Cash Ratio: {}", self.min_cash_ratio);
+        }
+        if self.max_drawdown_pct > Decimal::ZERO {
+            println!("📉 Max Drawdown: {}%", self.max_drawdown_pct);
+        }
+        if self.max_depth_levels > 0 {
+            println!("🪜 Max Depth: {} levels", self.max_depth_levels);
+        }
+        if self.sizing_mode == SizingMode::Confidence {
+            println!(
+                "📐 Confidence Sizing: ${:.2}-${:.2}, full size at {:.2} edge",
+                self.confidence_min_capital, self.max_capital_per_trade, self.confidence_full_edge
+            );
+        }
+        if self.max_depth_shares > Decimal::ZERO {
+            println!("🪜 Max Depth: {} shares", self.max_depth_shares);
+        }
+        for (name, capital) in &self.sub_accounts {
+            println!("📒 Sub-account {}: ${:.2}", name, capital);
+        }
+        if self.dead_man_interval > 0 {
+            println!("💀 Dead-Man's Switch: check in every {}s", self.dead_man_interval);
+        }
+    }
+}
+
+impl Default for BotConfig {
+    fn default() -> Self {
+        Self {
+            // Master switch
+            paper_trade: true,
+            paper_starting_cash: Decimal::from(100),
+
+            // Authentication
+            signer_private_key: "0x0000000000000000000000000000000000000000000000000000000000000000".to_string(),
+            proxy_address: "0x0000000000000000000000000000000000000000".to_string(),
+            polygon_rpc_url: "https://polygon-rpc.com".to_string(),
+            clob_base_url: "https://clob.polymarket.com".to_string(),
+            clob_api_key: String::new(),
+            clob_api_secret: String::new(),
+            clob_api_passphrase: String::new(),
+
+            // Price feed
+            price_source: PriceSourceKind::Scraper,
+            scrape_timeout_ms: 15_000,
+            chrome_path: String::new(),
+            chrome_args: String::new(),
+            binance_depth: false,
+            binance_max_backoff: 60,
+            binance_stale_timeout: 30,
+            spot_blend_binance_weight: Decimal::ZERO,
+            spot_ema_alpha: Decimal::ONE,
+            http_price_max_backoff: 60,
+            http_timeout_ms: 10_000,
+            sim_seed: 42,
+            sim_start_price: Decimal::from(100_000),
+            sim_drift: Decimal::ZERO,
+            sim_vol: Decimal::from_str("0.50").unwrap(),
+
+            // Market discovery
+            auto_discover_markets: true,
+            market_rotation_threshold: 30,
+            recent_market_ttl: 900,
+            market_overlap: MarketOverlap::Current,
+            discovery_concurrency: 4,
+            discovery_windows: 2,
+            strike_max_deviation_pct: Decimal::from(2),
+            max_markets_per_session: 0,
+            gamma_api_url: "https://gamma-api.polymarket.com/markets".to_string(),
+            crypto_price_api_url: "https://polymarket.com/api/crypto/crypto-price".to_string(),
+
+            // Strategy parameters
+            token_id_up: String::new(),
+            token_id_down: String::new(),
+            strike_price: Decimal::ZERO,
+            force_direction: ForceDirection::Auto,
+
+            // Capital management
+            max_capital_per_trade: Decimal::from(20),
+            sub_accounts: Vec::new(),
+            per_market_budget: Decimal::ZERO,
+            min_cash_ratio: Decimal::ZERO,
+            max_drawdown_pct: Decimal::ZERO,
+            max_depth_levels: 0,
+            max_depth_shares: Decimal::ZERO,
+            sizing_mode: SizingMode::Fixed,
+            confidence_full_edge: Decimal::from_str("0.10").unwrap(),
+            confidence_min_capital: Decimal::from_str("2.00").unwrap(),
+
+            // Quant settings
+            panic_discount: Decimal::from_str("0.08").unwrap(),
+            discount_vol_coefficient: Decimal::ZERO,
+            max_panic_discount: Decimal::from_str("0.30").unwrap(),
+            discount_early_scale: Decimal::ONE,
+            discount_late_scale: Decimal::ONE,
+            min_edge: Decimal::ZERO,
+            min_strike_distance: Decimal::ZERO,
+            min_entry_price: Decimal::ZERO,
+            max_entry_price: Decimal::ONE,
+            slippage_adjusted_edge: false,
+            scalp_profit: Decimal::from_str("0.01").unwrap(),
+            take_profit_trigger: TakeProfitTrigger::Bid,
+            stop_loss_threshold: Decimal::from_str("0.10").unwrap(),
+            tp_sl_mode: TpSlMode::Relative,
+            take_profit_price: Decimal::from_str("0.90").unwrap(),
+            stop_loss_price: Decimal::from_str("0.20").unwrap(),
+            trailing_stop_distance: Decimal::ZERO,
+            trailing_stop_activation: Decimal::from_str("0.02").unwrap(),
+            fair_value_exit_band: Decimal::ZERO,
+            scale_in_step: Decimal::from_str("0.05").unwrap(),
+            max_scale_ins: 0,
+            max_spread: Decimal::from_str("0.50").unwrap(),
+            min_book_update_rate: Decimal::ZERO,
+            book_rate_window: 30,
+            vol_source: VolSource::Off,
+            vol_window: 120,
+
+            // Exit rules
+            force_exit_before_expiry_seconds: 0,
+            max_hold_ticks: 0,
+
+            // Diagnostics
+            agreement_window: 0,
+            agreement_threshold: Decimal::from_str("0.005").unwrap(),
+            debug_api: false,
+            log_format: LogFormat::Text,
+            log_level: "info".to_string(),
+            quiet: false,
+            log_incremental: false,
+            display_timezone: Tz::UTC,
+            db_path: String::new(),
+            stage_latency: false,
+            session_note: String::new(),
+            annotation_target: String::new(),
+            grafana_api_key: String::new(),
+            discord_webhook_url: String::new(),
+            notify_stdout: false,
+
+            // Execution
+            strategy: Strategy::Vulture,
+            quote_offset: Decimal::from_str("0.02").unwrap(),
+            inventory_skew: Decimal::ZERO,
+            execution_mode: ExecutionMode::Limit,
+            snipe_cushion: Decimal::from_str("0.02").unwrap(),
+            dump_cushion: Decimal::from_str("0.02").unwrap(),
+            snipe_wait_time: 2000,
+            verify_live_fills: false,
+            fill_verify_attempts: 3,
+            fill_verify_interval: 500,
+            reconcile_interval_ticks: 0,
+            reconcile_mode: ReconcileMode::Log,
+            max_open_orders: 0,
+            prevent_self_trade: SelfTradePrevention::Cancel,
+            order_ttl_seconds: 0,
+            paper_fill_latency_ticks: 0,
+            validate_paper_fills: false,
+            order_dedup_window_ms: 0,
+            price_tick_size: Decimal::new(1, 2),
+            size_step: Decimal::ONE,
+            min_order_size: Decimal::from(5),
+
+            // Timing
+            market_expiry_timestamp: chrono::Utc::now().timestamp_millis() + 15 * 60 * 1000,
+            tick_interval: 500,
+            tick_jitter_ms: 0,
+            warmup_ticks: 0,
+            http_rate_limit: 10,
+
+            // Service
+            health_port: 0,
+            health_max_tick_age: 30,
+            operator_token: String::new(),
+            dead_man_interval: 0,
+            dead_man_file: String::new(),
+        }
+    }
+}
+
+/// Longest snipe wait allowed, in ticks
+const MAX_SNIPE_WAIT_TICKS: u64 = 10;
+
+/// Config file looked for in the working directory
+const DEFAULT_CONFIG_FILE: &str = "bot.toml";
+
+/// Descriptions of each key, taken from the documented example file
+const ENV_EXAMPLE: &str = include_str!("../.env.example");
+
+/// Config values from the environment, falling back to a config file
+#[derive(Default)]
+struct ConfigSource {
+    file: HashMap<String, String>, // keyed by env var name
+    overrides: HashMap<String, String>, // command-line flags, keyed by env var name
+    env: Option<HashMap<String, String>>, // stands in for the process environment when set
+    used: RefCell<HashSet<String>>,
+    defaults_only: bool,                     // ignore the environment (template generation)
+    defaults: RefCell<Vec<(String, String)>>, // every typed read's key and default, in read order
+}
+
+impl ConfigSource {
+    /// Flatten a TOML document of `field = value` pairs
+    fn from_toml(contents: &str) -> Result<Self> {
+        let table: toml::Table = contents.parse()?;
+        let file = table
+            .into_iter()
+            .map(|(key, value)| {
+                let value = match value {
+                    toml::Value::String(s) => s,
+                    toml::Value::Integer(i) => i.to_string(),
+                    toml::Value::Float(f) => f.to_string(),
+                    toml::Value::Boolean(b) => b.to_string(),
+                    other => anyhow::bail!("'{}' must be a string, number or boolean, got {}", key, other.type_str()),
+                };
+                Ok((key.to_uppercase(), value))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            file,
+            ..Self::default()
+        })
+    }
+
+    /// Read and flatten a TOML config file
+    fn read_file(path: &str) -> Result<Self> {
+        let contents = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path))?;
+        Self::from_toml(&contents).with_context(|| format!("Invalid {}", path))
+    }
+
+    /// Reject file and flag keys that no field read, so typos don't pass silently
+    fn check_unused(&self) -> Result<()> {
+        let used = self.used.borrow();
+        let file_keys = self.file.keys().map(|key| (key, key.to_lowercase()));
+        let override_keys = self.overrides.keys().map(|key| (key, key.clone()));
+        let mut unknown: Vec<String> = file_keys
+            .chain(override_keys)
+            .filter(|(key, _)| !used.contains(*key))
+            .map(|(_, name)| name)
+            .collect();
+        if unknown.is_empty() {
+            return Ok(());
+        }
+
+        unknown.sort();
+        anyhow::bail!("Unknown config keys: {}", unknown.join(", "))
+    }
+
+    fn var(&self, key: &str) -> Result<String, env::VarError> {
+        self.used.borrow_mut().insert(key.to_string());
+        if self.defaults_only {
+            return Err(env::VarError::NotPresent);
+        }
+        if let Some(value) = self.overrides.get(key) {
+            return Ok(value.clone());
+        }
+        let value = match &self.env {
+            Some(env) => env.get(key).cloned().ok_or(env::VarError::NotPresent),
+            None => env::var(key),
+        };
+        value.or_else(|e| self.file.get(key).cloned().ok_or(e))
+    }
+
+    /// Read a key, remembering its default for the template
+    fn typed_var(&self, key: &str, default: &dyn std::fmt::Display) -> Result<String, env::VarError> {
+        {
+            let mut defaults = self.defaults.borrow_mut();
+            if !defaults.iter().any(|(seen, _)| seen == key) {
+                defaults.push((key.to_string(), default.to_string()));
+            }
+        }
+        self.var(key)
+    }
+
+    fn get_string(&self, key: &str, default: String) -> String {
+        self.typed_var(key, &default).unwrap_or(default)
+    }
+
+    /// Parse an enum-like value, failing on anything unrecognized
+    fn get_parsed<T>(&self, key: &str, default: T) -> Result<T>
+    where
+        T: FromStr<Err = anyhow::Error> + std::fmt::Display,
+    {
+        match self.typed_var(key, &default) {
+            Ok(value) => value.parse().with_context(|| format!("Invalid {}", key)),
+            Err(_) => Ok(default),
+        }
+    }
+
+    fn get_bool(&self, key: &str, default: bool) -> bool {
+        self.typed_var(key, &default)
+            .map(|v| v.to_lowercase() == "true")
+            .unwrap_or(default)
+    }
+
+    fn get_i64(&self, key: &str, default: i64) -> i64 {
+        self.typed_var(key, &default)
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(default)
+    }
+
+    fn get_u64(&self, key: &str, default: u64) -> u64 {
+        self.typed_var(key, &default)
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(default)
+    }
+
+    fn get_decimal(&self, key: &str, default: Decimal) -> Decimal {
+        self.typed_var(key, &default)
+            .ok()
+            .and_then(|v| Decimal::from_str(&v).ok())
+            .unwrap_or(default)
     }
 }
 
-// Helper functions for parsing environment variables
+/// Format strategy allocations the way `parse_allocations` reads them
+fn format_allocations(allocations: &[(String, Decimal)]) -> String {
+    allocations
+        .iter()
+        .map(|(name, amount)| format!("{}={}", name, amount))
+        .collect::<Vec<_>>()
+        .join(",")
+}
 
-fn get_env_bool(key: &str, default: bool) -> bool {
-    env::var(key)
-        .map(|v| v.to_lowercase() == "true")
-        .unwrap_or(default)
+fn is_http_url(url: &str) -> bool {
+    url.starts_with("http://") || url.starts_with("https://")
 }
 
-fn get_env_i64(key: &str, default: i64) -> i64 {
-    env::var(key)
-        .ok()
-        .and_then(|v| v.parse().ok())
-        .unwrap_or(default)
+/// One-line description of each key in `.env.example`
+fn key_descriptions() -> HashMap<&'static str, &'static str> {
+    ENV_EXAMPLE
+        .lines()
+        .filter_map(|line| {
+            let (key, rest) = line.split_once('=')?;
+            let (_, comment) = rest.split_once('#')?;
+            Some((key.trim(), comment.trim()))
+        })
+        .filter(|(key, _)| !key.starts_with('#'))
+        .collect()
 }
 
-fn get_env_u64(key: &str, default: u64) -> u64 {
-    env::var(key)
-        .ok()
-        .and_then(|v| v.parse().ok())
-        .unwrap_or(default)
+/// Parse "name=amount,name=amount" strategy allocations
+fn parse_allocations(value: &str) -> Result<Vec<(String, Decimal)>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (name, amount) = entry
+                .split_once('=')
+                .with_context(|| format!("Expected name=amount, got '{}'", entry))?;
+            let amount = Decimal::from_str(amount.trim())
+                .with_context(|| format!("Invalid amount for '{}'", name.trim()))?;
+            Ok((name.trim().to_string(), amount))
+        })
+        .collect()
 }
 
-fn get_env_decimal(key: &str, default: Decimal) -> Decimal {
-    env::var(key)
-        .ok()
-        .and_then(|v| Decimal::from_str(&v).ok())
-        .unwrap_or(default)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_file_with_env_override() {
+        let file = "paper_trade = true\n\
+                    panic_discount = 0.12\n\
+                    scalp_profit = \"0.03\"\n\
+                    debug_api = true\n\
+                    tick_interval = 750\n";
+
+        let config = BotConfig::load_in_memory(file, &[], HashMap::new()).unwrap();
+        assert!(config.paper_trade);
+        assert!(config.debug_api);
+        assert_eq!(config.panic_discount, Decimal::from_str("0.12").unwrap());
+        assert_eq!(config.scalp_profit, Decimal::from_str("0.03").unwrap());
+        assert_eq!(config.tick_interval, 750);
+        assert_eq!(config.stop_loss_threshold, BotConfig::default().stop_loss_threshold);
+
+        // The environment wins over the file
+        let config = BotConfig::load_in_memory(file, &[("SCALP_PROFIT", "0.05")], HashMap::new());
+        assert_eq!(config.unwrap().scalp_profit, Decimal::from_str("0.05").unwrap());
+    }
+
+    #[test]
+    fn test_validate_execution_settings() {
+        assert!(BotConfig::default().validate().is_ok());
+
+        let invalid = [
+            BotConfig { snipe_cushion: Decimal::from_str("-0.01").unwrap(), ..BotConfig::default() },
+            BotConfig { snipe_cushion: Decimal::from_str("0.51").unwrap(), ..BotConfig::default() },
+            BotConfig { dump_cushion: Decimal::from_str("-0.01").unwrap(), ..BotConfig::default() },
+            BotConfig { dump_cushion: Decimal::from_str("0.51").unwrap(), ..BotConfig::default() },
+            BotConfig { snipe_wait_time: 5_000, tick_interval: 500, ..BotConfig::default() },
+        ];
+        for config in invalid {
+            assert!(config.validate().is_err(), "accepted {:?}", config);
+        }
+
+        // Errors are reported together
+        let config = BotConfig {
+            snipe_cushion: Decimal::ONE,
+            dump_cushion: Decimal::ONE,
+            snipe_wait_time: 60_000,
+            ..BotConfig::default()
+        };
+        let message = config.validate().unwrap_err().to_string();
+        assert!(message.contains("SNIPE_CUSHION"));
+        assert!(message.contains("DUMP_CUSHION"));
+        assert!(message.contains("SNIPE_WAIT_TIME"));
+
+        // Live books carry no last trade to trigger on
+        let config = BotConfig {
+            paper_trade: false,
+            take_profit_trigger: TakeProfitTrigger::Last,
+            ..BotConfig::default()
+        };
+        assert!(config.validate().unwrap_err().to_string().contains("TAKE_PROFIT_TRIGGER"));
+    }
+
+    #[test]
+    fn test_file_rejects_unknown_and_invalid_values() {
+        // An empty environment, so neither the process's nor .env can mask the file
+        let file_only = |toml: &str| ConfigSource {
+            env: Some(HashMap::new()),
+            ..ConfigSource::from_toml(toml).unwrap()
+        };
+
+        let source = file_only("panic_discont = 0.12");
+        BotConfig::from_source(&source).unwrap();
+        assert!(source.check_unused().unwrap_err().to_string().contains("panic_discont"));
+
+        // File values still go through validate()
+        let source = file_only("panic_discount = 1.5");
+        assert!(BotConfig::from_source(&source).is_err());
+
+        assert!(ConfigSource::from_toml("sub_accounts = [1, 2]").is_err());
+    }
+
+    #[test]
+    fn test_template_lists_every_key() {
+        let template = BotConfig::template().unwrap();
+        let lines: HashMap<&str, &str> = template
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| line.split_once('='))
+            .collect();
+
+        // Every key the loader reads appears, with its default
+        let source = ConfigSource {
+            env: Some(HashMap::new()),
+            ..ConfigSource::default()
+        };
+        BotConfig::from_source(&source).unwrap();
+        for key in source.used.borrow().iter() {
+            assert!(lines.contains_key(key.as_str()), "{} missing from template", key);
+        }
+        assert!(lines["PAPER_STARTING_CASH"].starts_with("100  # "));
+        assert!(lines["PRICE_SOURCE"].starts_with("scraper  # "));
+        assert!(lines["PANIC_DISCOUNT"].starts_with("0.08  # "));
+
+        // ...and is documented in .env.example
+        let descriptions = key_descriptions();
+        for key in lines.keys() {
+            assert!(descriptions.contains_key(key), "{} has no description in .env.example", key);
+        }
+    }
 }
@@ -0,0 +1,205 @@
+/// SQLite session store for analysis across runs
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+use rust_decimal::prelude::*;
+use rust_decimal::Decimal;
+use std::sync::{Arc, Mutex};
+
+use crate::models::{ClosedTrade, SessionSummary, TickData};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS ticks (
+    session_id TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    tick_number INTEGER NOT NULL,
+    market_slug TEXT NOT NULL,
+    spot_price REAL NOT NULL,
+    strike_price REAL NOT NULL,
+    fair_value REAL NOT NULL,
+    target_buy_price REAL NOT NULL,
+    best_bid REAL,
+    best_ask REAL,
+    spread REAL,
+    minutes_remaining REAL NOT NULL,
+    state TEXT NOT NULL,
+    unrealized_pnl REAL
+);
+CREATE INDEX IF NOT EXISTS ticks_session ON ticks (session_id, tick_number);
+
+CREATE TABLE IF NOT EXISTS trades (
+    session_id TEXT NOT NULL,
+    token_id TEXT NOT NULL,
+    entry_price REAL NOT NULL,
+    exit_price REAL NOT NULL,
+    size REAL NOT NULL,
+    pnl REAL NOT NULL,
+    entry_time INTEGER NOT NULL,
+    exit_time INTEGER NOT NULL,
+    committed_time INTEGER NOT NULL,
+    exit_reason TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS trades_session ON trades (session_id);
+
+CREATE TABLE IF NOT EXISTS sessions (
+    session_id TEXT PRIMARY KEY,
+    start_time INTEGER NOT NULL,
+    end_time INTEGER NOT NULL,
+    duration_seconds INTEGER NOT NULL,
+    total_ticks INTEGER NOT NULL,
+    markets_traded INTEGER NOT NULL,
+    total_pnl REAL NOT NULL,
+    final_cash REAL NOT NULL,
+    total_trades INTEGER NOT NULL,
+    wins INTEGER NOT NULL,
+    losses INTEGER NOT NULL,
+    win_rate REAL NOT NULL,
+    fill_rate REAL NOT NULL,
+    note TEXT
+);
+";
+
+/// Ticks, trades and session summaries keyed by session id
+///
+/// Prices are stored as REAL so they aggregate in SQL; the JSON logs remain
+/// the exact record. Writes run on the blocking pool, so a slow disk never
+/// stalls the runtime the tick loop and feeds share.
+pub struct SessionDb {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl SessionDb {
+    /// Open (or create) the database at `path` and ensure the tables exist
+    pub fn open(path: &str) -> Result<Self> {
+        let conn = Connection::open(path).with_context(|| format!("Failed to open database {}", path))?;
+        conn.execute_batch(SCHEMA).context("Failed to create database schema")?;
+        Self::migrate(&conn).context("Failed to migrate database schema")?;
+        Ok(Self { conn: Arc::new(Mutex::new(conn)) })
+    }
+
+    /// Bring tables created by an older version up to `SCHEMA`
+    ///
+    /// `CREATE TABLE IF NOT EXISTS` leaves an existing table as it was, so
+    /// columns added since have to be added here.
+    fn migrate(conn: &Connection) -> rusqlite::Result<()> {
+        if !Self::has_column(conn, "ticks", "unrealized_pnl")? {
+            conn.execute_batch("ALTER TABLE ticks ADD COLUMN unrealized_pnl REAL")?;
+        }
+        Ok(())
+    }
+
+    fn has_column(conn: &Connection, table: &str, column: &str) -> rusqlite::Result<bool> {
+        let mut statement = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+        let mut names = statement.query_map([], |row| row.get::<_, String>(1))?;
+        names.try_fold(false, |found, name| Ok(found || name? == column))
+    }
+
+    /// Run a write against the connection on the blocking pool
+    async fn write<F>(&self, write: F) -> Result<()>
+    where
+        F: FnOnce(&Connection) -> rusqlite::Result<usize> + Send + 'static,
+    {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || write(&conn.lock().expect("db lock poisoned")))
+            .await
+            .context("Database write task failed")??;
+        Ok(())
+    }
+
+    pub async fn insert_tick(&self, session_id: &str, tick: TickData) -> Result<()> {
+        let session_id = session_id.to_string();
+        self.write(move |conn| {
+            conn.execute(
+                "INSERT INTO ticks (session_id, timestamp, tick_number, market_slug, spot_price, strike_price,
+                    fair_value, target_buy_price, best_bid, best_ask, spread, minutes_remaining, state, unrealized_pnl)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+                params![
+                    session_id,
+                    tick.timestamp,
+                    tick.tick_number as i64,
+                    tick.market_slug,
+                    real(tick.spot_price),
+                    real(tick.strike_price),
+                    real(tick.fair_value),
+                    real(tick.target_buy_price),
+                    tick.best_bid.map(real),
+                    tick.best_ask.map(real),
+                    tick.spread.map(real),
+                    tick.minutes_remaining,
+                    tick.state,
+                    tick.unrealized_pnl.map(real),
+                ],
+            )
+        })
+        .await
+    }
+
+    pub async fn insert_trade(&self, session_id: &str, trade: ClosedTrade) -> Result<()> {
+        let session_id = session_id.to_string();
+        self.write(move |conn| {
+            conn.execute(
+                "INSERT INTO trades (session_id, token_id, entry_price, exit_price, size, pnl,
+                    entry_time, exit_time, committed_time, exit_reason)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    session_id,
+                    trade.token_id,
+                    real(trade.entry_price),
+                    real(trade.exit_price),
+                    real(trade.size),
+                    real(trade.pnl),
+                    trade.entry_time,
+                    trade.exit_time,
+                    trade.committed_time,
+                    format!("{:?}", trade.exit_reason),
+                ],
+            )
+        })
+        .await
+    }
+
+    /// Record the session summary, replacing any earlier flush of the same session
+    pub async fn insert_summary(&self, summary: SessionSummary) -> Result<()> {
+        self.write(move |conn| {
+            let stats = &summary.trade_stats;
+            conn.execute(
+                "INSERT OR REPLACE INTO sessions (session_id, start_time, end_time, duration_seconds, total_ticks,
+                    markets_traded, total_pnl, final_cash, total_trades, wins, losses, win_rate, fill_rate, note)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+                params![
+                    summary.session_id,
+                    summary.start_time,
+                    summary.end_time,
+                    summary.duration_seconds,
+                    summary.total_ticks as i64,
+                    summary.markets_traded as i64,
+                    real(summary.total_pnl),
+                    real(summary.final_cash),
+                    stats.total_trades as i64,
+                    stats.wins as i64,
+                    stats.losses as i64,
+                    stats.win_rate,
+                    summary.fill_stats.fill_rate,
+                    summary.note,
+                ],
+            )
+        })
+        .await
+    }
+
+    #[cfg(test)]
+    pub fn count(&self, table: &str, session_id: &str) -> i64 {
+        self.conn
+            .lock()
+            .unwrap()
+            .query_row(
+                &format!("SELECT COUNT(*) FROM {} WHERE session_id = ?1", table),
+                params![session_id],
+                |row| row.get(0),
+            )
+            .unwrap()
+    }
+}
+
+fn real(value: Decimal) -> f64 {
+    value.to_f64().unwrap_or(f64::NAN)
+}
@@ -0,0 +1,98 @@
+/// Operator dead-man's switch for unattended live trading
+use std::path::PathBuf;
+
+/// Trips when the operator hasn't checked in within the interval
+///
+/// Check-ins arrive through the `/checkin` control endpoint or by touching
+/// the check-in file; the window starts when the bot does.
+///
+/// Anything that can check in can keep the switch from firing, so both routes
+/// must be limited to the operator: the endpoint takes an authenticated POST
+/// (`OPERATOR_TOKEN`), and the file must be writable only by the operator.
+pub struct DeadMansSwitch {
+    interval_ms: i64,
+    file: Option<PathBuf>,
+    last_checkin_ms: i64,
+}
+
+impl DeadMansSwitch {
+    /// Arm a switch with `interval_secs` between check-ins (0 = disabled)
+    pub fn new(interval_secs: u64, file: &str, now_ms: i64) -> Self {
+        Self {
+            interval_ms: interval_secs as i64 * 1000,
+            file: (!file.is_empty()).then(|| PathBuf::from(file)),
+            last_checkin_ms: now_ms,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.interval_ms > 0
+    }
+
+    /// Record an operator check-in made at `at_ms`
+    pub fn check_in(&mut self, at_ms: i64) {
+        self.last_checkin_ms = self.last_checkin_ms.max(at_ms);
+    }
+
+    /// Milliseconds since the last check-in
+    pub fn silence_ms(&self, now_ms: i64) -> i64 {
+        now_ms - self.last_checkin_ms
+    }
+
+    /// Whether the operator has gone quiet for longer than the interval
+    ///
+    /// A touch of the check-in file since the last call counts as a check-in.
+    pub fn expired(&mut self, now_ms: i64) -> bool {
+        if !self.is_enabled() {
+            return false;
+        }
+        if let Some(touched_ms) = self.file_touched_ms() {
+            self.check_in(touched_ms);
+        }
+        self.silence_ms(now_ms) > self.interval_ms
+    }
+
+    /// Modification time of the check-in file, if it exists
+    fn file_touched_ms(&self) -> Option<i64> {
+        let modified = std::fs::metadata(self.file.as_ref()?).ok()?.modified().ok()?;
+        let since_epoch = modified.duration_since(std::time::UNIX_EPOCH).ok()?;
+        Some(since_epoch.as_millis() as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expires_without_checkin() {
+        let mut switch = DeadMansSwitch::new(60, "", 1_000_000);
+        assert!(!switch.expired(1_060_000));
+        assert!(switch.expired(1_060_001));
+
+        // A check-in restarts the window
+        switch.check_in(1_050_000);
+        assert!(!switch.expired(1_100_000));
+        assert!(switch.expired(1_110_001));
+
+        // Disabled switches never trip
+        let mut off = DeadMansSwitch::new(0, "", 0);
+        assert!(!off.expired(i64::MAX));
+    }
+
+    #[test]
+    fn test_touching_file_checks_in() {
+        let path = std::env::temp_dir().join(format!("deadman_test_{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut switch = DeadMansSwitch::new(60, path.to_str().unwrap(), 0);
+        assert!(switch.expired(61_000));
+
+        std::fs::write(&path, "").unwrap();
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        assert!(!switch.expired(now_ms));
+        assert!(switch.silence_ms(now_ms) < 60_000);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,103 @@
+/// Research diagnostics for the fair value model
+use rust_decimal::Decimal;
+use std::collections::VecDeque;
+
+/// Flags stretches where the model's fair value just tracks the market mid
+///
+/// A model that matches the book tick after tick carries no independent
+/// signal, so a persistently tiny gap is worth a review.
+pub struct AgreementMonitor {
+    window: usize,
+    threshold: Decimal,
+    gaps: VecDeque<Decimal>,
+    alerted: bool,
+}
+
+impl AgreementMonitor {
+    /// Create a monitor over `window` ticks (0 disables it)
+    pub fn new(window: usize, threshold: Decimal) -> Self {
+        Self {
+            window,
+            threshold,
+            gaps: VecDeque::with_capacity(window),
+            alerted: false,
+        }
+    }
+
+    /// Record a tick; returns the mean gap when an alert should be raised
+    ///
+    /// Fires once per stretch of agreement and re-arms when the gap widens.
+    pub fn record(&mut self, fair_value: Decimal, market_mid: Decimal) -> Option<Decimal> {
+        if self.window == 0 {
+            return None;
+        }
+
+        if self.gaps.len() == self.window {
+            self.gaps.pop_front();
+        }
+        self.gaps.push_back((fair_value - market_mid).abs());
+
+        if self.gaps.len() < self.window {
+            return None;
+        }
+
+        let mean_gap = self.mean_gap();
+        if mean_gap <= self.threshold {
+            if !self.alerted {
+                self.alerted = true;
+                return Some(mean_gap);
+            }
+        } else {
+            self.alerted = false;
+        }
+
+        None
+    }
+
+    /// Mean absolute gap between fair value and mid over the window
+    pub fn mean_gap(&self) -> Decimal {
+        if self.gaps.is_empty() {
+            return Decimal::ZERO;
+        }
+        self.gaps.iter().sum::<Decimal>() / Decimal::from(self.gaps.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    #[test]
+    fn test_perfect_tracking_triggers_note() {
+        let mut monitor = AgreementMonitor::new(5, dec("0.005"));
+
+        // Model equals the mid every tick: alert once the window fills
+        let mids = ["0.50", "0.52", "0.55", "0.53", "0.51"];
+        let alerts: Vec<_> = mids
+            .iter()
+            .map(|mid| monitor.record(dec(mid), dec(mid)))
+            .collect();
+        assert!(alerts[..4].iter().all(Option::is_none));
+        assert_eq!(alerts[4], Some(Decimal::ZERO));
+
+        // Still agreeing: no repeated alert
+        assert_eq!(monitor.record(dec("0.50"), dec("0.50")), None);
+    }
+
+    #[test]
+    fn test_independent_model_does_not_trigger() {
+        let mut monitor = AgreementMonitor::new(3, dec("0.005"));
+        for _ in 0..10 {
+            assert_eq!(monitor.record(dec("0.60"), dec("0.50")), None);
+        }
+
+        // Disabled monitor never alerts
+        let mut disabled = AgreementMonitor::new(0, dec("0.005"));
+        assert_eq!(disabled.record(dec("0.50"), dec("0.50")), None);
+    }
+}
@@ -0,0 +1,228 @@
+/// Liveness endpoint for running the bot as a service
+use anyhow::{Context, Result};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+/// What the bot last reported about itself
+#[derive(Debug, Clone, Default)]
+pub struct HealthStatus {
+    pub state: String,
+    pub tick_count: u64,
+    pub last_tick_ms: Option<i64>, // Unix milliseconds of the last completed tick
+    pub last_spot_price: Option<Decimal>,
+    pub price_source_ready: bool,
+    pub last_checkin_ms: Option<i64>, // Unix milliseconds of the last operator `/checkin`
+    pub resume_requested: bool,       // operator `/resume` not yet acted on
+    pub flatten_requested: bool,      // operator SIGUSR1 not yet acted on
+}
+
+/// Health status shared between the bot loop and the server
+pub type SharedHealth = Arc<RwLock<HealthStatus>>;
+
+/// Body of a `/health` response
+#[derive(Debug, Serialize)]
+pub struct HealthReport {
+    pub healthy: bool,
+    pub state: String,
+    pub tick_count: u64,
+    pub seconds_since_last_tick: Option<f64>,
+    pub last_spot_price: Option<Decimal>,
+    pub price_source_ready: bool,
+}
+
+impl HealthReport {
+    /// Judge the bot healthy if it completed a tick within `max_tick_age` seconds
+    pub fn new(status: &HealthStatus, now_ms: i64, max_tick_age: u64) -> Self {
+        let seconds_since_last_tick = status
+            .last_tick_ms
+            .map(|last| (now_ms - last).max(0) as f64 / 1000.0);
+
+        Self {
+            healthy: matches!(seconds_since_last_tick, Some(age) if age <= max_tick_age as f64),
+            state: status.state.clone(),
+            tick_count: status.tick_count,
+            seconds_since_last_tick,
+            last_spot_price: status.last_spot_price,
+            price_source_ready: status.price_source_ready,
+        }
+    }
+}
+
+/// Bind `/health` on `port` (0 = any free port) and serve it in the background
+///
+/// `/health` is open to any probe. The operator routes change what the bot
+/// does, so they take a POST carrying `operator_token` as a bearer token, and
+/// are refused outright while no token is configured.
+pub async fn start(port: u16, status: SharedHealth, max_tick_age: u64, operator_token: String) -> Result<SocketAddr> {
+    let listener = TcpListener::bind(("0.0.0.0", port))
+        .await
+        .with_context(|| format!("Failed to bind health endpoint on port {}", port))?;
+    let addr = listener.local_addr()?;
+    info!("🩺 Health endpoint listening on http://{}/health", addr);
+
+    let operator_token = Arc::new(operator_token);
+    tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    let status = status.clone();
+                    let operator_token = operator_token.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = handle(stream, &status, max_tick_age, &operator_token).await {
+                            warn!("⚠️ Health request failed: {}", e);
+                        }
+                    });
+                }
+                Err(e) => warn!("⚠️ Health endpoint accept failed: {}", e),
+            }
+        }
+    });
+
+    Ok(addr)
+}
+
+/// Answer one HTTP request: 200/503 with a report on `/health`, 200 on an
+/// authorized `/checkin` and `/resume`, 404 elsewhere
+async fn handle(mut stream: TcpStream, status: &SharedHealth, max_tick_age: u64, operator_token: &str) -> Result<()> {
+    let mut buf = [0u8; 1024];
+    let read = stream.read(&mut buf).await?;
+    let request = String::from_utf8_lossy(&buf[..read]);
+    let mut request_line = request.split_whitespace();
+    let method = request_line.next().unwrap_or("");
+    let path = request_line.next().unwrap_or("");
+
+    let (status_line, body) = if path == "/health" {
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        let report = HealthReport::new(&*status.read().await, now_ms, max_tick_age);
+        let status_line = if report.healthy {
+            "200 OK"
+        } else {
+            "503 Service Unavailable"
+        };
+        (status_line, serde_json::to_string(&report)?)
+    } else if path == "/checkin" {
+        match refuse_operator_request(method, &request, operator_token) {
+            Some(refusal) => refusal,
+            None => {
+                status.write().await.last_checkin_ms = Some(chrono::Utc::now().timestamp_millis());
+                info!("🫡 Operator check-in received");
+                ("200 OK", r#"{"checked_in":true}"#.to_string())
+            }
+        }
+    } else if path == "/resume" {
+        match refuse_operator_request(method, &request, operator_token) {
+            Some(refusal) => refusal,
+            None => {
+                status.write().await.resume_requested = true;
+                info!("▶️ Operator resume received");
+                ("200 OK", r#"{"resume_requested":true}"#.to_string())
+            }
+        }
+    } else {
+        ("404 Not Found", r#"{"error":"not found"}"#.to_string())
+    };
+
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status_line,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+/// The response refusing an operator request, or None if it may proceed
+///
+/// The request must be a POST with `Authorization: Bearer <operator_token>`,
+/// and there must be a token to match.
+fn refuse_operator_request(method: &str, request: &str, operator_token: &str) -> Option<(&'static str, String)> {
+    if operator_token.is_empty() {
+        return Some(("403 Forbidden", r#"{"error":"operator routes are disabled without OPERATOR_TOKEN"}"#.to_string()));
+    }
+    if method != "POST" {
+        return Some(("405 Method Not Allowed", r#"{"error":"use POST"}"#.to_string()));
+    }
+    let authorized = request.lines().skip(1).any(|line| {
+        line.split_once(':').is_some_and(|(name, value)| {
+            name.trim().eq_ignore_ascii_case("authorization")
+                && value.trim().strip_prefix("Bearer ") == Some(operator_token)
+        })
+    });
+    if !authorized {
+        warn!("⚠️ Operator request without a valid token refused");
+        return Some(("401 Unauthorized", r#"{"error":"unauthorized"}"#.to_string()));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stale_tick_is_unhealthy() {
+        let status = HealthStatus {
+            state: "SCANNING".to_string(),
+            tick_count: 12,
+            last_tick_ms: Some(1_000_000),
+            last_spot_price: Some(Decimal::from(98_500)),
+            price_source_ready: true,
+            last_checkin_ms: None,
+            resume_requested: false,
+            flatten_requested: false,
+        };
+
+        let report = HealthReport::new(&status, 1_010_000, 30);
+        assert!(report.healthy);
+        assert_eq!(report.seconds_since_last_tick, Some(10.0));
+
+        assert!(!HealthReport::new(&status, 1_031_000, 30).healthy);
+
+        // No tick yet is not healthy either
+        assert!(!HealthReport::new(&HealthStatus::default(), 1_000_000, 30).healthy);
+    }
+
+    #[tokio::test]
+    async fn test_operator_routes_require_post_and_token() {
+        let status = SharedHealth::default();
+        let addr = start(0, status.clone(), 30, "s3cret".to_string()).await.unwrap();
+        let url = |path: &str| format!("http://127.0.0.1:{}{}", addr.port(), path);
+        let client = reqwest::Client::new();
+
+        // A crawler or probe GETting the path changes nothing
+        assert_eq!(client.get(url("/resume")).send().await.unwrap().status(), 405);
+        let response = client.post(url("/resume")).send().await.unwrap();
+        assert_eq!(response.status(), 401);
+        let response = client.post(url("/resume")).bearer_auth("wrong").send().await.unwrap();
+        assert_eq!(response.status(), 401);
+        assert!(!status.read().await.resume_requested);
+
+        let response = client.post(url("/resume")).bearer_auth("s3cret").send().await.unwrap();
+        assert_eq!(response.status(), 200);
+        assert!(status.read().await.resume_requested);
+
+        // A check-in is held to the same rule, so a poller can't keep the switch from firing
+        assert_eq!(client.get(url("/checkin")).send().await.unwrap().status(), 405);
+        assert_eq!(client.post(url("/checkin")).send().await.unwrap().status(), 401);
+        assert_eq!(status.read().await.last_checkin_ms, None);
+        let response = client.post(url("/checkin")).bearer_auth("s3cret").send().await.unwrap();
+        assert_eq!(response.status(), 200);
+        assert!(status.read().await.last_checkin_ms.is_some());
+
+        // /health stays open
+        assert_eq!(client.get(url("/health")).send().await.unwrap().status(), 503);
+
+        // No token configured: the route is off
+        let addr = start(0, SharedHealth::default(), 30, String::new()).await.unwrap();
+        let url = format!("http://127.0.0.1:{}/resume", addr.port());
+        assert_eq!(client.post(url).bearer_auth("").send().await.unwrap().status(), 403);
+    }
+}
@@ -0,0 +1,19 @@
+/// Shared HTTP client for every REST call the bot makes
+use std::time::Duration;
+
+/// Interval between TCP and HTTP/2 keep-alive pings on idle pooled connections
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Build the client shared by discovery, price polling, order books and annotations
+///
+/// reqwest clients are cheap handles onto one connection pool, so cloning this
+/// one keeps TLS sessions warm across services instead of reconnecting per call.
+pub fn build(timeout_ms: u64) -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(Duration::from_millis(timeout_ms))
+        .tcp_keepalive(KEEP_ALIVE_INTERVAL)
+        .http2_keep_alive_interval(KEEP_ALIVE_INTERVAL)
+        .http2_keep_alive_while_idle(true)
+        .build()
+        .expect("Failed to build HTTP client")
+}
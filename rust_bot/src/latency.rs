@@ -0,0 +1,212 @@
+/// Tick latency measurement
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::time::Duration;
+
+/// Number of recent ticks kept for percentile reporting
+const HISTOGRAM_CAPACITY: usize = 2000;
+
+/// Rolling window of latency samples
+#[derive(Debug, Clone)]
+pub struct LatencyHistogram {
+    samples: VecDeque<Duration>,
+    capacity: usize,
+}
+
+impl LatencyHistogram {
+    /// Create a histogram holding at most `capacity` samples
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity.min(HISTOGRAM_CAPACITY)),
+            capacity,
+        }
+    }
+
+    /// Add a sample, evicting the oldest when full
+    pub fn record(&mut self, duration: Duration) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(duration);
+    }
+
+    /// Number of samples held
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Nearest-rank percentile (0-100) of the held samples
+    pub fn percentile(&self, pct: f64) -> Option<Duration> {
+        if self.samples.is_empty() {
+            return None;
+        }
+
+        let mut sorted: Vec<Duration> = self.samples.iter().copied().collect();
+        sorted.sort();
+
+        let rank = ((pct / 100.0) * sorted.len() as f64).ceil() as usize;
+        Some(sorted[rank.clamp(1, sorted.len()) - 1])
+    }
+
+    /// Summarise as p50/p95/max in milliseconds
+    pub fn stats(&self) -> LatencyStats {
+        let ms = |d: Option<Duration>| d.map(|d| d.as_secs_f64() * 1000.0).unwrap_or(0.0);
+        LatencyStats {
+            samples: self.len() as u64,
+            p50_ms: ms(self.percentile(50.0)),
+            p95_ms: ms(self.percentile(95.0)),
+            max_ms: ms(self.samples.iter().max().copied()),
+        }
+    }
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self::new(HISTOGRAM_CAPACITY)
+    }
+}
+
+/// Latency percentiles reported in the session summary
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LatencyStats {
+    pub samples: u64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+}
+
+/// Time spent in each phase of a single tick; None for phases the tick didn't reach
+#[derive(Debug, Clone, Default)]
+pub struct TickTimings {
+    pub discovery: Option<Duration>,
+    pub spot_fetch: Option<Duration>,
+    pub book_up: Option<Duration>,
+    pub book_down: Option<Duration>,
+    pub strategy: Option<Duration>,
+    pub logging: Option<Duration>,
+}
+
+impl TickTimings {
+    /// The phases this tick ran, in tick order
+    pub fn stages(&self) -> Vec<(&'static str, Duration)> {
+        [
+            ("discovery", self.discovery),
+            ("spot_fetch", self.spot_fetch),
+            ("book_up", self.book_up),
+            ("book_down", self.book_down),
+            ("strategy", self.strategy),
+            ("logging", self.logging),
+        ]
+        .into_iter()
+        .filter_map(|(stage, elapsed)| Some((stage, elapsed?)))
+        .collect()
+    }
+
+    /// Human-readable phase breakdown
+    pub fn breakdown(&self) -> String {
+        self.stages()
+            .iter()
+            .map(|(stage, elapsed)| format!("{} {}ms", stage, elapsed.as_millis()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Latency histograms per tick phase, keyed by stage name
+#[derive(Debug, Clone, Default)]
+pub struct StageLatency {
+    stages: BTreeMap<&'static str, LatencyHistogram>,
+}
+
+impl StageLatency {
+    /// Add each phase a tick ran to its stage's histogram
+    pub fn record(&mut self, timings: &TickTimings) {
+        for (stage, elapsed) in timings.stages() {
+            self.stages.entry(stage).or_default().record(elapsed);
+        }
+    }
+
+    /// Percentiles for every stage seen so far
+    pub fn stats(&self) -> BTreeMap<String, LatencyStats> {
+        self.stages
+            .iter()
+            .map(|(stage, histogram)| (stage.to_string(), histogram.stats()))
+            .collect()
+    }
+}
+
+/// Whether a tick took long enough to warn about (over 80% of the interval)
+pub fn is_slow_tick(elapsed: Duration, tick_interval_ms: u64) -> bool {
+    elapsed.as_secs_f64() * 1000.0 > tick_interval_ms as f64 * 0.8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_percentiles() {
+        let mut histogram = LatencyHistogram::default();
+        for ms in (1..=100).rev() {
+            histogram.record(Duration::from_millis(ms));
+        }
+
+        assert_eq!(histogram.percentile(50.0), Some(Duration::from_millis(50)));
+        assert_eq!(histogram.percentile(95.0), Some(Duration::from_millis(95)));
+
+        let stats = histogram.stats();
+        assert_eq!(stats.samples, 100);
+        assert!((stats.p95_ms - 95.0).abs() < 1e-9);
+        assert!((stats.max_ms - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_histogram_rolls_over() {
+        let mut histogram = LatencyHistogram::new(3);
+        for ms in [500, 10, 20, 30] {
+            histogram.record(Duration::from_millis(ms));
+        }
+
+        // The 500ms outlier has been evicted
+        assert_eq!(histogram.len(), 3);
+        assert_eq!(histogram.percentile(100.0), Some(Duration::from_millis(30)));
+        assert_eq!(LatencyHistogram::default().percentile(50.0), None);
+    }
+
+    #[test]
+    fn test_stage_latency_per_phase() {
+        let full_tick = TickTimings {
+            discovery: Some(Duration::from_millis(2)),
+            spot_fetch: Some(Duration::from_millis(1)),
+            book_up: Some(Duration::from_millis(120)),
+            book_down: Some(Duration::from_millis(140)),
+            strategy: Some(Duration::from_millis(5)),
+            logging: Some(Duration::from_millis(1)),
+        };
+        // A tick that bailed out before the books
+        let short_tick = TickTimings {
+            discovery: Some(Duration::from_millis(4)),
+            spot_fetch: Some(Duration::from_millis(3)),
+            ..TickTimings::default()
+        };
+
+        let mut latency = StageLatency::default();
+        latency.record(&full_tick);
+        latency.record(&short_tick);
+
+        let stats = latency.stats();
+        let stages: Vec<&str> = stats.keys().map(String::as_str).collect();
+        assert_eq!(stages, ["book_down", "book_up", "discovery", "logging", "spot_fetch", "strategy"]);
+        assert_eq!(stats["discovery"].samples, 2);
+        assert_eq!(stats["book_up"].samples, 1);
+        assert!((stats["book_down"].max_ms - 140.0).abs() < 1e-9);
+
+        assert_eq!(short_tick.breakdown(), "discovery 4ms, spot_fetch 3ms");
+    }
+
+    #[test]
+    fn test_slow_tick_threshold() {
+        assert!(!is_slow_tick(Duration::from_millis(400), 500));
+        assert!(is_slow_tick(Duration::from_millis(401), 500));
+    }
+}
@@ -0,0 +1,148 @@
+/// Per-strategy sub-accounting for strategies sharing one wallet
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use tracing::info;
+
+use crate::models::ClosedTrade;
+
+/// Notional bankroll and realized P&L of a single strategy
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SubAccount {
+    pub allocated: Decimal,
+    pub realized_pnl: Decimal,
+    pub open_cost: Decimal,
+    pub trades: u64,
+}
+
+impl SubAccount {
+    /// Cash this strategy can still commit to new positions
+    pub fn available(&self) -> Decimal {
+        self.allocated + self.realized_pnl - self.open_cost
+    }
+}
+
+/// Ledger of sub-accounts keyed by strategy name
+#[derive(Debug, Clone, Default)]
+pub struct SubAccountLedger {
+    accounts: HashMap<String, SubAccount>,
+}
+
+impl SubAccountLedger {
+    /// Create a ledger from (strategy name, allocated capital) pairs
+    pub fn new(allocations: &[(String, Decimal)]) -> Self {
+        let accounts = allocations
+            .iter()
+            .map(|(name, allocated)| {
+                (
+                    name.clone(),
+                    SubAccount {
+                        allocated: *allocated,
+                        ..SubAccount::default()
+                    },
+                )
+            })
+            .collect();
+
+        Self { accounts }
+    }
+
+    /// Whether any sub-accounts are configured
+    pub fn is_enabled(&self) -> bool {
+        !self.accounts.is_empty()
+    }
+
+    /// Get a strategy's sub-account
+    pub fn account(&self, strategy: &str) -> Option<&SubAccount> {
+        self.accounts.get(strategy)
+    }
+
+    /// Cash available to a strategy, or None if it has no sub-account
+    pub fn available(&self, strategy: &str) -> Option<Decimal> {
+        self.accounts.get(strategy).map(SubAccount::available)
+    }
+
+    /// Update the cost basis of a strategy's open position
+    pub fn set_open_cost(&mut self, strategy: &str, cost: Decimal) {
+        if let Some(account) = self.accounts.get_mut(strategy) {
+            account.open_cost = cost;
+        }
+    }
+
+    /// Attribute a closed trade's P&L to a strategy
+    pub fn record_trade(&mut self, strategy: &str, trade: &ClosedTrade) {
+        if let Some(account) = self.accounts.get_mut(strategy) {
+            account.realized_pnl += trade.pnl;
+            account.trades += 1;
+        }
+    }
+
+    /// Print per-strategy balances
+    pub fn print_summary(&self) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let mut names: Vec<&String> = self.accounts.keys().collect();
+        names.sort();
+
+        info!("📒 SUB-ACCOUNTS");
+        for name in names {
+            let account = &self.accounts[name];
+            info!(
+                "   {}: Allocated ${:.2} | Realized P&L ${:.2} | Trades {} | Available ${:.2}",
+                name,
+                account.allocated,
+                account.realized_pnl,
+                account.trades,
+                account.available()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ExitReason;
+
+    fn trade(pnl: i64) -> ClosedTrade {
+        ClosedTrade {
+            token_id: "token".to_string(),
+            entry_price: Decimal::ONE,
+            exit_price: Decimal::ONE,
+            size: Decimal::ONE,
+            pnl: Decimal::from(pnl),
+            entry_time: 0,
+            exit_time: 0,
+            exit_reason: ExitReason::Manual,
+            committed_time: 0,
+        }
+    }
+
+    #[test]
+    fn test_strategies_tracked_separately() {
+        let mut ledger = SubAccountLedger::new(&[
+            ("vulture".to_string(), Decimal::from(60)),
+            ("mm".to_string(), Decimal::from(40)),
+        ]);
+
+        ledger.record_trade("vulture", &trade(5));
+        ledger.record_trade("mm", &trade(-3));
+        ledger.record_trade("mm", &trade(1));
+        ledger.set_open_cost("vulture", Decimal::from(20));
+
+        let vulture = ledger.account("vulture").unwrap();
+        assert_eq!(vulture.realized_pnl, Decimal::from(5));
+        assert_eq!(vulture.trades, 1);
+        assert_eq!(ledger.available("vulture"), Some(Decimal::from(45)));
+
+        let mm = ledger.account("mm").unwrap();
+        assert_eq!(mm.realized_pnl, Decimal::from(-2));
+        assert_eq!(mm.trades, 2);
+        assert_eq!(ledger.available("mm"), Some(Decimal::from(38)));
+
+        // Unknown strategies are not tracked
+        ledger.record_trade("other", &trade(100));
+        assert_eq!(ledger.available("other"), None);
+    }
+}
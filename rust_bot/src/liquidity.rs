@@ -0,0 +1,140 @@
+/// Order book liveness tracking, used as a liquidity proxy
+use rust_decimal::prelude::*;
+use rust_decimal::Decimal;
+use std::collections::{HashMap, VecDeque};
+
+/// Top-of-book activity for one token
+struct TokenActivity {
+    first_seen: i64,
+    last_top: (Option<Decimal>, Option<Decimal>),
+    updates: VecDeque<i64>, // timestamps (ms) of top-of-book changes
+}
+
+/// Tracks how often each token's best bid/ask changes
+///
+/// A book that stops moving belongs to a dying or illiquid market, where
+/// fills are unlikely and quotes are stale.
+pub struct BookActivityMonitor {
+    window_ms: i64,
+    min_rate: f64, // updates per second, 0 = disabled
+    tokens: HashMap<String, TokenActivity>,
+}
+
+impl BookActivityMonitor {
+    /// Create a monitor requiring `min_rate` updates/sec over `window_seconds`
+    pub fn new(window_seconds: i64, min_rate: Decimal) -> Self {
+        Self {
+            window_ms: window_seconds * 1000,
+            min_rate: min_rate.to_f64().unwrap_or(0.0),
+            tokens: HashMap::new(),
+        }
+    }
+
+    /// Record a polled top of book for a token
+    pub fn record(&mut self, token_id: &str, best_bid: Option<Decimal>, best_ask: Option<Decimal>, now_ms: i64) {
+        let activity = self
+            .tokens
+            .entry(token_id.to_string())
+            .or_insert_with(|| TokenActivity {
+                first_seen: now_ms,
+                last_top: (best_bid, best_ask),
+                updates: VecDeque::new(),
+            });
+
+        if activity.last_top != (best_bid, best_ask) {
+            activity.last_top = (best_bid, best_ask);
+            activity.updates.push_back(now_ms);
+        }
+
+        while let Some(&oldest) = activity.updates.front() {
+            if now_ms - oldest > self.window_ms {
+                activity.updates.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Updates per second over the window, or None until a full window is observed
+    pub fn update_rate(&self, token_id: &str, now_ms: i64) -> Option<f64> {
+        let activity = self.tokens.get(token_id)?;
+        if self.window_ms <= 0 || now_ms - activity.first_seen < self.window_ms {
+            return None;
+        }
+
+        let recent = activity
+            .updates
+            .iter()
+            .filter(|&&ts| now_ms - ts <= self.window_ms)
+            .count();
+        Some(recent as f64 * 1000.0 / self.window_ms as f64)
+    }
+
+    /// Whether the token's book has gone too quiet to trade
+    pub fn is_stale(&self, token_id: &str, now_ms: i64) -> bool {
+        if self.min_rate <= 0.0 {
+            return false;
+        }
+
+        matches!(self.update_rate(token_id, now_ms), Some(rate) if rate < self.min_rate)
+    }
+
+    /// Forget all tokens (on market rotation)
+    pub fn clear(&mut self) {
+        self.tokens.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    /// Poll a book every 500ms for 10s, moving the quote every `every` polls
+    fn poll(monitor: &mut BookActivityMonitor, token: &str, every: usize) -> i64 {
+        let mut bid = dec("0.40");
+        let mut now = 0;
+        for i in 0..=20 {
+            now = i as i64 * 500;
+            if i % every == 0 {
+                bid += dec("0.01");
+            }
+            monitor.record(token, Some(bid), Some(bid + dec("0.02")), now);
+        }
+        now
+    }
+
+    #[test]
+    fn test_low_update_rate_blocks_entries() {
+        let mut monitor = BookActivityMonitor::new(10, dec("0.5"));
+
+        // Quote moves once every 5s: 0.2 updates/sec
+        let now = poll(&mut monitor, "quiet", 10);
+        assert!(monitor.update_rate("quiet", now).unwrap() < 0.5);
+        assert!(monitor.is_stale("quiet", now));
+
+        // Quote moves every second: 1 update/sec
+        let now = poll(&mut monitor, "busy", 2);
+        assert!(monitor.update_rate("busy", now).unwrap() >= 0.5);
+        assert!(!monitor.is_stale("busy", now));
+    }
+
+    #[test]
+    fn test_needs_full_window() {
+        let mut monitor = BookActivityMonitor::new(10, dec("0.5"));
+        monitor.record("token", Some(dec("0.40")), Some(dec("0.42")), 0);
+
+        // A frozen book is not judged until a full window has passed
+        monitor.record("token", Some(dec("0.40")), Some(dec("0.42")), 5_000);
+        assert!(!monitor.is_stale("token", 5_000));
+        monitor.record("token", Some(dec("0.40")), Some(dec("0.42")), 10_000);
+        assert!(monitor.is_stale("token", 10_000));
+
+        // Disabled with a zero floor
+        let disabled = BookActivityMonitor::new(10, Decimal::ZERO);
+        assert!(!disabled.is_stale("token", 10_000));
+    }
+}
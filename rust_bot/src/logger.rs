@@ -1,42 +1,242 @@
 /// Session logging and data persistence
-use anyhow::Result;
+use anyhow::{Context, Result};
+use chrono_tz::Tz;
+use rust_decimal::prelude::*;
 use rust_decimal::Decimal;
 use serde_json;
+use std::collections::BTreeMap;
 use std::sync::Arc;
-use tokio::fs::File;
+use tokio::fs::{File, OpenOptions};
 use tokio::io::AsyncWriteExt;
-use tokio::sync::RwLock;
-use tracing::info;
+use tokio::sync::{Mutex, RwLock};
+use tracing::{debug, info, warn};
 
-use crate::models::{SessionSummary, TickData};
+use crate::db::SessionDb;
+use crate::latency::{LatencyHistogram, StageLatency, TickTimings};
+use crate::models::{
+    ClosedTrade, EquityPoint, EquityStats, FillReport, FillStats, SessionSummary, TickData, TradeStats,
+};
+
+/// Emit a tick as a structured event, at debug level when `quiet`
+///
+/// Values are attached as fields rather than formatted into the message, so
+/// `LOG_FORMAT=json` produces queryable keys.
+pub fn trace_tick(tick: &TickData, quiet: bool) {
+    let num = |value: Decimal| value.to_f64();
+    macro_rules! tick_event {
+        ($level:ident) => {
+            $level!(
+                tick_number = tick.tick_number,
+                market_slug = %tick.market_slug,
+                state = %tick.state,
+                spot_price = num(tick.spot_price),
+                strike_price = num(tick.strike_price),
+                fair_value = num(tick.fair_value),
+                target_buy_price = num(tick.target_buy_price),
+                best_bid = tick.best_bid.and_then(num),
+                best_ask = tick.best_ask.and_then(num),
+                minutes_remaining = tick.minutes_remaining,
+                unrealized_pnl = tick.unrealized_pnl.and_then(num),
+                "🔍 STATE: {}",
+                tick.state
+            )
+        };
+    }
+
+    if quiet {
+        tick_event!(debug);
+    } else {
+        tick_event!(info);
+    }
+}
 
 /// Session logger for recording tick data
 pub struct SessionLogger {
     session_id: String,
     start_time: i64,
     ticks: Arc<RwLock<Vec<TickData>>>,
+    tick_count: Arc<RwLock<u64>>,
+    tick_log: Option<TickLog>,
+    trades: Arc<RwLock<Vec<ClosedTrade>>>,
+    tick_latency: Arc<RwLock<LatencyHistogram>>,
+    stage_latency: Arc<RwLock<StageLatency>>,
+    fill_stats: Arc<RwLock<BTreeMap<String, FillStats>>>, // per market slug
     markets_traded: Arc<RwLock<u64>>,
+    note: Arc<RwLock<Option<String>>>,
+    equity_curve: Arc<RwLock<Vec<EquityPoint>>>,
+    paper_fill_mismatches: Arc<RwLock<u64>>,
+    db: Option<SessionDb>, // DB_PATH: ticks, trades and the summary go here instead of JSON
+    timezone: Tz,          // DISPLAY_TIMEZONE for the session ID and summary times
+}
+
+/// Append-only JSONL file receiving each tick as it is logged
+struct TickLog {
+    path: String,
+    file: Mutex<Option<File>>, // opened on the first tick
 }
 
 impl SessionLogger {
     /// Create a new session logger
-    pub fn new() -> Self {
-        let session_id = chrono::Utc::now().format("%Y%m%d_%H%M%S").to_string();
+    ///
+    /// With `incremental`, ticks are appended to `session_{id}.jsonl` as they
+    /// arrive instead of being held in memory until shutdown, so a crash
+    /// loses nothing already logged. The session ID is stamped in `timezone`.
+    pub fn new(incremental: bool, timezone: Tz) -> Self {
+        let session_id = chrono::Utc::now()
+            .with_timezone(&timezone)
+            .format("%Y%m%d_%H%M%S")
+            .to_string();
+        let tick_log_path = incremental.then(|| format!("session_{}.jsonl", session_id));
+        Self {
+            timezone,
+            ..Self::with_tick_log(session_id, tick_log_path)
+        }
+    }
+
+    fn with_tick_log(session_id: String, tick_log_path: Option<String>) -> Self {
         let start_time = chrono::Utc::now().timestamp_millis();
 
         info!("📊 Session started: {}", session_id);
+        if let Some(path) = &tick_log_path {
+            info!("📝 Logging ticks incrementally to: {}", path);
+        }
 
         Self {
             session_id,
             start_time,
             ticks: Arc::new(RwLock::new(Vec::new())),
+            tick_count: Arc::new(RwLock::new(0)),
+            tick_log: tick_log_path.map(|path| TickLog {
+                path,
+                file: Mutex::new(None),
+            }),
+            trades: Arc::new(RwLock::new(Vec::new())),
+            tick_latency: Arc::new(RwLock::new(LatencyHistogram::default())),
+            stage_latency: Arc::new(RwLock::new(StageLatency::default())),
+            fill_stats: Arc::new(RwLock::new(BTreeMap::new())),
             markets_traded: Arc::new(RwLock::new(0)),
+            note: Arc::new(RwLock::new(None)),
+            equity_curve: Arc::new(RwLock::new(Vec::new())),
+            paper_fill_mismatches: Arc::new(RwLock::new(0)),
+            db: None,
+            timezone: Tz::UTC,
         }
     }
 
+    /// Write ticks, trades and the summary to a SQLite database instead of JSON files
+    pub fn with_db(mut self, db: SessionDb) -> Self {
+        self.db = Some(db);
+        self
+    }
+
     /// Log a tick
     pub async fn log_tick(&self, tick_data: TickData) {
-        self.ticks.write().await.push(tick_data);
+        *self.tick_count.write().await += 1;
+        if tick_data.paper_fill_check == Some(false) {
+            *self.paper_fill_mismatches.write().await += 1;
+        }
+
+        if let Some(db) = &self.db {
+            if let Err(e) = db.insert_tick(&self.session_id, tick_data).await {
+                warn!("⚠️ Failed to insert tick into database: {:#}", e);
+            }
+            return;
+        }
+
+        match &self.tick_log {
+            Some(log) => {
+                if let Err(e) = Self::append_tick(log, &tick_data).await {
+                    warn!("⚠️ Failed to append tick to {}: {:#}", log.path, e);
+                }
+            }
+            None => self.ticks.write().await.push(tick_data),
+        }
+    }
+
+    async fn append_tick(log: &TickLog, tick_data: &TickData) -> Result<()> {
+        let mut line = serde_json::to_string(tick_data)?;
+        line.push('\n');
+
+        let mut file = log.file.lock().await;
+        if file.is_none() {
+            let opened = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&log.path)
+                .await
+                .with_context(|| format!("Failed to open {}", log.path))?;
+            *file = Some(opened);
+        }
+
+        let file = file.as_mut().expect("tick log opened above");
+        file.write_all(line.as_bytes()).await?;
+        file.flush().await?;
+        Ok(())
+    }
+
+    /// Record a closed trade
+    pub async fn log_trade(&self, trade: ClosedTrade) {
+        info!(
+            "📒 Trade closed ({:?}): {:.4} -> {:.4} x {} | P&L: ${:.2}",
+            trade.exit_reason, trade.entry_price, trade.exit_price, trade.size, trade.pnl
+        );
+        if let Some(apr) = trade.lockup_apr() {
+            info!("   Capital locked {:.0}s | Effective APR: {:.1}%", trade.lockup_seconds(), apr * 100.0);
+        }
+        if let Some(db) = &self.db {
+            if let Err(e) = db.insert_trade(&self.session_id, trade.clone()).await {
+                warn!("⚠️ Failed to insert trade into database: {:#}", e);
+            }
+        }
+        self.trades.write().await.push(trade);
+    }
+
+    /// Compute statistics over the trades recorded so far
+    pub async fn trade_stats(&self) -> TradeStats {
+        TradeStats::from_trades(&self.trades.read().await)
+    }
+
+    /// Record order fills for a market
+    pub async fn log_fill_stats(&self, market_slug: &str, stats: FillStats) {
+        if stats == FillStats::default() {
+            return;
+        }
+        self.fill_stats
+            .write()
+            .await
+            .entry(market_slug.to_string())
+            .or_default()
+            .merge(stats);
+    }
+
+    /// Fill rate over the markets recorded so far
+    pub async fn fill_report(&self) -> FillReport {
+        let per_market = self.fill_stats.read().await.clone();
+        let mut overall = FillStats::default();
+        for stats in per_market.values() {
+            overall.merge(*stats);
+        }
+
+        FillReport {
+            overall,
+            fill_rate: overall.fill_rate(),
+            per_market,
+        }
+    }
+
+    /// Record how long a tick took
+    pub async fn record_tick_latency(&self, duration: std::time::Duration) {
+        self.tick_latency.write().await.record(duration);
+    }
+
+    /// Record how long each phase of a tick took
+    pub async fn record_stage_latency(&self, timings: &TickTimings) {
+        self.stage_latency.write().await.record(timings);
+    }
+
+    /// Record session equity (cash plus the position marked to market) at a tick
+    pub async fn record_equity(&self, timestamp: i64, equity: Decimal) {
+        self.equity_curve.write().await.push(EquityPoint { timestamp, equity });
     }
 
     /// Increment markets traded counter
@@ -44,51 +244,435 @@ impl SessionLogger {
         *self.markets_traded.write().await += 1;
     }
 
-    /// Flush session data to JSON file
+    /// Attach a free-text journal note to the session summary
+    pub async fn set_note(&self, note: &str) {
+        let note = note.trim();
+        *self.note.write().await = (!note.is_empty()).then(|| note.to_string());
+    }
+
+    /// Flush session data to JSON file, or the summary row with DB_PATH
     pub async fn flush(
         &self,
         total_pnl: Decimal,
         final_cash: Decimal,
     ) -> Result<()> {
+        let summary = self.summary(total_pnl, final_cash).await;
+
+        if let Some(db) = &self.db {
+            db.insert_summary(summary.clone()).await?;
+            info!("🗄️ Session summary saved to database");
+        } else {
+            // Serialize to JSON
+            let json = serde_json::to_string_pretty(&summary)?;
+
+            // Write to file
+            let filename = format!("session_{}.json", self.session_id);
+            let mut file = File::create(&filename).await?;
+            file.write_all(json.as_bytes()).await?;
+
+            info!("📄 Session data saved to: {}", filename);
+        }
+        self.print_summary(&summary);
+
+        Ok(())
+    }
+
+    /// Build the session summary as of now
+    async fn summary(&self, total_pnl: Decimal, final_cash: Decimal) -> SessionSummary {
         let end_time = chrono::Utc::now().timestamp_millis();
         let duration_seconds = (end_time - self.start_time) / 1000;
+        // In incremental mode the ticks live in the JSONL file, not the summary
         let ticks = self.ticks.read().await.clone();
+        let total_ticks = *self.tick_count.read().await;
+        let trades = self.trades.read().await.clone();
+        let trade_stats = self.trade_stats().await;
+        let fill_stats = self.fill_report().await;
+        let tick_latency = self.tick_latency.read().await.stats();
+        let stage_latency = self.stage_latency.read().await.stats();
         let markets_traded = *self.markets_traded.read().await;
+        let equity_curve = self.equity_curve.read().await.clone();
 
-        let summary = SessionSummary {
+        SessionSummary {
             session_id: self.session_id.clone(),
             start_time: self.start_time,
             end_time,
             duration_seconds,
-            total_ticks: ticks.len() as u64,
+            total_ticks,
             markets_traded,
             total_pnl,
             final_cash,
+            trade_stats,
+            fill_stats,
+            trades,
+            tick_latency,
+            stage_latency,
+            note: self.note.read().await.clone(),
+            equity_stats: EquityStats::from_curve(&equity_curve),
+            equity_curve,
+            paper_fill_mismatches: *self.paper_fill_mismatches.read().await,
             ticks,
-        };
-
-        // Serialize to JSON
-        let json = serde_json::to_string_pretty(&summary)?;
-
-        // Write to file
-        let filename = format!("session_{}.json", self.session_id);
-        let mut file = File::create(&filename).await?;
-        file.write_all(json.as_bytes()).await?;
-
-        info!("📄 Session data saved to: {}", filename);
-        self.print_summary(&summary);
+        }
+    }
 
-        Ok(())
+    /// Format Unix milliseconds for display in the session's timezone
+    fn format_time(&self, millis: i64) -> String {
+        chrono::DateTime::from_timestamp_millis(millis)
+            .map(|time| time.with_timezone(&self.timezone).format("%Y-%m-%d %H:%M:%S %Z").to_string())
+            .unwrap_or_else(|| millis.to_string())
     }
 
     /// Print session summary
     fn print_summary(&self, summary: &SessionSummary) {
         info!("📊 SESSION SUMMARY");
         info!("   Session ID: {}", summary.session_id);
+        if let Some(note) = &summary.note {
+            info!("   Note: {}", note);
+        }
+        info!(
+            "   Started: {} | Ended: {}",
+            self.format_time(summary.start_time),
+            self.format_time(summary.end_time)
+        );
         info!("   Duration: {}s", summary.duration_seconds);
         info!("   Total Ticks: {}", summary.total_ticks);
         info!("   Markets Traded: {}", summary.markets_traded);
         info!("   Total P&L: ${:.2}", summary.total_pnl);
         info!("   Final Cash: ${:.2}", summary.final_cash);
+        if !summary.equity_curve.is_empty() {
+            let equity = &summary.equity_stats;
+            info!(
+                "   Peak Equity: ${:.2} | Max Drawdown: ${:.2} ({:.1}%)",
+                equity.peak_equity,
+                equity.max_drawdown,
+                equity.max_drawdown_pct * 100.0
+            );
+        }
+
+        if summary.paper_fill_mismatches > 0 {
+            info!(
+                "   Paper Fills Beyond Live Depth: {} ticks",
+                summary.paper_fill_mismatches
+            );
+        }
+
+        let stats = &summary.trade_stats;
+        info!("   Trades: {} ({} wins / {} losses)", stats.total_trades, stats.wins, stats.losses);
+        info!("   Win Rate: {:.1}%", stats.win_rate * 100.0);
+        info!("   Avg Win: ${:.2} | Avg Loss: ${:.2}", stats.avg_win, stats.avg_loss);
+        match stats.profit_factor {
+            Some(pf) => info!("   Profit Factor: {:.2}", pf),
+            None => info!("   Profit Factor: n/a"),
+        }
+        info!("   Avg Holding Time: {:.1}s", stats.avg_holding_seconds);
+        match stats.lockup_apr {
+            Some(apr) => info!(
+                "   Capital Lockup: {:.1}s avg | Effective APR: {:.1}%",
+                stats.avg_lockup_seconds,
+                apr * 100.0
+            ),
+            None => info!("   Capital Lockup: n/a"),
+        }
+
+        let fills = &summary.fill_stats;
+        info!(
+            "   Fill Rate: {:.1}% ({} of {} limit orders)",
+            fills.fill_rate * 100.0,
+            fills.overall.filled,
+            fills.overall.placed
+        );
+        for (slug, market) in &fills.per_market {
+            info!("     {}: {:.1}% ({}/{})", slug, market.fill_rate() * 100.0, market.filled, market.placed);
+        }
+        if fills.overall.market_placed > 0 {
+            info!(
+                "   Market Fill Rate: {:.1}% ({} of {} market orders)",
+                fills.overall.market_fill_rate() * 100.0,
+                fills.overall.market_filled,
+                fills.overall.market_placed
+            );
+        }
+        info!(
+            "   Tick Latency: p50 {:.0}ms | p95 {:.0}ms | max {:.0}ms",
+            summary.tick_latency.p50_ms, summary.tick_latency.p95_ms, summary.tick_latency.max_ms
+        );
+        for (stage, latency) in &summary.stage_latency {
+            info!(
+                "     {}: p50 {:.0}ms | p95 {:.0}ms | max {:.0}ms",
+                stage, latency.p50_ms, latency.p95_ms, latency.max_ms
+            );
+        }
+    }
+}
+
+/// Log output captured in memory, for tests that assert on what was logged
+#[cfg(test)]
+#[derive(Clone, Default)]
+pub struct Capture(Arc<std::sync::Mutex<Vec<u8>>>);
+
+#[cfg(test)]
+impl std::io::Write for Capture {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+impl Capture {
+    pub fn output(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ExitReason;
+    use std::str::FromStr;
+
+    fn trade(entry: &str, exit: &str, size: i64, held_secs: i64) -> ClosedTrade {
+        let entry_price = Decimal::from_str(entry).unwrap();
+        let exit_price = Decimal::from_str(exit).unwrap();
+        let size = Decimal::from(size);
+        ClosedTrade {
+            token_id: "token".to_string(),
+            entry_price,
+            exit_price,
+            size,
+            pnl: (exit_price - entry_price) * size,
+            entry_time: 0,
+            exit_time: held_secs * 1000,
+            exit_reason: ExitReason::TakeProfit,
+            committed_time: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_trade_stats() {
+        let logger = SessionLogger::new(false, Tz::UTC);
+        logger.log_trade(trade("0.40", "0.45", 100, 30)).await; // +5.00
+        logger.log_trade(trade("0.50", "0.52", 100, 60)).await; // +2.00
+        logger.log_trade(trade("0.60", "0.50", 20, 90)).await;  // -2.00
+
+        let stats = logger.trade_stats().await;
+        assert_eq!(stats.total_trades, 3);
+        assert_eq!(stats.wins, 2);
+        assert_eq!(stats.losses, 1);
+        assert!((stats.win_rate - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(stats.avg_win, Decimal::from_str("3.5").unwrap());
+        assert_eq!(stats.avg_loss, Decimal::from(-2));
+        assert_eq!(stats.profit_factor, Some(Decimal::from_str("3.5").unwrap()));
+        assert!((stats.avg_holding_seconds - 60.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_lockup_apr() {
+        // $50 committed for 30 minutes resting plus 1 hour held, returning $5 (10%)
+        let mut held = trade("0.50", "0.55", 100, 0);
+        held.committed_time = 1_700_000_000_000;
+        held.entry_time = 1_700_001_800_000;
+        held.exit_time = 1_700_005_400_000;
+        assert!((held.holding_seconds() - 3_600.0).abs() < 1e-9);
+        assert!((held.lockup_seconds() - 5_400.0).abs() < 1e-9);
+
+        let expected = 0.10 * 8_760.0 / 1.5; // 10% over 1.5 hours, annualized
+        assert!((held.lockup_apr().unwrap() - expected).abs() < 1e-6);
+
+        // The session figure weights by capital and time: a second, identical trade leaves it unchanged
+        let stats = TradeStats::from_trades(&[held.clone(), held]);
+        assert!((stats.lockup_apr.unwrap() - expected).abs() < 1e-6);
+        assert!((stats.avg_lockup_seconds - 5_400.0).abs() < 1e-9);
+    }
+
+    fn tick(tick_number: u64) -> TickData {
+        TickData {
+            timestamp: tick_number as i64 * 1000,
+            tick_number,
+            market_slug: "btc-updown-15m-test".to_string(),
+            spot_price: Decimal::from(98_500),
+            strike_price: Decimal::from(98_400),
+            fair_value: Decimal::from_str("0.56").unwrap(),
+            target_buy_price: Decimal::from_str("0.41").unwrap(),
+            best_bid: Some(Decimal::from_str("0.55").unwrap()),
+            best_ask: Some(Decimal::from_str("0.57").unwrap()),
+            spread: Some(Decimal::from_str("0.02").unwrap()),
+            minutes_remaining: 12.5,
+            state: "Scanning".to_string(),
+            unrealized_pnl: None,
+            paper_fill_check: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_incremental_tick_log() {
+        let path = std::env::temp_dir().join(format!("vulture_ticks_{}.jsonl", std::process::id()));
+        let path = path.to_string_lossy().to_string();
+        let _ = std::fs::remove_file(&path);
+
+        let logger = SessionLogger::with_tick_log("test".to_string(), Some(path.clone()));
+        for n in 1..=3 {
+            logger.log_tick(tick(n)).await;
+        }
+
+        // Each tick is on disk as soon as it is logged, not kept in memory
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 3);
+        let parsed: Vec<TickData> = lines.iter().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(parsed.iter().map(|t| t.tick_number).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(parsed[0].market_slug, "btc-updown-15m-test");
+        assert_eq!(parsed[2].best_ask, Some(Decimal::from_str("0.57").unwrap()));
+        assert!(logger.ticks.read().await.is_empty());
+        assert_eq!(*logger.tick_count.read().await, 3);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_db_logging() {
+        let path = std::env::temp_dir().join(format!("vulture_session_{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let db = SessionDb::open(path.to_str().unwrap()).unwrap();
+
+        let logger = SessionLogger::with_tick_log("test".to_string(), None).with_db(db);
+        for n in 1..=5 {
+            logger.log_tick(tick(n)).await;
+        }
+        logger.log_trade(trade("0.40", "0.45", 100, 30)).await;
+        logger.log_trade(trade("0.60", "0.50", 20, 90)).await;
+        logger.flush(Decimal::from(3), Decimal::from(103)).await.unwrap();
+
+        // Rows land in the database, keyed by session, instead of memory
+        let db = logger.db.as_ref().unwrap();
+        assert_eq!(db.count("ticks", "test"), 5);
+        assert_eq!(db.count("trades", "test"), 2);
+        assert_eq!(db.count("sessions", "test"), 1);
+        assert_eq!(db.count("ticks", "other"), 0);
+        assert!(logger.ticks.read().await.is_empty());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_db_migrates_old_ticks_table() {
+        let path = std::env::temp_dir().join(format!("vulture_migrate_{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        // A ticks table from before unrealized P&L was logged
+        rusqlite::Connection::open(&path)
+            .unwrap()
+            .execute_batch(
+                "CREATE TABLE ticks (session_id TEXT NOT NULL, timestamp INTEGER NOT NULL,
+                    tick_number INTEGER NOT NULL, market_slug TEXT NOT NULL, spot_price REAL NOT NULL,
+                    strike_price REAL NOT NULL, fair_value REAL NOT NULL, target_buy_price REAL NOT NULL,
+                    best_bid REAL, best_ask REAL, spread REAL, minutes_remaining REAL NOT NULL, state TEXT NOT NULL)",
+            )
+            .unwrap();
+
+        // Opening adds the column, and reopening leaves it alone
+        SessionDb::open(path.to_str().unwrap()).unwrap();
+        let db = SessionDb::open(path.to_str().unwrap()).unwrap();
+        let logger = SessionLogger::with_tick_log("test".to_string(), None).with_db(db);
+        logger.log_tick(tick(1)).await;
+        assert_eq!(logger.db.as_ref().unwrap().count("ticks", "test"), 1);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_paper_fill_mismatches_counted() {
+        let logger = SessionLogger::new(false, Tz::UTC);
+        for check in [None, Some(true), Some(false), Some(false)] {
+            logger.log_tick(TickData { paper_fill_check: check, ..tick(1) }).await;
+        }
+        let summary = logger.summary(Decimal::ZERO, Decimal::from(100)).await;
+        assert_eq!(summary.paper_fill_mismatches, 2);
+    }
+
+    #[tokio::test]
+    async fn test_note_in_summary() {
+        let logger = SessionLogger::new(false, Tz::UTC);
+        let summary = serde_json::to_value(logger.summary(Decimal::ZERO, Decimal::from(100)).await).unwrap();
+        assert_eq!(summary["note"], serde_json::Value::Null);
+
+        logger.set_note("  Choppy open after CPI; widened PANIC_DISCOUNT to 0.10 \n").await;
+        let summary = serde_json::to_value(logger.summary(Decimal::ZERO, Decimal::from(100)).await).unwrap();
+        assert_eq!(summary["note"], "Choppy open after CPI; widened PANIC_DISCOUNT to 0.10");
+    }
+
+    #[tokio::test]
+    async fn test_equity_curve_and_drawdown() {
+        let logger = SessionLogger::new(false, Tz::UTC);
+
+        // $95 cash holding 10 shares: equity follows the bid
+        let cash = Decimal::from(95);
+        let shares = Decimal::from(10);
+        for (n, bid) in ["0.50", "0.90", "0.60", "0.75", "0.30", "0.40"].iter().enumerate() {
+            let equity = cash + shares * Decimal::from_str(bid).unwrap();
+            logger.record_equity(n as i64 * 1000, equity).await;
+        }
+
+        let summary = logger.summary(Decimal::ZERO, cash).await;
+        let curve: Vec<Decimal> = summary.equity_curve.iter().map(|point| point.equity).collect();
+        let expected: Vec<Decimal> = ["100", "104", "101", "102.5", "98", "99"]
+            .iter()
+            .map(|equity| Decimal::from_str(equity).unwrap())
+            .collect();
+        assert_eq!(curve, expected);
+
+        // 104 -> 98 is the deepest fall, not 104 -> 101 or 102.5 -> 98
+        assert_eq!(summary.equity_stats.peak_equity, Decimal::from(104));
+        assert_eq!(summary.equity_stats.max_drawdown, Decimal::from(6));
+        assert!((summary.equity_stats.max_drawdown_pct - 6.0 / 104.0).abs() < 1e-9);
+
+        assert_eq!(EquityStats::from_curve(&[]), EquityStats::default());
+    }
+
+    #[test]
+    fn test_tick_event_json_fields() {
+        let capture = Capture::default();
+        let writer = capture.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .json()
+            .flatten_event(true)
+            .with_writer(move || writer.clone())
+            .finish();
+        tracing::subscriber::with_default(subscriber, || trace_tick(&tick(7), false));
+
+        let event: serde_json::Value = serde_json::from_str(capture.output().trim()).unwrap();
+        assert_eq!(event["tick_number"], 7);
+        assert_eq!(event["state"], "Scanning");
+        assert_eq!(event["market_slug"], "btc-updown-15m-test");
+        assert_eq!(event["spot_price"], 98500.0);
+        assert_eq!(event["fair_value"], 0.56);
+        assert_eq!(event["best_ask"], 0.57);
+        assert_eq!(event["minutes_remaining"], 12.5);
+        assert_eq!(event["message"], "🔍 STATE: Scanning");
+    }
+
+    #[test]
+    fn test_quiet_tick_event_below_info() {
+        let capture_at = |level: tracing::Level| {
+            let capture = Capture::default();
+            let writer = capture.clone();
+            let subscriber = tracing_subscriber::fmt()
+                .json()
+                .flatten_event(true)
+                .with_max_level(level)
+                .with_writer(move || writer.clone())
+                .finish();
+            tracing::subscriber::with_default(subscriber, || trace_tick(&tick(7), true));
+            capture.output()
+        };
+
+        // Quiet: nothing at info, the full event at debug
+        assert!(capture_at(tracing::Level::INFO).is_empty());
+        let event: serde_json::Value = serde_json::from_str(capture_at(tracing::Level::DEBUG).trim()).unwrap();
+        assert_eq!(event["level"], "DEBUG");
+        assert_eq!(event["spot_price"], 98500.0);
     }
 }
@@ -1,56 +1,186 @@
 /// High-performance Polymarket trading bot in Rust using polyfill-rs
+mod annotations;
+mod backoff;
+mod binance;
+mod cli;
 mod config;
+mod db;
+mod deadman;
+mod diagnostics;
+mod health;
+mod http_client;
+mod latency;
+mod ledger;
+mod liquidity;
 mod logger;
+mod market_making;
 mod models;
+mod notify;
+#[cfg(feature = "scraper")]
 mod polymarket_price;
+mod polymarket_price_simple;
+mod price_source;
 mod quant;
+mod rate_limit;
+mod risk;
+mod simulated_price;
 mod slug_oracle;
+mod smoothing;
 mod trading;
+mod user_feed;
+mod volatility;
 mod wallet;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use clap::Parser;
+use rand::{Rng, SeedableRng};
 use rust_decimal::Decimal;
-use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::signal;
-use tokio::time::{interval, Duration};
-use tracing::{error, info, warn};
+use tokio::time::Duration;
+use tracing::{debug, error, info, warn};
 
 use config::BotConfig;
+use deadman::DeadMansSwitch;
+use diagnostics::AgreementMonitor;
+use health::{HealthStatus, SharedHealth};
+use latency::TickTimings;
+use ledger::SubAccountLedger;
+use liquidity::BookActivityMonitor;
 use logger::SessionLogger;
-use models::{BotState, MarketInfo, TickData};
-use polymarket_price::PolymarketPriceService;
+use market_making::{Quote, Quotes};
+use models::{
+    BotState, ExecutionMode, ExitReason, LogFormat, MarketInfo, ReconcileMode, SizingMode, Strategy, TickData, TpSlMode,
+    VolSource,
+};
+use notify::{BotEvent, NotificationSink};
+use price_source::{PriceSource, PriceSourceKind, ReferenceBook};
 use quant::QuantEngine;
+use rate_limit::RateLimiter;
+use risk::RiskManager;
 use slug_oracle::SlugOracle;
-use trading::TradingService;
+use smoothing::SpotEma;
+use trading::{TradingError, TradingResult, TradingService};
+use volatility::VolatilityEstimator;
 use wallet::WalletService;
 
+/// Exit sells `flatten` sends before giving up and alerting
+const FLATTEN_ATTEMPTS: usize = 2;
+
+/// Share difference reconciliation treats as rounding dust rather than drift
+const RECONCILE_TOLERANCE: Decimal = Decimal::from_parts(1, 0, 0, false, 2);
+
+/// Spot-vs-reference-book gap worth a warning, as a fraction of price (0.1%)
+const MAX_REFERENCE_DEVIATION: Decimal = Decimal::from_parts(1, 0, 0, false, 3);
+
+/// Log a routine per-tick line: at info, or at debug in quiet mode
+macro_rules! routine {
+    ($quiet:expr, $($arg:tt)+) => {
+        if $quiet {
+            debug!($($arg)+)
+        } else {
+            info!($($arg)+)
+        }
+    };
+}
+
+/// Time until the next tick: `tick_interval` randomized by ±`jitter` ms
+fn jittered_tick_interval<R: Rng>(tick_interval: u64, jitter: u64, rng: &mut R) -> Duration {
+    if jitter == 0 {
+        return Duration::from_millis(tick_interval);
+    }
+
+    let offset = rng.gen_range(-(jitter as i64)..=jitter as i64);
+    Duration::from_millis((tick_interval as i64 + offset).max(0) as u64)
+}
+
+/// Whether spot is more than `MAX_REFERENCE_DEVIATION` away from the reference book's microprice
+///
+/// An empty book says nothing, so it never flags.
+fn strays_from_reference(spot_price: Decimal, book: &ReferenceBook) -> bool {
+    let microprice = book.microprice();
+    microprice > Decimal::ZERO && ((spot_price - microprice) / microprice).abs() > MAX_REFERENCE_DEVIATION
+}
+
+/// Flag a flatten on every SIGUSR1; the next tick closes out and keeps scanning
+#[cfg(unix)]
+fn listen_for_flatten(health: SharedHealth) -> Result<()> {
+    let mut usr1 = signal::unix::signal(signal::unix::SignalKind::user_defined1())
+        .context("Unable to listen for SIGUSR1")?;
+    tokio::spawn(async move {
+        while usr1.recv().await.is_some() {
+            info!("🧯 Received SIGUSR1 - flattening on the next tick...");
+            health.write().await.flatten_requested = true;
+        }
+    });
+    Ok(())
+}
+
 /// Main trading bot orchestrator
 struct TradingBot {
     config: BotConfig,
-    price_scraper: Arc<PolymarketPriceService>,
+    price_source: Arc<dyn PriceSource>,
     slug_oracle: SlugOracle,
     trading: Arc<TradingService>,
+    rate_limiter: Arc<RateLimiter>,
+    http: reqwest::Client, // shared by every REST caller so connections are pooled
     wallet: Option<WalletService>,
     logger: SessionLogger,
+    ledger: SubAccountLedger,
+    agreement: AgreementMonitor,
+    volatility: VolatilityEstimator,
+    spot_ema: SpotEma,
+    book_activity: BookActivityMonitor,
+    notifiers: Vec<Box<dyn NotificationSink>>, // annotations, Discord, stdout, as configured
+    health: SharedHealth,
+    dead_mans_switch: DeadMansSwitch,
+    risk: RiskManager,
 
     // State
     current_market: Option<MarketInfo>,
     state: BotState,
     tick_count: u64,
+    price_samples: u64, // ticks whose spot price fed the EMA and vol windows
     active_order_id: Option<String>,
     markets_traded: u64,
     total_pnl: Decimal,
+    implied_vol: Option<f64>, // solved from the previous tick's UP book
+    entries_paused: bool,     // stale-book guard currently blocking entries
+    market_spent: Decimal,    // capital deployed in the current market
+    peak_bid: Option<Decimal>, // high-water mark of the bid while in position
+    held_ticks: u64,           // ticks the current position has been held
+    scale_ins: u64,            // buys added to the current position since entry
+    last_trade: Option<Decimal>, // last trade print of the traded token, when the book has one
+    spot_price: Option<Decimal>, // this tick's smoothed spot, for the strike-distance filter
+    traded_book: Option<models::OrderBook>, // this tick's depth for the traded token
+    held_book: Option<models::OrderBook>,   // this tick's book for the held token, when it isn't the traded one
+    halted: bool, // stopped by the dead-man's switch or the session's market cap
+    quotes: Quotes, // market-making bid and ask resting in the current market
+    position_shares: Decimal, // shares held as of the last fill check, to spot new fills
 }
 
 impl TradingBot {
     /// Create a new trading bot
     async fn new(config: BotConfig) -> Result<Self> {
         // Initialize services
-        let price_scraper = Arc::new(PolymarketPriceService::new());
-        let slug_oracle = SlugOracle::new();
-        let trading = Arc::new(TradingService::new(config.clone())?);
-        let logger = SessionLogger::new();
+        let http = http_client::build(config.http_timeout_ms);
+        let price_source = price_source::create_price_source(&config, &http);
+        let rate_limiter = Arc::new(RateLimiter::new(config.http_rate_limit as f64));
+        let slug_oracle = SlugOracle::new(&config, http.clone(), rate_limiter.clone());
+        let trading = Arc::new(TradingService::new(config.clone(), rate_limiter.clone())?);
+        let mut logger = SessionLogger::new(config.log_incremental, config.display_timezone);
+        if !config.db_path.is_empty() {
+            logger = logger.with_db(db::SessionDb::open(&config.db_path)?);
+            info!("🗄️ Logging session to database: {}", config.db_path);
+        }
+        logger.set_note(&config.session_note).await;
+        let ledger = SubAccountLedger::new(&config.sub_accounts);
+
+        let strategy_name = config.strategy.ledger_name();
+        if ledger.is_enabled() && ledger.account(strategy_name).is_none() {
+            warn!("⚠️ SUB_ACCOUNTS has no entry for '{}' - its capital is not limited", strategy_name);
+        }
 
         // Initialize wallet service for live mode
         let wallet = if !config.paper_trade {
@@ -63,19 +193,57 @@ impl TradingBot {
             None
         };
 
+        let agreement = AgreementMonitor::new(config.agreement_window, config.agreement_threshold);
+        let volatility = VolatilityEstimator::new(config.vol_window);
+        let spot_ema = SpotEma::new(config.spot_ema_alpha);
+        let notifiers = notify::sinks_from_config(&config, &http);
+        let book_activity =
+            BookActivityMonitor::new(config.book_rate_window, config.min_book_update_rate);
+        let dead_mans_switch = DeadMansSwitch::new(
+            config.dead_man_interval,
+            &config.dead_man_file,
+            chrono::Utc::now().timestamp_millis(),
+        );
+        let risk = RiskManager::new(config.max_drawdown_pct);
+
         Ok(Self {
             config,
-            price_scraper,
+            price_source,
             slug_oracle,
             trading,
+            rate_limiter,
+            http,
             wallet,
             logger,
+            ledger,
+            agreement,
+            volatility,
+            spot_ema,
+            book_activity,
+            notifiers,
+            health: Arc::new(tokio::sync::RwLock::new(HealthStatus::default())),
+            dead_mans_switch,
+            risk,
             current_market: None,
             state: BotState::Scanning,
             tick_count: 0,
+            price_samples: 0,
             active_order_id: None,
             markets_traded: 0,
             total_pnl: Decimal::ZERO,
+            implied_vol: None,
+            entries_paused: false,
+            market_spent: Decimal::ZERO,
+            peak_bid: None,
+            held_ticks: 0,
+            scale_ins: 0,
+            last_trade: None,
+            spot_price: None,
+            traded_book: None,
+            held_book: None,
+            halted: false,
+            quotes: Quotes::default(),
+            position_shares: Decimal::ZERO,
         })
     }
 
@@ -95,9 +263,38 @@ impl TradingBot {
                 .await?;
         }
 
-        // Start Polymarket price scraper
-        self.price_scraper.start().await?;
-        info!("⏳ Waiting for price scraper to initialize...");
+        // Start the price source, falling back to HTTP if the scraper can't launch
+        if let Err(e) = self.price_source.start().await {
+            if self.config.price_source != PriceSourceKind::Scraper {
+                return Err(e);
+            }
+            warn!("⚠️ Price scraper failed to start: {}. Falling back to HTTP", e);
+            self.price_source = price_source::scraper_fallback(&self.config, &self.http);
+            self.price_source.start().await?;
+        }
+        info!("⏳ Waiting for {} price source to initialize...", self.price_source.name());
+
+        // Confirm live fills as the exchange reports them
+        if !self.config.paper_trade && !self.config.clob_api_key.is_empty() {
+            user_feed::start(
+                user_feed::UserCredentials {
+                    api_key: self.config.clob_api_key.clone(),
+                    secret: self.config.clob_api_secret.clone(),
+                    passphrase: self.config.clob_api_passphrase.clone(),
+                },
+                self.trading.clone(),
+            );
+        }
+
+        if self.config.health_port > 0 {
+            health::start(
+                self.config.health_port as u16,
+                self.health.clone(),
+                self.config.health_max_tick_age,
+                self.config.operator_token.clone(),
+            )
+            .await?;
+        }
 
         // Start main loop
         info!(
@@ -121,14 +318,26 @@ impl TradingBot {
             }
         });
 
+        // SIGUSR1 de-risks without stopping the bot
+        #[cfg(unix)]
+        listen_for_flatten(self.health.clone())?;
+
         // Main trading loop
-        let mut tick_interval = interval(Duration::from_millis(self.config.tick_interval));
+        let mut rng = rand::rngs::StdRng::from_entropy();
+        let mut next_tick = tokio::time::Instant::now();
+
+        while *bot_running.read().await && !self.halted {
+            tokio::time::sleep_until(next_tick).await;
 
-        while *bot_running.read().await {
-            tick_interval.tick().await;
+            // Schedule from the intended start; don't pile up ticks after a slow one
+            next_tick = next_tick.max(tokio::time::Instant::now())
+                + jittered_tick_interval(self.config.tick_interval, self.config.tick_jitter_ms, &mut rng);
 
             if let Err(e) = self.tick().await {
-                error!("⚠️ Tick error: {}", e);
+                match e.downcast_ref::<TradingError>() {
+                    Some(err) if err.is_transient() => warn!("⚠️ Tick error, retrying next tick: {}", err),
+                    _ => error!("⚠️ Tick error: {}", e),
+                }
             }
         }
 
@@ -142,16 +351,70 @@ impl TradingBot {
     /// Main tick loop
     async fn tick(&mut self) -> Result<()> {
         self.tick_count += 1;
-        info!("--- ⏱️ TICK #{} ---", self.tick_count);
+        routine!(self.config.quiet, "--- ⏱️ TICK #{} ---", self.tick_count);
+
+        if self.check_dead_mans_switch(chrono::Utc::now().timestamp_millis()).await? {
+            return Ok(());
+        }
+        if std::mem::take(&mut self.health.write().await.resume_requested) {
+            self.resume_entries();
+        }
+        // The request stays pending until a flatten succeeds, so a failed one is retried next tick
+        if self.health.read().await.flatten_requested {
+            self.flatten_on_request().await?;
+            self.health.write().await.flatten_requested = false;
+            return Ok(());
+        }
+        self.check_reconciliation().await;
+
+        let started = Instant::now();
+        let mut timings = TickTimings::default();
+        let result = self.run_tick(&mut timings).await;
+        let elapsed = started.elapsed();
+
+        if result.is_ok() {
+            let mut health = self.health.write().await;
+            health.state = self.state.to_string();
+            health.tick_count = self.tick_count;
+            health.last_tick_ms = Some(chrono::Utc::now().timestamp_millis());
+            health.price_source_ready = self.price_source.is_ready().await;
+        }
 
+        self.logger.record_tick_latency(elapsed).await;
+        if self.config.stage_latency {
+            self.logger.record_stage_latency(&timings).await;
+        }
+        if latency::is_slow_tick(elapsed, self.config.tick_interval) {
+            warn!(
+                "🐢 Slow tick #{}: {}ms of {}ms budget ({})",
+                self.tick_count,
+                elapsed.as_millis(),
+                self.config.tick_interval,
+                timings.breakdown()
+            );
+        }
+
+        result
+    }
+
+    /// Run one tick, recording how long each phase takes
+    async fn run_tick(&mut self, timings: &mut TickTimings) -> Result<()> {
         // 1. Discover or validate current market
-        if let Err(e) = self.ensure_active_market().await {
+        let phase = Instant::now();
+        let discovery = self.ensure_active_market().await;
+        timings.discovery = Some(phase.elapsed());
+        if let Err(e) = discovery {
             warn!("⚠️ Market discovery failed: {}", e);
             return Ok(());
         }
 
+        // Nothing to trade once the session has ended
+        let Some(market) = &self.current_market else {
+            return Ok(());
+        };
+
         // 2. Check if market is expiring soon
-        if self.current_market.as_ref().unwrap().is_expiring_soon(self.config.market_rotation_threshold) {
+        if market.is_expiring_soon(self.config.market_rotation_threshold) {
             info!("🏁 Market ending soon - rotating");
             self.rotate_market().await?;
             return Ok(());
@@ -162,27 +425,47 @@ impl TradingBot {
             let market = self.current_market.as_ref().unwrap();
 
             // Get BTC spot price
-            let spot_price = match self.price_scraper.get_price().await {
+            let phase = Instant::now();
+            let spot_price = self.price_source.get_price().await;
+            timings.spot_fetch = Some(phase.elapsed());
+            let spot_price = match spot_price {
                 Some(price) => price,
                 None => {
-                    warn!("⚠️ Polymarket price not available yet");
+                    warn!("⚠️ Spot price not available yet");
                     return Ok(());
                 }
             };
+            self.volatility
+                .record(chrono::Utc::now().timestamp_millis(), spot_price);
+            self.health.write().await.last_spot_price = Some(spot_price);
+
+            // Warn when the feed drifts from the external book, when streamed; it isn't traded on
+            if let Some(book) = self.price_source.reference_book().await {
+                if strays_from_reference(spot_price, &book) {
+                    warn!(
+                        "⚠️ Spot ${:.2} strays from Binance microprice ${:.2} (bid {:.2} / ask {:.2})",
+                        spot_price,
+                        book.microprice(),
+                        book.best_bid,
+                        book.best_ask
+                    );
+                }
+            }
+
+            // The model sees the smoothed price; vol and health use the raw feed
+            let spot_price = self.spot_ema.update(spot_price);
+            self.price_samples += 1;
 
             // Calculate trading direction and fair value
             let minutes_remaining = market.minutes_remaining();
-            let (token_direction, fair_value, _) = QuantEngine::select_trading_direction(
-                spot_price,
-                market.strike_price,
-                minutes_remaining,
-            );
-
-            let trading_token = if token_direction == "UP" {
-                market.token_id_up.clone()
-            } else {
-                market.token_id_down.clone()
-            };
+            let (token_direction, fair_value, trading_token) =
+                match self.select_trade(market, spot_price, minutes_remaining) {
+                    Ok(selection) => selection,
+                    Err(e) => {
+                        warn!("⚠️ {}", e);
+                        return Ok(());
+                    }
+                };
 
             (
                 trading_token,
@@ -193,67 +476,79 @@ impl TradingBot {
                 spot_price,
                 market.token_id_up.clone(),
                 market.token_id_down.clone(),
-                token_direction.to_string(),
+                token_direction,
             )
         };
+        self.spot_price = Some(spot_price);
 
         // 6. Get order books for both UP and DOWN tokens
-        let (up_bid, up_ask) = if self.config.paper_trade {
-            match self.fetch_order_book_http(&token_id_up).await {
-                Ok((bid, ask)) => (bid, ask),
-                Err(e) => {
-                    warn!("⚠️ Failed to fetch UP order book: {}", e);
-                    return Ok(());
-                }
-            }
-        } else {
-            match self.trading.fetch_order_book(&token_id_up).await {
-                Ok((bid, ask)) => (bid, ask),
-                Err(e) => {
-                    warn!("⚠️ Failed to fetch UP order book: {}", e);
-                    return Ok(());
-                }
-            }
-        };
-
-        let (down_bid, down_ask) = if self.config.paper_trade {
-            match self.fetch_order_book_http(&token_id_down).await {
-                Ok((bid, ask)) => (bid, ask),
-                Err(e) => {
-                    warn!("⚠️ Failed to fetch DOWN order book: {}", e);
-                    return Ok(());
-                }
-            }
-        } else {
-            match self.trading.fetch_order_book(&token_id_down).await {
-                Ok((bid, ask)) => (bid, ask),
-                Err(e) => {
-                    warn!("⚠️ Failed to fetch DOWN order book: {}", e);
-                    return Ok(());
-                }
+        let books = self.fetch_books(&token_id_up, &token_id_down, timings).await;
+        let (up_book, down_book) = match books {
+            Ok(books) => books,
+            Err(e) => {
+                warn!("⚠️ {:#}", e);
+                return Ok(());
             }
         };
 
-        if up_bid.is_none() || up_ask.is_none() || down_bid.is_none() || down_ask.is_none() {
-            warn!("⚠️ Order book has no liquidity");
-            return Ok(());
-        }
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        self.book_activity.record(&token_id_up, up_book.best_bid(), up_book.best_ask(), now_ms);
+        self.book_activity.record(&token_id_down, down_book.best_bid(), down_book.best_ask(), now_ms);
 
         // Use the trading token's order book for execution
-        let (best_bid, best_ask) = if token_direction_str == "UP" {
-            (up_bid, up_ask)
-        } else {
-            (down_bid, down_ask)
+        let book = if token_direction_str == "UP" { &up_book } else { &down_book };
+        let Some((best_bid, best_ask)) = self.actionable_quotes(book.best_bid(), book.best_ask()) else {
+            warn!("⚠️ Order book has no liquidity on the side needed while {}", self.state);
+            return Ok(());
+        };
+        let spread = best_ask - best_bid;
+        self.last_trade = book.last_trade();
+        self.traded_book = Some(book.clone());
+
+        // After a strike cross the held token is no longer the traded one; exits quote its own book
+        self.held_book = match self.trading.get_position().await {
+            Some(pos) if pos.token_id != trading_token => [(&token_id_up, &up_book), (&token_id_down, &down_book)]
+                .into_iter()
+                .find(|(id, _)| **id == pos.token_id)
+                .map(|(_, book)| book.clone()),
+            _ => None,
         };
 
-        let spread = best_ask.unwrap() - best_bid.unwrap();
+        let quiet = self.config.quiet;
+        routine!(quiet, "📊 Spot: ${:.2} | Strike: ${:.2} | Direction: {}", spot_price, market_strike, token_direction_str);
+        match self.model_vol() {
+            Some(vol) => routine!(quiet, "🧮 Fair: {:.4} (vol {:.1}%)", fair_value, vol * 100.0),
+            None => routine!(quiet, "🧮 Fair: {:.4}", fair_value),
+        }
+        for (label, book) in [("UP:  ", &up_book), ("DOWN:", &down_book)] {
+            routine!(
+                quiet,
+                "📖 {} Bid {:.4} / Ask {:.4}",
+                label,
+                book.best_bid().unwrap_or_default(),
+                book.best_ask().unwrap_or_default()
+            );
+        }
+        routine!(quiet, "📊 Trading {} token (Spread: {:.4})", token_direction_str, spread);
+        routine!(quiet, "⏰ Time Left: {:.1} minutes", minutes_remaining);
+
+        // Research aid: flag a model that merely mirrors the book
+        if let Some(mean_gap) = book.mid().and_then(|mid| self.agreement.record(fair_value, mid)) {
+            info!(
+                "🔬 Model fair value has tracked the book mid within {:.4} for {} ticks - review for independent signal",
+                mean_gap, self.config.agreement_window
+            );
+        }
 
-        info!("📊 Spot: ${:.2} | Strike: ${:.2} | Direction: {}", spot_price, market_strike, token_direction_str);
-        info!("🧮 Fair: {:.4}", fair_value);
-        info!("📖 UP:   Bid {:.4} / Ask {:.4}", up_bid.unwrap(), up_ask.unwrap());
-        info!("📖 DOWN: Bid {:.4} / Ask {:.4}", down_bid.unwrap(), down_ask.unwrap());
-        info!("📊 Trading {} token (Spread: {:.4})", token_direction_str, spread);
-        info!("⏰ Time Left: {:.1} minutes", minutes_remaining);
+        // Anchor next tick's model to the vol the market is pricing
+        if self.config.vol_source == VolSource::Implied {
+            if let Some(vol) = up_book
+                .mid()
+                .and_then(|up_mid| QuantEngine::implied_vol(up_mid, spot_price, market_strike, minutes_remaining))
+            {
+                self.implied_vol = Some(vol);
+            }
+        }
 
         // 6. Check spread validity
         if !QuantEngine::is_spread_acceptable(spread, self.config.max_spread) {
@@ -262,17 +557,54 @@ impl TradingBot {
         }
 
         // 7. Execute trading strategy
-        self.execute_strategy(&trading_token, fair_value, best_bid.unwrap(), best_ask.unwrap())
-            .await?;
-
-        // 8. Check paper fills (paper mode only)
+        let phase = Instant::now();
+        let strategy = self
+            .execute_strategy(&trading_token, fair_value, best_bid, best_ask, minutes_remaining)
+            .await;
+        timings.strategy = Some(phase.elapsed());
+        strategy?;
+
+        // 8. Check paper fills (paper mode only); an empty side fills nothing
         if self.config.paper_trade {
             self.trading
-                .check_paper_fills(&trading_token, best_ask.unwrap(), best_bid.unwrap())
+                .check_paper_fills(
+                    &trading_token,
+                    book.best_ask().unwrap_or(Decimal::MAX),
+                    book.best_bid().unwrap_or(Decimal::ZERO),
+                )
                 .await;
+            // A take profit resting on the held token fills against its own book
+            if let (Some(pos), Some(held_book)) = (self.trading.get_position().await, &self.held_book) {
+                self.trading
+                    .check_paper_fills(
+                        &pos.token_id,
+                        held_book.best_ask().unwrap_or(Decimal::MAX),
+                        held_book.best_bid().unwrap_or(Decimal::ZERO),
+                    )
+                    .await;
+            }
+        }
+
+        // Record any trades closed this tick
+        self.record_closed_trades().await;
+        let paper_fill_check = self.validate_paper_fills(&token_id_up, &token_id_down, &up_book, &down_book).await;
+
+        let unrealized_pnl = self.unrealized_pnl(&token_id_up, &up_book, &down_book).await;
+        if let Some(pnl) = unrealized_pnl {
+            routine!(quiet, "💰 Unrealized P&L: ${:.2}", pnl);
+        }
+        // Live equity costs a wallet call, so it is only taken for the drawdown stop
+        if self.config.paper_trade || self.config.max_drawdown_pct > Decimal::ZERO {
+            if let Some(equity) = self.equity(&token_id_up, &up_book, &down_book).await {
+                self.logger
+                    .record_equity(chrono::Utc::now().timestamp_millis(), equity)
+                    .await;
+                self.check_drawdown(equity).await;
+            }
         }
 
         // 9. Log tick data
+        let phase = Instant::now();
         let tick_data = TickData {
             timestamp: chrono::Utc::now().timestamp_millis(),
             tick_number: self.tick_count,
@@ -282,46 +614,236 @@ impl TradingBot {
             fair_value,
             target_buy_price: QuantEngine::calculate_entry_price(
                 fair_value,
-                self.config.panic_discount,
+                self.panic_discount(minutes_remaining),
             ),
-            best_bid,
-            best_ask,
-            spread: Some(spread),
+            best_bid: book.best_bid(),
+            best_ask: book.best_ask(),
+            spread: book.spread(),
             minutes_remaining,
             state: self.state.to_string(),
+            unrealized_pnl,
+            paper_fill_check,
         };
 
+        logger::trace_tick(&tick_data, quiet);
         self.logger.log_tick(tick_data).await;
-        info!("🔍 STATE: {}", self.state);
+        timings.logging = Some(phase.elapsed());
 
         Ok(())
     }
 
-    /// Fetch order book via HTTP (for paper trading mode)
-    async fn fetch_order_book_http(&self, token_id: &str) -> Result<(Option<Decimal>, Option<Decimal>)> {
-        use serde::Deserialize;
+    /// Check this tick's paper fills against the live depth, with VALIDATE_PAPER_FILLS
+    ///
+    /// None without fills to check; otherwise whether the live book held every
+    /// fill's size at its limit price or better.
+    async fn validate_paper_fills(
+        &self,
+        token_id_up: &str,
+        token_id_down: &str,
+        up_book: &models::OrderBook,
+        down_book: &models::OrderBook,
+    ) -> Option<bool> {
+        let fills = self.trading.take_paper_fills().await;
+        let mut satisfiable = None;
+        for fill in fills {
+            let book = match fill.token_id.as_str() {
+                id if id == token_id_up => up_book,
+                id if id == token_id_down => down_book,
+                _ => continue, // a token from another market
+            };
+            let available = book.liquidity_at(fill.side, fill.price);
+            let fits = available >= fill.size;
+            if !fits {
+                warn!(
+                    "🧪 Paper {:?} {} @ {:.4} beats the live book: only {} available at that price",
+                    fill.side, fill.size, fill.price, available
+                );
+            }
+            satisfiable = Some(satisfiable.unwrap_or(true) && fits);
+        }
+        satisfiable
+    }
 
-        #[derive(Deserialize)]
-        struct OrderBookLevel {
-            price: String,
+    /// The bid and ask this tick's action can trade against, or None if its side is empty
+    ///
+    /// Entries lift the ask and exits hit the bid, so a one-sided book (common
+    /// near expiry) only blocks the action that needs the missing side. That
+    /// side is collapsed onto the present one, so nothing is priced off it.
+    fn actionable_quotes(&self, best_bid: Option<Decimal>, best_ask: Option<Decimal>) -> Option<(Decimal, Decimal)> {
+        match (best_bid, best_ask) {
+            (Some(bid), Some(ask)) => Some((bid, ask)),
+            // Quoting needs both sides to sit inside
+            _ if self.config.strategy == Strategy::MarketMaking => None,
+            (None, Some(ask)) if self.state == BotState::Scanning => Some((ask, ask)),
+            (Some(bid), None) if self.state != BotState::Scanning => Some((bid, bid)),
+            _ => None,
+        }
+    }
+
+    /// Pick the direction, fair value and token ID to trade in this market
+    fn select_trade(
+        &self,
+        market: &MarketInfo,
+        spot_price: Decimal,
+        minutes_remaining: f64,
+    ) -> Result<(String, Decimal, String)> {
+        let (token_direction, fair_value, _) = QuantEngine::select_direction_with_override(
+            spot_price,
+            market.strike_price,
+            minutes_remaining,
+            self.config.force_direction,
+            self.model_vol(),
+        );
+
+        let trading_token = if token_direction == "UP" {
+            market.token_id_up.clone()
+        } else {
+            market.token_id_down.clone()
+        };
+
+        if trading_token.is_empty() {
+            anyhow::bail!("No {} token ID available for market {}", token_direction, market.slug);
+        }
+
+        Ok((token_direction, fair_value, trading_token))
+    }
+
+    /// Entry discount, scaled by time to expiry and widened by realized volatility when configured
+    fn panic_discount(&self, minutes_remaining: f64) -> Decimal {
+        let base = QuantEngine::dynamic_entry_discount(
+            self.config.panic_discount,
+            minutes_remaining,
+            self.config.discount_early_scale,
+            self.config.discount_late_scale,
+        );
+        QuantEngine::effective_discount(
+            base,
+            self.config.discount_vol_coefficient,
+            self.volatility.realized_vol(),
+            self.config.max_panic_discount,
+        )
+    }
+
+    /// Cash free for new entries: paper cash not reserved by open buys, or the wallet's USDC
+    async fn free_cash(&self) -> Result<Decimal> {
+        match &self.wallet {
+            Some(wallet) => wallet.get_usdc_balance().await,
+            None => Ok(self.trading.get_cash_balance().await - self.trading.reserved_cash().await),
+        }
+    }
+
+    /// Value of the open position, marked at the bid when it's in `token_id`
+    async fn position_value(&self, token_id: &str, best_bid: Decimal) -> Decimal {
+        match self.trading.get_position().await {
+            Some(pos) if pos.token_id == token_id => pos.shares * best_bid,
+            Some(pos) => pos.shares * pos.entry_price,
+            None => Decimal::ZERO,
+        }
+    }
+
+    /// Mark-to-market P&L of the open position at the bid of its token's book
+    async fn unrealized_pnl(
+        &self,
+        token_id_up: &str,
+        up_book: &models::OrderBook,
+        down_book: &models::OrderBook,
+    ) -> Option<Decimal> {
+        let pos = self.trading.get_position().await?;
+        let book = if pos.token_id == token_id_up { up_book } else { down_book };
+        book.best_bid().map(|bid| pos.calculate_pnl(bid))
+    }
+
+    /// Cash (paper, or the wallet's USDC live) plus the open position marked at its best bid
+    ///
+    /// None while holding into a book with no bids, which has no mark, or
+    /// when the wallet balance can't be read.
+    async fn equity(
+        &self,
+        token_id_up: &str,
+        up_book: &models::OrderBook,
+        down_book: &models::OrderBook,
+    ) -> Option<Decimal> {
+        let cash = match &self.wallet {
+            Some(wallet) => match wallet.get_usdc_balance().await {
+                Ok(balance) => balance,
+                Err(e) => {
+                    warn!("⚠️ USDC balance check failed, skipping equity: {}", e);
+                    return None;
+                }
+            },
+            None => self.trading.get_cash_balance().await,
+        };
+        let Some(pos) = self.trading.get_position().await else {
+            return Some(cash);
+        };
+        let book = if pos.token_id == token_id_up { up_book } else { down_book };
+        book.best_bid().map(|bid| cash + pos.shares * bid)
+    }
+
+    /// Volatility fed to the fair value model, if the configured source has one
+    fn model_vol(&self) -> Option<f64> {
+        match self.config.vol_source {
+            VolSource::Off => None,
+            VolSource::Realized => self.volatility.realized_vol(),
+            VolSource::Implied => self.implied_vol,
+        }
+    }
+
+    /// Fetch the order books for the UP and DOWN tokens
+    async fn fetch_books(
+        &self,
+        token_id_up: &str,
+        token_id_down: &str,
+        timings: &mut TickTimings,
+    ) -> Result<(models::OrderBook, models::OrderBook)> {
+        let phase = Instant::now();
+        let up = self.fetch_order_book(token_id_up).await;
+        timings.book_up = Some(phase.elapsed());
+        let up = up.context("Failed to fetch UP order book")?;
+
+        let phase = Instant::now();
+        let down = self.fetch_order_book(token_id_down).await;
+        timings.book_down = Some(phase.elapsed());
+        let down = down.context("Failed to fetch DOWN order book")?;
+
+        Ok((up, down))
+    }
+
+    /// Fetch a token's order book from the source for the current mode
+    async fn fetch_order_book(&self, token_id: &str) -> Result<models::OrderBook> {
+        if self.config.paper_trade {
+            let book = self.fetch_order_book_http(token_id).await?;
+
+            // Keep the depth so paper market orders can walk it
+            self.trading.update_paper_book(token_id, book.clone()).await;
+            Ok(book)
+        } else {
+            Ok(self.trading.fetch_order_book(token_id).await?)
         }
+    }
+
+    /// Fetch order book depth via HTTP (for paper trading mode)
+    async fn fetch_order_book_http(&self, token_id: &str) -> Result<models::OrderBook> {
+        use serde::Deserialize;
 
         #[derive(Deserialize)]
         struct OrderBook {
-            bids: Vec<OrderBookLevel>,
-            asks: Vec<OrderBookLevel>,
+            bids: Vec<models::OrderBookLevel>,
+            asks: Vec<models::OrderBookLevel>,
+            #[serde(default)]
+            last_trade_price: Option<String>,
         }
 
-        let url = format!("https://clob.polymarket.com/book?token_id={}", token_id);
-        let client = reqwest::Client::new();
-        let book: OrderBook = client.get(&url).send().await?.json().await?;
+        let url = format!("{}/book?token_id={}", self.config.clob_base_url, token_id);
+        let book: OrderBook = self.rate_limiter.send(self.http.get(&url)).await?.json().await?;
 
-        let best_bid = book.bids.first()
-            .and_then(|level| Decimal::from_str(&level.price).ok());
-        let best_ask = book.asks.first()
-            .and_then(|level| Decimal::from_str(&level.price).ok());
-
-        Ok((best_bid, best_ask))
+        Ok(models::OrderBook {
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            market: token_id.to_string(),
+            bids: book.bids,
+            asks: book.asks,
+            last_trade_price: book.last_trade_price,
+        })
     }
 
     /// Ensure we have an active market
@@ -329,14 +851,18 @@ impl TradingBot {
         if self.config.auto_discover_markets {
             // Check if we need to discover
             if self.current_market.is_none() {
+                if self.session_complete() {
+                    return Ok(());
+                }
                 info!("🔍 No active market. Discovering...");
-                let mut market = self.slug_oracle.discover_active_market().await?;
+                let spot = self.price_source.get_price().await;
+                let mut market = self.slug_oracle.discover_active_market(spot).await?;
 
-                // If strike price is the default (100000), use current BTC price
-                if market.strike_price == Decimal::from_str("100000")? {
-                    if let Some(spot_price) = self.price_scraper.get_price().await {
+                // Until the open price is published, stand in the current BTC price
+                if !market.strike_finalized {
+                    if let Some(spot_price) = spot {
                         market.strike_price = spot_price;
-                        info!("📍 Using current BTC price as strike: ${:.2}", spot_price);
+                        info!("📍 Using current BTC price as strike until the open price is available: ${:.2}", spot_price);
                     }
                 }
 
@@ -344,51 +870,273 @@ impl TradingBot {
                 self.markets_traded += 1;
                 self.logger.increment_markets_traded().await;
 
-                // Set the market slug for price scraper
-                self.price_scraper.set_market_slug(market.slug.clone()).await;
+                // Set the market slug for the price source
+                self.price_source.set_market_slug(market.slug.clone()).await;
 
                 info!("🎯 ========================================");
                 info!("🎯 MARKET #{}: {}", self.markets_traded, market.slug);
                 info!("🎯 Strike: ${:.2}", market.strike_price);
                 info!("🎯 ========================================");
+
+                // Resume anything a previous run left on the book
+                if !self.config.paper_trade {
+                    self.sync_live_state(&market).await;
+                }
+            } else if let Some(market) = self.current_market.as_mut().filter(|market| !market.strike_finalized) {
+                // Keep asking for the open price so the placeholder doesn't skew fair value
+                match self.slug_oracle.refresh_strike(market).await {
+                    Ok(()) => info!("🎯 Strike finalized: ${:.2}", market.strike_price),
+                    Err(e) => debug!("Strike still pending: {:#}", e),
+                }
             }
         }
 
         Ok(())
     }
 
-    /// Rotate to next market
-    async fn rotate_market(&mut self) -> Result<()> {
-        // Close any open positions
-        if self.trading.has_position().await {
-            warn!("🚨 Closing position before market rotation...");
-            if let Some(pos) = self.trading.get_position().await {
-                // Execute emergency exit
-                let exit_price = Decimal::from_str_exact("0.50")?; // Mid-market estimate
-                self.trading
-                    .execute_market_order(&pos.token_id, models::OrderSide::SELL, exit_price, pos.shares)
-                    .await?;
+    /// Adopt live orders and positions left over from a previous run
+    async fn sync_live_state(&mut self, market: &MarketInfo) {
+        if let Err(e) = self.trading.sync_live_state(market).await {
+            warn!("⚠️ Live state sync failed: {:#}", e);
+            return;
+        }
+
+        if let Some(order_id) = self.trading.open_order_ids().await.into_iter().next() {
+            self.active_order_id = Some(order_id);
+        }
+        if self.active_order_id.is_some() || self.trading.has_position().await {
+            info!("🔁 Resuming with existing live state");
+            self.state = BotState::InPosition;
+            self.peak_bid = None;
+            self.held_ticks = 0;
+            self.scale_ins = 0;
+        }
+    }
+
+    /// Flatten and halt if the operator has missed a check-in
+    ///
+    /// Returns whether the bot halted.
+    async fn check_dead_mans_switch(&mut self, now_ms: i64) -> Result<bool> {
+        if let Some(checkin_ms) = self.health.read().await.last_checkin_ms {
+            self.dead_mans_switch.check_in(checkin_ms);
+        }
+        if !self.dead_mans_switch.expired(now_ms) {
+            return Ok(false);
+        }
+
+        error!(
+            "💀 No operator check-in for {}s (max {}s) - flattening and halting",
+            self.dead_mans_switch.silence_ms(now_ms) / 1000,
+            self.config.dead_man_interval
+        );
+        self.flatten(ExitReason::DeadMan).await?;
+        self.state = BotState::Scanning;
+        self.halted = true;
+        let reason = "Operator check-in missed - flattened and halted";
+        self.broadcast(BotEvent::Halted { reason }).await;
+        Ok(true)
+    }
+
+    /// Every `RECONCILE_INTERVAL_TICKS`, check the tracked live position against the wallet
+    async fn check_reconciliation(&mut self) {
+        let interval = self.config.reconcile_interval_ticks;
+        if interval == 0 || self.tick_count % interval != 0 {
+            return;
+        }
+        let Some(wallet) = &self.wallet else {
+            return;
+        };
 
-                let pnl = pos.calculate_pnl(exit_price);
-                self.total_pnl += pnl;
-                info!("💸 Emergency exit P&L: ${:.2}", pnl);
+        // Flat, a missed fill could have left shares in either outcome
+        let token_ids = match (self.trading.get_position().await, &self.current_market) {
+            (Some(pos), _) => vec![pos.token_id],
+            (None, Some(market)) => vec![market.token_id_up.clone(), market.token_id_down.clone()],
+            (None, None) => return,
+        };
+        let mut balances = Vec::new();
+        for token_id in token_ids {
+            match wallet.get_outcome_token_balance(&token_id).await {
+                Ok(held) => balances.push((token_id, held)),
+                Err(e) => {
+                    warn!("⚠️ Position reconciliation failed: {:#}", e);
+                    return;
+                }
             }
         }
+        for (token_id, held) in balances {
+            self.reconcile_position(&token_id, held).await;
+        }
+    }
 
-        // Cancel any open orders
-        if let Some(order_id) = &self.active_order_id {
-            info!("🗑️ Cancelling open orders...");
-            let _ = self.trading.cancel_order(order_id).await;
-            self.active_order_id = None;
+    /// Compare the tracked shares of `token_id` with the `held` balance,
+    /// adopting it with `RECONCILE_MODE=correct`
+    ///
+    /// Returns whether the two disagreed by more than `RECONCILE_TOLERANCE`.
+    async fn reconcile_position(&mut self, token_id: &str, held: Decimal) -> bool {
+        let tracked = match self.trading.get_position().await {
+            Some(pos) if pos.token_id == token_id => pos.shares,
+            Some(_) => return false,
+            None => Decimal::ZERO,
+        };
+        if (tracked - held).abs() <= RECONCILE_TOLERANCE {
+            return false;
+        }
+
+        warn!(
+            "🧮 Position drift: tracking {} shares of {}... but the wallet holds {}",
+            tracked,
+            &token_id[..8.min(token_id.len())],
+            held
+        );
+        if self.config.reconcile_mode == ReconcileMode::Correct {
+            if tracked > Decimal::ZERO {
+                info!("🧮 Tracked shares corrected to {}", held);
+                self.trading.correct_position_shares(held).await;
+            } else if let Some(market) = self.current_market.clone() {
+                // Untracked shares: adopt them the way a restart would
+                self.sync_live_state(&market).await;
+            }
+            // Not a fill: keep the fill check from reporting the difference
+            self.position_shares = self.trading.get_position().await.map_or(Decimal::ZERO, |pos| pos.shares);
+        }
+        true
+    }
+
+    /// Halt new entries if `equity` has fallen `MAX_DRAWDOWN_PCT` below its peak
+    async fn check_drawdown(&mut self, equity: Decimal) {
+        if !self.risk.record_equity(equity) {
+            return;
+        }
+
+        warn!(
+            "📉 Equity ${:.2} is {:.1}% below its ${:.2} peak (max {}%) - halting new entries until POST /resume",
+            equity,
+            self.risk.drawdown_pct(equity),
+            self.risk.peak_equity().unwrap_or_default(),
+            self.config.max_drawdown_pct
+        );
+        let reason = "Max drawdown reached - new entries halted";
+        self.broadcast(BotEvent::Halted { reason }).await;
+    }
+
+    /// Lift a risk halt at the operator's request
+    fn resume_entries(&mut self) {
+        if !self.risk.entries_halted() {
+            return;
+        }
+        info!("▶️ Resuming entries; drawdown is measured from a fresh peak");
+        self.risk.resume();
+        if self.state == BotState::Halted {
+            self.state = BotState::Scanning;
+        }
+    }
+
+    /// Close out everything at the operator's request and go back to scanning
+    async fn flatten_on_request(&mut self) -> Result<()> {
+        warn!("🧯 Operator flatten: cancelling all orders and closing positions");
+        self.flatten(ExitReason::Manual).await?;
+        self.state = BotState::Scanning;
+        self.peak_bid = None;
+        self.held_ticks = 0;
+        self.scale_ins = 0;
+        Ok(())
+    }
+
+    /// Cancel every resting order: entries, scale-ins, exits and market-making quotes
+    async fn cancel_all_orders(&mut self) {
+        self.quotes.bid = None;
+        self.quotes.ask = None;
+        self.active_order_id = None;
+
+        let order_ids = self.trading.open_order_ids().await;
+        if !order_ids.is_empty() {
+            info!("🗑️ Cancelling {} open order(s)...", order_ids.len());
+        }
+        for order_id in order_ids {
+            if let Err(e) = self.trading.cancel_order(&order_id).await {
+                warn!("⚠️ Cancel of {} failed: {}", order_id, e);
+            }
+        }
+    }
+
+    /// Cancel every open order, then sell any position `DUMP_CUSHION` under the bid
+    ///
+    /// Orders go first so a resting exit can't hold the shares or a resting
+    /// buy fill afterwards. If shares are still held once the attempts run
+    /// out, an alert goes out and an error is returned so callers don't treat
+    /// the bot as flat; the next tick tries again.
+    async fn flatten(&mut self, reason: ExitReason) -> Result<()> {
+        self.cancel_all_orders().await;
+
+        for attempt in 1..=FLATTEN_ATTEMPTS {
+            let Some(pos) = self.trading.get_position().await else {
+                break;
+            };
+            let Some(bid) = self.trading.best_bid(&pos.token_id).await else {
+                error!("❌ No bid to sell {} shares into", pos.shares);
+                break;
+            };
+            let exit_price = (bid - self.config.dump_cushion).max(Decimal::new(1, 2));
+            warn!("🚨 Closing position ({:?}) @ {:.4}, attempt {}/{}...", reason, exit_price, attempt, FLATTEN_ATTEMPTS);
+            if let Err(e) = self
+                .trading
+                .execute_market_order(&pos.token_id, models::OrderSide::SELL, exit_price, pos.shares, Some(reason))
+                .await
+            {
+                warn!("⚠️ Exit sell failed: {}", e);
+            }
+            self.record_closed_trades().await;
+        }
+        self.record_fill_stats().await;
+
+        if let Some(pos) = self.trading.get_position().await {
+            error!("🚨 Flatten left {} shares of {} open", pos.shares, pos.token_id);
+            self.broadcast(BotEvent::Halted { reason: "Flatten failed - position still open" }).await;
+            anyhow::bail!("flatten left {} shares open", pos.shares);
+        }
+        Ok(())
+    }
+
+    /// Rotate to next market
+    async fn rotate_market(&mut self) -> Result<()> {
+        self.flatten(ExitReason::Rotation).await?;
+
+        // Don't let discovery hand the market we're leaving straight back
+        if let Some(market) = &self.current_market {
+            self.slug_oracle.mark_recent(&market.slug).await;
+            self.broadcast(BotEvent::Rotated { slug: &market.slug, total_pnl: self.total_pnl }).await;
         }
 
         // Discover next market
         self.current_market = None;
+        self.market_spent = Decimal::ZERO;
+        self.implied_vol = None;
+        self.book_activity.clear();
         self.state = BotState::Scanning;
 
+        if self.session_complete() {
+            info!(
+                "🏁 Traded {} markets (MAX_MARKETS_PER_SESSION) - ending session",
+                self.markets_traded
+            );
+            self.halted = true;
+        }
+
         Ok(())
     }
 
+    /// Whether `MAX_MARKETS_PER_SESSION` markets have been traded
+    fn session_complete(&self) -> bool {
+        self.config.max_markets_per_session > 0 && self.markets_traded >= self.config.max_markets_per_session
+    }
+
+    /// Whether the bot is still inside its `WARMUP_TICKS` observation period
+    ///
+    /// Only ticks that fed a spot price into the EMA and vol windows count.
+    fn warming_up(&self) -> bool {
+        self.config.warmup_ticks > 0 && self.price_samples <= self.config.warmup_ticks
+    }
+
     /// Execute trading strategy
     async fn execute_strategy(
         &mut self,
@@ -396,62 +1144,306 @@ impl TradingBot {
         fair_value: Decimal,
         best_bid: Decimal,
         best_ask: Decimal,
+        minutes_remaining: f64,
     ) -> Result<()> {
+        // Let the spot EMA and vol windows settle before acting on them; a
+        // position adopted from a previous run is still managed
+        if self.warming_up() && !self.trading.has_position().await {
+            routine!(
+                self.config.quiet,
+                "🌡️ Warming up ({}/{} ticks) - observing only",
+                self.price_samples,
+                self.config.warmup_ticks
+            );
+            return Ok(());
+        }
+
+        if self.config.strategy == Strategy::MarketMaking {
+            return self.make_market(token_id, fair_value, best_bid, best_ask).await;
+        }
+
+        // Self-heal a state left behind by a missed fill or race: nothing held
+        // and nothing resting means there is no position to manage
+        if self.state == BotState::InPosition
+            && self.trading.get_position().await.is_none()
+            && self.trading.open_order_ids().await.is_empty()
+        {
+            warn!("⚠️ State was IN_POSITION with no position or open orders - resetting to SCANNING");
+            self.state = BotState::Scanning;
+            self.active_order_id = None;
+            self.peak_bid = None;
+            self.held_ticks = 0;
+            self.scale_ins = 0;
+        }
+
         match self.state {
+            // Open positions are still managed; only new entries stop
+            BotState::Scanning if self.risk.entries_halted() => {
+                self.state = BotState::Halted;
+            }
+
             BotState::Scanning => {
+                if self.entries_blocked(token_id).await {
+                    return Ok(());
+                }
+
                 // Calculate entry target
                 let target_buy = QuantEngine::calculate_entry_price(
                     fair_value,
-                    self.config.panic_discount,
+                    self.panic_discount(minutes_remaining),
                 );
 
                 // Check if we should enter
                 if best_ask <= target_buy {
-                    let size = QuantEngine::calculate_position_size(
-                        self.config.max_capital_per_trade,
-                        best_ask,
-                    );
+                    if !QuantEngine::has_min_edge(fair_value, best_ask, self.config.min_edge) {
+                        info!(
+                            "⏭️ Edge too thin: {:.4} (min {:.4}) - skipping entry",
+                            fair_value - best_ask,
+                            self.config.min_edge
+                        );
+                        return Ok(());
+                    }
 
-                    info!("📤 Placing BUY order @ {:.4} (Size: {})", best_ask, size);
+                    if self.outside_entry_band(best_ask) {
+                        return Ok(());
+                    }
 
-                    match self.trading.buy(token_id, best_ask, size).await {
-                        Ok(order_id) => {
-                            self.active_order_id = Some(order_id);
-                            self.state = BotState::InPosition;
-                        }
-                        Err(e) => {
-                            error!("❌ Order placement failed: {}", e);
-                        }
+                    let mut capital = self.entry_capital();
+                    if self.config.sizing_mode == SizingMode::Confidence {
+                        capital = capital.min(QuantEngine::confidence_capital(
+                            self.config.max_capital_per_trade,
+                            self.config.confidence_min_capital,
+                            fair_value - best_ask,
+                            self.config.confidence_full_edge,
+                        ));
                     }
-                }
-            }
 
-            BotState::InPosition => {
+                    // Snipes cross the ask by the cushion to take it before it moves
+                    let entry_price = match self.config.execution_mode {
+                        ExecutionMode::Limit => best_ask,
+                        ExecutionMode::Snipe => (best_ask + self.config.snipe_cushion).min(Decimal::new(99, 2)),
+                    };
+
+                    let mut size = QuantEngine::calculate_position_size(capital, entry_price);
+                    if size <= Decimal::ZERO {
+                        warn!("⚠️ No capital left for '{}' in this market", self.config.strategy.ledger_name());
+                        return Ok(());
+                    }
+
+                    let Some(capped) = self.cap_to_depth(size) else {
+                        return Ok(());
+                    };
+                    size = capped;
+
+                    let Some(size) = self.order_size(size) else {
+                        info!(
+                            "⏭️ {} shares @ {:.4} is under the {} share minimum - skipping entry",
+                            size, entry_price, self.config.min_order_size
+                        );
+                        return Ok(());
+                    };
+
+                    // A large order walks past the ask; judge the edge at its average fill
+                    if let Some(book) = self.traded_book.as_ref().filter(|_| self.config.slippage_adjusted_edge) {
+                        let Some(fill_price) = QuantEngine::expected_fill_price(book, size) else {
+                            info!("📉 Ask depth can't fill {} shares - skipping entry", size);
+                            return Ok(());
+                        };
+                        if fill_price > target_buy
+                            || !QuantEngine::has_min_edge(fair_value, fill_price, self.config.min_edge)
+                        {
+                            info!(
+                                "📉 Average fill {:.4} for {} shares leaves {:.4} of edge (ask {:.4}) - skipping entry",
+                                fill_price,
+                                size,
+                                fair_value - fill_price,
+                                best_ask
+                            );
+                            return Ok(());
+                        }
+                    }
+
+                    if !self.preserves_cash_ratio(token_id, best_bid, entry_price * size).await? {
+                        return Ok(());
+                    }
+
+                    info!("📤 Placing BUY order @ {:.4} (Size: {})", entry_price, size);
+
+                    let entered = match self.config.execution_mode {
+                        ExecutionMode::Limit => self.trading.buy(token_id, entry_price, size).await.map(|order_id| {
+                            self.active_order_id = Some(order_id);
+                            true
+                        }),
+                        ExecutionMode::Snipe => {
+                            self.trading
+                                .snipe(token_id, models::OrderSide::BUY, entry_price, size, None)
+                                .await
+                        }
+                    };
+
+                    match entered {
+                        Ok(true) => {
+                            self.state = BotState::InPosition;
+                            self.peak_bid = None;
+                            self.held_ticks = 0;
+                            self.scale_ins = 0;
+                            self.market_spent += entry_price * size;
+                            self.broadcast(BotEvent::OrderPlaced { token_id, price: entry_price, size })
+                                .await;
+                        }
+                        Ok(false) => {}
+                        Err(e) if e.is_transient() => {
+                            warn!("⚠️ Order placement failed, retrying next tick: {}", e);
+                        }
+                        Err(
+                            e @ (TradingError::InsufficientFunds { .. }
+                            | TradingError::OrderLimit(_)
+                            | TradingError::Duplicate(_)),
+                        ) => {
+                            warn!("⚠️ Skipping entry: {}", e);
+                        }
+                        Err(e) => {
+                            error!("❌ Order placement failed: {}", e);
+                        }
+                    }
+                }
+            }
+
+            BotState::InPosition => {
                 if let Some(pos) = self.trading.get_position().await {
+                    let Some((held_bid, held_ask)) = self.held_quotes(&pos, token_id, best_bid, best_ask) else {
+                        warn!("⚠️ No book for the held token {} - holding", pos.token_id);
+                        return Ok(());
+                    };
+
+                    // Flatten ahead of expiry regardless of TP/SL
+                    if QuantEngine::should_force_exit(
+                        minutes_remaining,
+                        self.config.force_exit_before_expiry_seconds,
+                    ) {
+                        warn!(
+                            "⏳ Forced exit before expiry @ {:.4} ({:.0}s left)",
+                            held_bid,
+                            minutes_remaining * 60.0
+                        );
+                        let filled = self
+                            .exit_now(&pos.token_id, held_bid, pos.shares, ExitReason::ExpiryExit)
+                            .await?;
+                        if filled {
+                            self.state = BotState::Scanning;
+                        }
+                        return Ok(());
+                    }
+
+                    // The model is confident enough: take the move at the bid. The
+                    // outcomes are complementary, so a held token that is no longer
+                    // the selected one is worth 1 minus the selected fair value.
+                    let held_fair_value = if pos.token_id == token_id { fair_value } else { Decimal::ONE - fair_value };
+                    if QuantEngine::in_exit_band(held_fair_value, self.config.fair_value_exit_band) {
+                        info!(
+                            "🎯 Fair value {:.4} reached exit band {:.4} - exiting @ {:.4}",
+                            held_fair_value, self.config.fair_value_exit_band, held_bid
+                        );
+                        let filled = self
+                            .exit_now(&pos.token_id, held_bid, pos.shares, ExitReason::FairValueBand)
+                            .await?;
+                        if filled {
+                            self.state = BotState::Scanning;
+                        }
+                        return Ok(());
+                    }
+
+                    let peak_bid = self.peak_bid.map_or(held_bid, |peak| peak.max(held_bid));
+                    self.peak_bid = Some(peak_bid);
+                    self.held_ticks += 1;
+
                     let take_profit = QuantEngine::calculate_take_profit(
+                        self.config.tp_sl_mode,
                         pos.entry_price,
                         self.config.scalp_profit,
+                        self.config.take_profit_price,
                     );
                     let stop_loss = QuantEngine::calculate_stop_loss(
+                        self.config.tp_sl_mode,
                         pos.entry_price,
                         self.config.stop_loss_threshold,
+                        self.config.stop_loss_price,
+                    );
+                    let trailing = self.config.trailing_stop_distance > Decimal::ZERO;
+                    let trailing_stop = QuantEngine::calculate_trailing_stop(
+                        pos.entry_price,
+                        peak_bid,
+                        self.config.trailing_stop_activation,
+                        self.config.trailing_stop_distance,
+                    );
+
+                    let last_trade = match &self.held_book {
+                        Some(book) if pos.token_id != token_id => book.last_trade(),
+                        _ => self.last_trade,
+                    };
+                    let take_profit_reference = QuantEngine::take_profit_reference(
+                        self.config.take_profit_trigger,
+                        held_bid,
+                        held_ask,
+                        last_trade,
                     );
 
-                    // Check take profit
-                    if best_bid >= take_profit {
-                        info!("💰 Take profit triggered @ {:.4}", best_bid);
+                    // Check take profit (the trailing stop replaces it when enabled)
+                    if !trailing && take_profit_reference >= take_profit {
+                        info!(
+                            "💰 Take profit triggered @ {:.4} ({} {:.4})",
+                            held_bid, self.config.take_profit_trigger, take_profit_reference
+                        );
                         self.trading
-                            .sell(token_id, best_bid, pos.shares)
+                            .sell(&pos.token_id, held_bid, pos.shares, ExitReason::TakeProfit)
                             .await?;
                         self.state = BotState::Scanning;
                     }
+                    // Check trailing stop
+                    else if let Some(trigger) = trailing_stop.filter(|trigger| held_bid <= *trigger) {
+                        info!(
+                            "📉 Trailing stop triggered @ {:.4} (peak {:.4}, trigger {:.4})",
+                            held_bid, peak_bid, trigger
+                        );
+                        let filled = self
+                            .exit_now(&pos.token_id, held_bid, pos.shares, ExitReason::TrailingStop)
+                            .await?;
+                        if filled {
+                            self.state = BotState::Scanning;
+                        }
+                    }
                     // Check stop loss
-                    else if best_bid <= stop_loss {
-                        warn!("🛑 Stop loss triggered @ {:.4}", best_bid);
-                        self.trading
-                            .execute_market_order(token_id, models::OrderSide::SELL, best_bid, pos.shares)
+                    else if held_bid <= stop_loss {
+                        warn!("🛑 Stop loss triggered @ {:.4}", held_bid);
+                        let filled = self
+                            .exit_now(&pos.token_id, held_bid, pos.shares, ExitReason::StopLoss)
                             .await?;
-                        self.state = BotState::Scanning;
+                        if filled {
+                            self.state = BotState::Scanning;
+                        }
+                    }
+                    // Free capital tied up in a position that isn't going anywhere
+                    else if self.config.max_hold_ticks > 0 && self.held_ticks > self.config.max_hold_ticks {
+                        info!(
+                            "⌛ Held {} ticks without TP/SL (max {}) - exiting @ {:.4}",
+                            self.held_ticks, self.config.max_hold_ticks, held_bid
+                        );
+                        let filled = self
+                            .exit_now(&pos.token_id, held_bid, pos.shares, ExitReason::MaxHold)
+                            .await?;
+                        if filled {
+                            self.state = BotState::Scanning;
+                        }
+                    }
+                    // Average down into a better entry, only while the held token is
+                    // still the selected one: the other outcome's ask says nothing about ours
+                    else if pos.token_id == token_id
+                        && self.scale_ins < self.config.max_scale_ins
+                        && best_ask <= pos.entry_price - self.config.scale_in_step
+                        && QuantEngine::has_min_edge(fair_value, best_ask, self.config.min_edge)
+                        && self.trading.open_order_ids().await.is_empty()
+                    {
+                        self.scale_in(token_id, &pos, best_bid, best_ask).await?;
                     }
                 }
             }
@@ -462,27 +1454,427 @@ impl TradingBot {
         Ok(())
     }
 
+    /// The held token's bid and ask: this tick's quotes when it is the traded
+    /// token, otherwise its own book's
+    fn held_quotes(
+        &self,
+        pos: &models::Position,
+        token_id: &str,
+        best_bid: Decimal,
+        best_ask: Decimal,
+    ) -> Option<(Decimal, Decimal)> {
+        if pos.token_id == token_id {
+            return Some((best_bid, best_ask));
+        }
+        let book = self.held_book.as_ref()?;
+        self.actionable_quotes(book.best_bid(), book.best_ask())
+    }
+
+    /// Add one entry-sized buy to the position at the ask
+    ///
+    /// An add is a fresh entry into the same token, so it passes the same
+    /// gates: halted entries, a stale book, the strike distance, the entry
+    /// band, the depth limit and the cash ratio.
+    async fn scale_in(
+        &mut self,
+        token_id: &str,
+        pos: &models::Position,
+        best_bid: Decimal,
+        best_ask: Decimal,
+    ) -> Result<()> {
+        if self.risk.entries_halted() {
+            return Ok(());
+        }
+        if self.entries_blocked(token_id).await || self.outside_entry_band(best_ask) {
+            return Ok(());
+        }
+
+        let price = match self.config.execution_mode {
+            ExecutionMode::Limit => best_ask,
+            ExecutionMode::Snipe => (best_ask + self.config.snipe_cushion).min(Decimal::new(99, 2)),
+        };
+        let size = QuantEngine::calculate_position_size(self.entry_capital(), price);
+        let Some(size) = self.cap_to_depth(size).and_then(|size| self.order_size(size)) else {
+            info!("⏭️ No capital or depth left to scale in @ {:.4}", price);
+            return Ok(());
+        };
+        if !self.preserves_cash_ratio(token_id, best_bid, price * size).await? {
+            return Ok(());
+        }
+
+        info!(
+            "➕ Scale-in {}/{}: BUY {} @ {:.4} (avg entry {:.4} on {} shares)",
+            self.scale_ins + 1,
+            self.config.max_scale_ins,
+            size,
+            price,
+            pos.entry_price,
+            pos.shares
+        );
+        let placed = match self.config.execution_mode {
+            // Tracked like an entry so flatten and rotation cancel it
+            ExecutionMode::Limit => self.trading.buy(token_id, price, size).await.map(|order_id| {
+                self.active_order_id = Some(order_id);
+                true
+            }),
+            ExecutionMode::Snipe => {
+                self.trading
+                    .snipe(token_id, models::OrderSide::BUY, price, size, None)
+                    .await
+            }
+        };
+        match placed {
+            Ok(true) => {
+                self.scale_ins += 1;
+                self.market_spent += price * size;
+                self.broadcast(BotEvent::OrderPlaced { token_id, price, size }).await;
+            }
+            Ok(false) => {}
+            Err(e) => warn!("⚠️ Scale-in skipped: {}", e),
+        }
+        Ok(())
+    }
+
+    /// Whether a stale book or a spot too close to the strike rules out buying
+    async fn entries_blocked(&mut self, token_id: &str) -> bool {
+        // A book that has stopped updating won't give us a real fill
+        if self
+            .book_activity
+            .is_stale(token_id, chrono::Utc::now().timestamp_millis())
+        {
+            info!("💤 Order book update rate below floor - skipping entry");
+            if !self.entries_paused {
+                self.entries_paused = true;
+                let reason = "Order book update rate below floor - entries paused";
+                self.broadcast(BotEvent::Halted { reason }).await;
+            }
+            return true;
+        }
+        self.entries_paused = false;
+
+        // Near the strike the market is a coin flip with little edge to find
+        if let (Some(spot), Some(market)) = (self.spot_price, &self.current_market) {
+            let distance = (spot - market.strike_price).abs();
+            if self.config.min_strike_distance > Decimal::ZERO && distance <= self.config.min_strike_distance {
+                info!(
+                    "🪙 Spot ${:.2} is within ${} of the ${:.2} strike - skipping entry",
+                    spot, self.config.min_strike_distance, market.strike_price
+                );
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Whether the ask is outside `[MIN_ENTRY_PRICE, MAX_ENTRY_PRICE]`, or in
+    /// absolute mode not strictly between `STOP_LOSS_PRICE` and `TAKE_PROFIT_PRICE`
+    fn outside_entry_band(&self, best_ask: Decimal) -> bool {
+        // Outside the band the risk/reward is poor however big the edge looks
+        let outside = best_ask < self.config.min_entry_price || best_ask > self.config.max_entry_price;
+        if outside {
+            info!(
+                "🚧 Ask {:.4} outside the entry band [{:.2}, {:.2}] - skipping entry",
+                best_ask, self.config.min_entry_price, self.config.max_entry_price
+            );
+            return true;
+        }
+
+        // An entry already at an absolute level would exit on the next tick
+        let (stop_loss, take_profit) = (self.config.stop_loss_price, self.config.take_profit_price);
+        let past_level =
+            self.config.tp_sl_mode == TpSlMode::Absolute && (best_ask <= stop_loss || best_ask >= take_profit);
+        if past_level {
+            info!(
+                "🚧 Ask {:.4} not inside the TP/SL band ({:.2}, {:.2}) - skipping entry",
+                best_ask, stop_loss, take_profit
+            );
+        }
+        past_level
+    }
+
+    /// Take no more than MAX_DEPTH_LEVELS / MAX_DEPTH_SHARES of the asks; None if none is left
+    fn cap_to_depth(&self, size: Decimal) -> Option<Decimal> {
+        let Some(book) = &self.traded_book else {
+            return Some(size);
+        };
+        let capped = QuantEngine::cap_to_depth(book, size, self.config.max_depth_levels, self.config.max_depth_shares);
+        if capped <= Decimal::ZERO {
+            info!("🪜 No ask depth within the limit - skipping entry");
+            return None;
+        }
+        if capped < size {
+            info!("🪜 Capping entry at {} of {} shares to limit market impact", capped, size);
+        }
+        Some(capped)
+    }
+
+    /// Whether spending `cost` keeps MIN_CASH_RATIO of dry powder for averaging
+    /// down or the next opportunity
+    async fn preserves_cash_ratio(&self, token_id: &str, best_bid: Decimal, cost: Decimal) -> Result<bool> {
+        if self.config.min_cash_ratio <= Decimal::ZERO {
+            return Ok(true);
+        }
+        let cash = self.free_cash().await?;
+        let held = self.position_value(token_id, best_bid).await;
+        let preserved = QuantEngine::preserves_cash_ratio(cash, held, cost, self.config.min_cash_ratio);
+        if !preserved {
+            info!(
+                "🧯 ${:.2} entry would leave ${:.2} cash against ${:.2} of positions (min ratio {}) - skipping entry",
+                cost,
+                cash - cost,
+                held + cost,
+                self.config.min_cash_ratio
+            );
+        }
+        Ok(preserved)
+    }
+
+    /// Capital for one buy: `MAX_CAPITAL_PER_TRADE`, capped by the strategy's
+    /// sub-account and by what's left of this market's budget
+    fn entry_capital(&self) -> Decimal {
+        let mut capital = self.config.max_capital_per_trade;
+        if let Some(available) = self.ledger.available(self.config.strategy.ledger_name()) {
+            capital = capital.min(available.max(Decimal::ZERO));
+        }
+        if self.config.per_market_budget > Decimal::ZERO {
+            let remaining = self.config.per_market_budget - self.market_spent;
+            capital = capital.min(remaining.max(Decimal::ZERO));
+        }
+        capital
+    }
+
+    /// Spread capture: rest a bid under fair value and offer held inventory above it
+    async fn make_market(
+        &mut self,
+        token_id: &str,
+        fair_value: Decimal,
+        best_bid: Decimal,
+        best_ask: Decimal,
+    ) -> Result<()> {
+        // Quotes that have left the book since last tick were filled
+        let open_orders = self.trading.open_order_ids().await;
+        for (side, slot) in [("Bid", &mut self.quotes.bid), ("Ask", &mut self.quotes.ask)] {
+            if let Some(quote) = slot.take_if(|quote| !open_orders.contains(&quote.order_id)) {
+                info!("🤝 {} quote filled @ {:.4} (Size: {})", side, quote.price, quote.size);
+            }
+        }
+
+        let position = self.trading.get_position().await;
+        if position.as_ref().is_some_and(|pos| pos.token_id != token_id) {
+            warn!("🔀 Inventory is in the other outcome - flattening before quoting");
+            return self.flatten(ExitReason::Manual).await;
+        }
+        let (inventory, inventory_cost) = position
+            .map_or((Decimal::ZERO, Decimal::ZERO), |pos| (pos.shares, pos.entry_price * pos.shares));
+
+        // MAX_CAPITAL_PER_TRADE caps inventory plus the resting bid
+        let mut capital = (self.config.max_capital_per_trade - inventory_cost).max(Decimal::ZERO);
+        if let Some(available) = self.ledger.available(self.config.strategy.ledger_name()) {
+            capital = capital.min(available.max(Decimal::ZERO));
+        }
+
+        // Lean the quotes toward flat as inventory builds
+        let max_inventory = QuantEngine::calculate_position_size(self.config.max_capital_per_trade, fair_value);
+        let inventory_ratio = if max_inventory > Decimal::ZERO {
+            (inventory / max_inventory).min(Decimal::ONE)
+        } else {
+            Decimal::ZERO
+        };
+        let (bid_price, ask_price) = market_making::quote_prices(
+            fair_value,
+            inventory_ratio,
+            self.config.quote_offset,
+            self.config.inventory_skew,
+            best_bid,
+            best_ask,
+        );
+        let bid_size = if self.risk.entries_halted() {
+            Decimal::ZERO
+        } else {
+            self.order_size(QuantEngine::calculate_position_size(capital, bid_price))
+                .unwrap_or(Decimal::ZERO)
+        };
+        self.requote(token_id, models::OrderSide::BUY, bid_price, bid_size).await;
+        self.requote(token_id, models::OrderSide::SELL, ask_price, inventory).await;
+        Ok(())
+    }
+
+    /// Round an order size to `SIZE_STEP`, or None if that leaves it under `MIN_ORDER_SIZE`
+    fn order_size(&self, size: Decimal) -> Option<Decimal> {
+        let size = QuantEngine::round_to_step(size, self.config.size_step);
+        (size > Decimal::ZERO && size >= self.config.min_order_size).then_some(size)
+    }
+
+    /// Keep one side's quote at `price` for `size`, replacing it when it drifts
+    ///
+    /// A size of zero pulls the quote.
+    async fn requote(&mut self, token_id: &str, side: models::OrderSide, price: Decimal, size: Decimal) {
+        let slot = match side {
+            models::OrderSide::BUY => &mut self.quotes.bid,
+            models::OrderSide::SELL => &mut self.quotes.ask,
+        };
+
+        if let Some(quote) = slot.take() {
+            if !quote.is_stale(price, size) {
+                *slot = Some(quote);
+                return;
+            }
+            // A quote we can't cancel has just filled; the next tick picks it up
+            if self.trading.cancel_order(&quote.order_id).await.is_err() {
+                return;
+            }
+        }
+        if size <= Decimal::ZERO {
+            return;
+        }
+
+        let placed = match side {
+            models::OrderSide::BUY => self.trading.buy(token_id, price, size).await,
+            models::OrderSide::SELL => {
+                self.trading
+                    .sell(token_id, price, size, ExitReason::SpreadCapture)
+                    .await
+            }
+        };
+        match placed {
+            Ok(order_id) => {
+                info!("📌 Quoting {:?} {} @ {:.4}", side, size, price);
+                *slot = Some(Quote { order_id, price, size });
+            }
+            Err(e) => warn!("⚠️ {:?} quote failed: {}", side, e),
+        }
+    }
+
+    /// Sell immediately: a market order, or a dump `DUMP_CUSHION` under the bid in snipe mode
+    ///
+    /// Resting exits on the token are pulled first so they don't hold the shares.
+    async fn exit_now(
+        &self,
+        token_id: &str,
+        best_bid: Decimal,
+        shares: Decimal,
+        reason: ExitReason,
+    ) -> TradingResult<bool> {
+        self.trading.cancel_resting_sells(token_id).await?;
+        match self.config.execution_mode {
+            ExecutionMode::Limit => {
+                self.trading
+                    .execute_market_order(token_id, models::OrderSide::SELL, best_bid, shares, Some(reason))
+                    .await
+            }
+            ExecutionMode::Snipe => {
+                let price = (best_bid - self.config.dump_cushion).max(Decimal::new(1, 2));
+                self.trading
+                    .snipe(token_id, models::OrderSide::SELL, price, shares, Some(reason))
+                    .await
+            }
+        }
+    }
+
+    /// Send an event to every notification sink
+    async fn broadcast(&self, event: BotEvent<'_>) {
+        for sink in &self.notifiers {
+            sink.notify(&event).await;
+        }
+    }
+
+    /// Move closed trades from the trading service into the session log
+    async fn record_closed_trades(&mut self) {
+        for trade in self.trading.take_closed_trades().await {
+            self.total_pnl += trade.pnl;
+            self.ledger.record_trade(self.config.strategy.ledger_name(), &trade);
+            let cash = self.trading.get_cash_balance().await;
+            self.broadcast(BotEvent::Exited { trade: &trade, cash }).await;
+            self.logger.log_trade(trade).await;
+        }
+
+        // Keep the sub-account's open cost in line with the position
+        let position = self.trading.get_position().await;
+        let open_cost = match &position {
+            Some(pos) => pos.entry_price * pos.shares,
+            None => Decimal::ZERO,
+        };
+        self.ledger.set_open_cost(self.config.strategy.ledger_name(), open_cost);
+
+        // Report shares bought since the last check
+        let shares = position.as_ref().map_or(Decimal::ZERO, |pos| pos.shares);
+        if let Some(pos) = position.filter(|_| shares > self.position_shares) {
+            let cash = self.trading.get_cash_balance().await;
+            self.broadcast(BotEvent::Filled {
+                token_id: &pos.token_id,
+                entry_price: pos.entry_price,
+                size: shares - self.position_shares,
+                cash,
+            })
+            .await;
+        }
+        self.position_shares = shares;
+    }
+
+    /// Attribute limit order fills since the last call to the current market
+    async fn record_fill_stats(&self) {
+        let stats = self.trading.take_fill_stats().await;
+        let slug = self
+            .current_market
+            .as_ref()
+            .map_or("unknown", |market| market.slug.as_str());
+        self.logger.log_fill_stats(slug, stats).await;
+    }
+
     /// Shutdown bot gracefully
     async fn shutdown(&mut self) -> Result<()> {
         info!("📊 Flushing session data...");
+        self.record_closed_trades().await;
+        self.record_fill_stats().await;
 
         let final_cash = self.trading.get_cash_balance().await;
+        let stats = self.logger.trade_stats().await;
+        self.broadcast(BotEvent::Shutdown {
+            total_pnl: self.total_pnl,
+            final_cash,
+            trades: stats.total_trades,
+            win_rate: stats.win_rate,
+        })
+        .await;
         self.logger.flush(self.total_pnl, final_cash).await?;
+        self.ledger.print_summary();
 
         info!("✅ Shutdown complete");
         Ok(())
     }
 }
 
+/// Tracing filter for the configured level (raw API bodies are logged at debug level)
+fn log_filter(config: &BotConfig) -> String {
+    let level = config.log_level.trim().to_lowercase();
+    if config.debug_api {
+        format!("{},polymarket_vulture_bot::slug_oracle=debug", level)
+    } else {
+        level
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize tracing
-    tracing_subscriber::fmt()
-        .with_env_filter("info")
-        .init();
+    let cli = cli::Cli::parse();
+    if cli.dump_config_template {
+        print!("{}", BotConfig::template()?);
+        return Ok(());
+    }
 
-    // Load configuration
-    let config = BotConfig::from_env()?;
+    // Load configuration: defaults, file, environment, then flags
+    let config = BotConfig::load(cli.config.as_deref(), cli.overrides())?;
+
+    // Initialize tracing
+    let filter = log_filter(&config);
+    match config.log_format {
+        LogFormat::Text => tracing_subscriber::fmt().with_env_filter(filter).init(),
+        LogFormat::Json => tracing_subscriber::fmt()
+            .json()
+            .flatten_event(true)
+            .with_env_filter(filter)
+            .init(),
+    }
 
     // Create and start bot
     let mut bot = TradingBot::new(config).await?;
@@ -490,3 +1882,1336 @@ async fn main() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn test_config() -> BotConfig {
+        BotConfig {
+            force_exit_before_expiry_seconds: 60,
+            ..BotConfig::default()
+        }
+    }
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    fn test_market() -> MarketInfo {
+        MarketInfo {
+            slug: "btc-updown-15m-test".to_string(),
+            token_id_up: "up-token".to_string(),
+            token_id_down: "down-token".to_string(),
+            strike_price: Decimal::from(98500),
+            expiry_timestamp: chrono::Utc::now().timestamp_millis() + 10 * 60 * 1000,
+            strike_finalized: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tick_with_mock_price_source() {
+        let mut bot = TradingBot::new(BotConfig::default()).await.unwrap();
+        let mock = Arc::new(price_source::MockPriceSource::new(vec![None]));
+        bot.price_source = mock.clone();
+        bot.current_market = Some(test_market());
+
+        // No spot price yet: the tick bails out before touching the order book
+        bot.tick().await.unwrap();
+        assert_eq!(bot.tick_count, 1);
+        assert_eq!(mock.calls(), 1);
+        assert_eq!(bot.state, BotState::Scanning);
+        assert!(!bot.trading.has_position().await);
+    }
+
+    #[tokio::test]
+    async fn test_force_direction_down_above_strike() {
+        let config = BotConfig {
+            force_direction: models::ForceDirection::Down,
+            ..BotConfig::default()
+        };
+        let bot = TradingBot::new(config).await.unwrap();
+        let market = test_market();
+
+        let (direction, fair, token) = bot.select_trade(&market, Decimal::from(99000), 10.0).unwrap();
+        assert_eq!(direction, "DOWN");
+        assert_eq!(token, "down-token");
+        assert!(fair < dec("0.50"));
+
+        // A forced side without a token ID is rejected
+        let missing = MarketInfo {
+            token_id_down: String::new(),
+            ..market
+        };
+        assert!(bot.select_trade(&missing, Decimal::from(99000), 10.0).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_forced_exit_near_expiry() {
+        let mut bot = TradingBot::new(test_config()).await.unwrap();
+        bot.trading
+            .execute_market_order("token", models::OrderSide::BUY, dec("0.50"), dec("10"), None)
+            .await
+            .unwrap();
+        bot.state = BotState::InPosition;
+
+        // Price between stop loss and take profit, well before the window: hold
+        bot.execute_strategy("token", dec("0.55"), dec("0.50"), dec("0.51"), 5.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::InPosition);
+        assert!(bot.trading.has_position().await);
+
+        // Inside the forced-exit window: flatten at the book bid
+        bot.execute_strategy("token", dec("0.55"), dec("0.50"), dec("0.51"), 0.5)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::Scanning);
+        assert!(!bot.trading.has_position().await);
+
+        let trades = bot.trading.take_closed_trades().await;
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].exit_reason, ExitReason::ExpiryExit);
+        assert_eq!(trades[0].exit_price, dec("0.50"));
+    }
+
+    #[tokio::test]
+    async fn test_forced_exit_sells_held_token_after_flip() {
+        use models::OrderBook;
+
+        let mut bot = TradingBot::new(test_config()).await.unwrap();
+        bot.trading
+            .execute_market_order("down-token", models::OrderSide::BUY, dec("0.50"), dec("10"), None)
+            .await
+            .unwrap();
+        bot.state = BotState::InPosition;
+
+        // Spot crossed the strike: this tick trades UP, but DOWN is what we hold
+        bot.held_book = Some(OrderBook::top("0.28", "0.30"));
+        bot.execute_strategy("up-token", dec("0.70"), dec("0.69"), dec("0.71"), 0.5)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::Scanning);
+        assert!(!bot.trading.has_position().await);
+
+        let trades = bot.trading.take_closed_trades().await;
+        assert_eq!(trades[0].token_id, "down-token");
+        assert_eq!(trades[0].exit_price, dec("0.28"));
+    }
+
+    #[tokio::test]
+    async fn test_stops_track_held_token_after_flip() {
+        use models::OrderBook;
+
+        let config = BotConfig {
+            trailing_stop_distance: dec("0.03"),
+            trailing_stop_activation: dec("0.02"),
+            ..test_config()
+        };
+        let mut bot = TradingBot::new(config).await.unwrap();
+        bot.trading
+            .execute_market_order("down-token", models::OrderSide::BUY, dec("0.50"), dec("10"), None)
+            .await
+            .unwrap();
+        bot.state = BotState::InPosition;
+
+        // UP rallies after the cross; the DOWN bid held peaks at 0.56
+        bot.held_book = Some(OrderBook::top("0.56", "0.57"));
+        bot.execute_strategy("up-token", dec("0.50"), dec("0.80"), dec("0.81"), 10.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.peak_bid, Some(dec("0.56")));
+        assert_eq!(bot.state, BotState::InPosition);
+
+        // The DOWN bid falls back through the trail while UP stays high
+        bot.held_book = Some(OrderBook::top("0.52", "0.53"));
+        bot.execute_strategy("up-token", dec("0.50"), dec("0.82"), dec("0.83"), 10.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::Scanning);
+
+        let trades = bot.trading.take_closed_trades().await;
+        assert_eq!(trades[0].token_id, "down-token");
+        assert_eq!(trades[0].exit_reason, ExitReason::TrailingStop);
+        assert_eq!(trades[0].exit_price, dec("0.52"));
+    }
+
+    #[tokio::test]
+    async fn test_stop_loss_pulls_resting_exit() {
+        let mut bot = TradingBot::new(test_config()).await.unwrap();
+        bot.trading
+            .execute_market_order("token", models::OrderSide::BUY, dec("0.50"), dec("10"), None)
+            .await
+            .unwrap();
+        bot.trading.sell("token", dec("0.90"), dec("10"), ExitReason::TakeProfit).await.unwrap();
+        bot.state = BotState::InPosition;
+
+        // The resting take profit holds every share; the stop still gets them
+        bot.execute_strategy("token", dec("0.50"), dec("0.30"), dec("0.31"), 10.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::Scanning);
+        assert!(bot.trading.open_order_ids().await.is_empty());
+        let trades = bot.trading.take_closed_trades().await;
+        assert_eq!(trades[0].exit_reason, ExitReason::StopLoss);
+        assert_eq!(trades[0].size, dec("10"));
+    }
+
+    #[tokio::test]
+    async fn test_max_hold_exits_flat_position() {
+        let config = BotConfig {
+            max_hold_ticks: 3,
+            ..test_config()
+        };
+        let mut bot = TradingBot::new(config).await.unwrap();
+        bot.trading
+            .execute_market_order("token", models::OrderSide::BUY, dec("0.50"), dec("10"), None)
+            .await
+            .unwrap();
+        bot.state = BotState::InPosition;
+
+        // Flat between stop loss and take profit: held up to the limit
+        for _ in 0..3 {
+            bot.execute_strategy("token", dec("0.55"), dec("0.50"), dec("0.51"), 5.0)
+                .await
+                .unwrap();
+            assert_eq!(bot.state, BotState::InPosition);
+        }
+
+        // One tick past it, with spot across the strike: exit the held token at its own bid
+        bot.held_book = Some(models::OrderBook::top("0.49", "0.51"));
+        bot.execute_strategy("other-token", dec("0.45"), dec("0.40"), dec("0.42"), 5.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::Scanning);
+        assert!(!bot.trading.has_position().await);
+
+        let trades = bot.trading.take_closed_trades().await;
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].exit_reason, ExitReason::MaxHold);
+        assert_eq!(trades[0].token_id, "token");
+        assert_eq!(trades[0].exit_price, dec("0.49"));
+    }
+
+    #[tokio::test]
+    async fn test_market_making_captures_spread() {
+        let config = BotConfig {
+            strategy: Strategy::MarketMaking,
+            quote_offset: dec("0.02"),
+            max_capital_per_trade: dec("10"),
+            ..test_config()
+        };
+        let mut bot = TradingBot::new(config).await.unwrap();
+
+        // Flat: only a bid, sized to the capital cap
+        bot.execute_strategy("up-token", dec("0.50"), dec("0.47"), dec("0.53"), 5.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.quotes.bid.as_ref().map(|q| (q.price, q.size)), Some((dec("0.48"), dec("20"))));
+        assert!(bot.quotes.ask.is_none());
+        bot.trading.check_paper_fills("up-token", dec("0.48"), dec("0.46")).await;
+
+        // Holding a full inventory: offer it out, no new bid
+        bot.execute_strategy("up-token", dec("0.50"), dec("0.47"), dec("0.53"), 5.0)
+            .await
+            .unwrap();
+        assert!(bot.quotes.bid.is_none());
+        assert_eq!(bot.quotes.ask.as_ref().map(|q| (q.price, q.size)), Some((dec("0.52"), dec("20"))));
+        bot.trading.check_paper_fills("up-token", dec("0.54"), dec("0.52")).await;
+
+        // Both sides filled: the spread is the P&L
+        let trades = bot.trading.take_closed_trades().await;
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].pnl, dec("0.80"));
+        assert_eq!(trades[0].exit_reason, ExitReason::SpreadCapture);
+        assert!(!bot.trading.has_position().await);
+
+        // Rotation pulls the resting quotes and leaves nothing behind
+        bot.execute_strategy("up-token", dec("0.50"), dec("0.47"), dec("0.53"), 5.0)
+            .await
+            .unwrap();
+        assert!(bot.quotes.bid.is_some());
+        bot.rotate_market().await.unwrap();
+        assert!(bot.quotes.bid.is_none());
+        assert!(bot.trading.open_order_ids().await.is_empty());
+        assert!(!bot.trading.has_position().await);
+    }
+
+    #[tokio::test]
+    async fn test_missed_checkin_flattens_and_halts() {
+        let config = BotConfig {
+            dead_man_interval: 60,
+            ..test_config()
+        };
+        let mut bot = TradingBot::new(config).await.unwrap();
+        let start = chrono::Utc::now().timestamp_millis();
+        bot.trading
+            .execute_market_order("token", models::OrderSide::BUY, dec("0.50"), dec("10"), None)
+            .await
+            .unwrap();
+        bot.trading.update_paper_book("token", models::OrderBook::top("0.50", "0.51")).await;
+        bot.state = BotState::InPosition;
+
+        // A /checkin inside the window keeps the bot running past the first deadline
+        assert!(!bot.check_dead_mans_switch(start + 30_000).await.unwrap());
+        bot.health.write().await.last_checkin_ms = Some(start + 50_000);
+        assert!(!bot.check_dead_mans_switch(start + 100_000).await.unwrap());
+        assert!(bot.trading.has_position().await);
+
+        // Silence past the window: flatten and halt
+        assert!(bot.check_dead_mans_switch(start + 111_000).await.unwrap());
+        assert!(bot.halted);
+        assert_eq!(bot.state, BotState::Scanning);
+        assert!(!bot.trading.has_position().await);
+        assert_eq!(bot.total_pnl, Decimal::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_flatten_sells_under_bid_and_reports_leftovers() {
+        let mut bot = TradingBot::new(test_config()).await.unwrap();
+        bot.trading
+            .execute_market_order("token", models::OrderSide::BUY, dec("0.50"), dec("10"), None)
+            .await
+            .unwrap();
+        bot.trading.sell("token", dec("0.90"), dec("10"), ExitReason::TakeProfit).await.unwrap();
+
+        // No bid to sell into: the resting exit is still cancelled, but the shares stay and it errors
+        assert!(bot.flatten(ExitReason::Manual).await.is_err());
+        assert!(bot.trading.open_order_ids().await.is_empty());
+        assert!(bot.trading.has_position().await);
+
+        // With a bid the position closes
+        bot.trading.update_paper_book("token", models::OrderBook::top("0.55", "0.56")).await;
+        bot.flatten(ExitReason::Manual).await.unwrap();
+        assert!(!bot.trading.has_position().await);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_sigusr1_flattens_and_keeps_running() {
+        let mut bot = TradingBot::new(test_config()).await.unwrap();
+        bot.trading
+            .execute_market_order("token", models::OrderSide::BUY, dec("0.50"), dec("10"), None)
+            .await
+            .unwrap();
+        bot.trading.buy("other", dec("0.30"), dec("10")).await.unwrap();
+        bot.state = BotState::InPosition;
+
+        listen_for_flatten(bot.health.clone()).unwrap();
+        let sent = std::process::Command::new("kill")
+            .args(["-USR1", &std::process::id().to_string()])
+            .status()
+            .unwrap();
+        assert!(sent.success());
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !bot.health.read().await.flatten_requested {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+
+        // With no bid to sell into the flatten fails and the request stays pending
+        assert!(bot.tick().await.is_err());
+        assert!(bot.trading.has_position().await);
+        assert!(bot.health.read().await.flatten_requested);
+
+        // The next tick cancels even untracked orders, flattens, and keeps running
+        bot.trading.update_paper_book("token", models::OrderBook::top("0.50", "0.51")).await;
+        bot.tick().await.unwrap();
+        assert!(!bot.trading.has_position().await);
+        assert!(bot.trading.open_order_ids().await.is_empty());
+        assert_eq!(bot.state, BotState::Scanning);
+        assert!(!bot.halted);
+        assert!(!bot.health.read().await.flatten_requested);
+    }
+
+    #[tokio::test]
+    async fn test_position_drift_logged_and_corrected() {
+        let mut bot = TradingBot::new(test_config()).await.unwrap();
+
+        // Flat, shares turning up in the wallet are untracked drift
+        assert!(!bot.reconcile_position("token", Decimal::ZERO).await);
+        assert!(bot.reconcile_position("token", dec("8")).await);
+        assert!(!bot.trading.has_position().await);
+
+        bot.trading
+            .execute_market_order("token", models::OrderSide::BUY, dec("0.50"), dec("10"), None)
+            .await
+            .unwrap();
+
+        // Log-only: the drift is reported and the position left as tracked
+        assert!(!bot.reconcile_position("token", dec("10")).await);
+        assert!(!bot.reconcile_position("token", dec("10.005")).await, "dust is within tolerance");
+        assert!(!bot.reconcile_position("other", dec("3")).await, "only the held token is compared");
+        assert!(bot.reconcile_position("token", dec("8")).await);
+        assert_eq!(bot.trading.get_position().await.unwrap().shares, dec("10"));
+
+        bot.config.reconcile_mode = ReconcileMode::Correct;
+        assert!(bot.reconcile_position("token", dec("8")).await);
+        assert_eq!(bot.trading.get_position().await.unwrap().shares, dec("8"));
+        assert!(!bot.reconcile_position("token", dec("8")).await);
+
+        // Nothing left in the wallet drops the position
+        assert!(bot.reconcile_position("token", Decimal::ZERO).await);
+        assert!(!bot.trading.has_position().await);
+    }
+
+    #[tokio::test]
+    async fn test_forced_exit_skipped_when_flat() {
+        let mut bot = TradingBot::new(test_config()).await.unwrap();
+
+        // Ask far above the entry target so no new entry is placed either
+        bot.execute_strategy("token", dec("0.50"), dec("0.60"), dec("0.61"), 0.5)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::Scanning);
+        assert!(bot.trading.take_closed_trades().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_stale_book_blocks_entry() {
+        let config = BotConfig {
+            min_book_update_rate: dec("0.5"),
+            book_rate_window: 10,
+            ..BotConfig::default()
+        };
+        let mut bot = TradingBot::new(config).await.unwrap();
+        let now = chrono::Utc::now().timestamp_millis();
+
+        // A book frozen for the whole window blocks entries, even at a bargain
+        bot.book_activity.record("up-token", Some(dec("0.30")), Some(dec("0.32")), now - 10_000);
+        bot.execute_strategy("up-token", dec("0.60"), dec("0.30"), dec("0.32"), 10.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::Scanning);
+        assert!(bot.active_order_id.is_none());
+
+        // A book that keeps moving permits them
+        for i in 0..10 {
+            let bid = dec("0.30") + Decimal::new(i, 3);
+            bot.book_activity
+                .record("up-token", Some(bid), Some(dec("0.32")), now - 9_000 + i * 1_000);
+        }
+        bot.execute_strategy("up-token", dec("0.60"), dec("0.30"), dec("0.32"), 10.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::InPosition);
+        assert!(bot.active_order_id.is_some());
+    }
+
+    #[test]
+    fn test_tick_jitter_bounds() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+
+        // No jitter keeps the fixed cadence
+        assert_eq!(jittered_tick_interval(500, 0, &mut rng), Duration::from_millis(500));
+
+        let sleeps: Vec<Duration> = (0..1000)
+            .map(|_| jittered_tick_interval(500, 100, &mut rng))
+            .collect();
+        assert!(sleeps
+            .iter()
+            .all(|sleep| *sleep >= Duration::from_millis(400) && *sleep <= Duration::from_millis(600)));
+
+        // Actually randomized, on both sides of the base interval
+        assert!(sleeps.iter().any(|sleep| *sleep < Duration::from_millis(500)));
+        assert!(sleeps.iter().any(|sleep| *sleep > Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn test_spot_checked_against_reference_microprice() {
+        let book = ReferenceBook {
+            best_bid: dec("60000"),
+            best_ask: dec("60010"),
+            bid_size: dec("1"),
+            ask_size: dec("1"),
+        };
+
+        // Within 0.1% of the 60005 microprice either way
+        assert!(!strays_from_reference(dec("60005"), &book));
+        assert!(!strays_from_reference(dec("59950"), &book));
+        assert!(!strays_from_reference(dec("60060"), &book));
+
+        // Past it
+        assert!(strays_from_reference(dec("60100"), &book));
+        assert!(strays_from_reference(dec("59900"), &book));
+
+        // A heavy bid leans the microprice toward the ask
+        let leaning = ReferenceBook { bid_size: dec("9"), ..book };
+        assert_eq!(leaning.microprice(), dec("60009"));
+
+        // An empty book never flags
+        let empty = ReferenceBook {
+            best_bid: Decimal::ZERO,
+            best_ask: Decimal::ZERO,
+            bid_size: Decimal::ZERO,
+            ask_size: Decimal::ZERO,
+        };
+        assert!(!strays_from_reference(dec("60000"), &empty));
+    }
+
+    #[tokio::test]
+    async fn test_per_market_budget_resets_on_rotation() {
+        let config = BotConfig {
+            per_market_budget: dec("10"),
+            ..test_config()
+        };
+        let mut bot = TradingBot::new(config).await.unwrap();
+        bot.current_market = Some(test_market());
+
+        // The budget, not MAX_CAPITAL_PER_TRADE, caps the first entry
+        bot.execute_strategy("up-token", dec("0.60"), dec("0.38"), dec("0.40"), 10.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::InPosition);
+        assert_eq!(bot.market_spent, dec("10"));
+
+        // With the budget spent, a second entry in the same market is refused
+        bot.state = BotState::Scanning;
+        bot.active_order_id = None;
+        bot.execute_strategy("up-token", dec("0.60"), dec("0.38"), dec("0.40"), 10.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::Scanning);
+
+        // Rotation hands the next market a fresh budget
+        bot.rotate_market().await.unwrap();
+        assert_eq!(bot.market_spent, Decimal::ZERO);
+        bot.current_market = Some(test_market());
+        bot.execute_strategy("up-token", dec("0.60"), dec("0.38"), dec("0.40"), 10.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::InPosition);
+    }
+
+    #[tokio::test]
+    async fn test_min_edge_blocks_thin_entry() {
+        let config = BotConfig {
+            min_edge: dec("0.10"),
+            ..test_config()
+        };
+        let mut bot = TradingBot::new(config).await.unwrap();
+
+        // Ask inside the panic discount but with only 0.09 of edge
+        bot.execute_strategy("up-token", dec("0.50"), dec("0.39"), dec("0.41"), 10.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::Scanning);
+        assert!(bot.active_order_id.is_none());
+
+        // A wide enough edge enters
+        bot.execute_strategy("up-token", dec("0.50"), dec("0.38"), dec("0.40"), 10.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::InPosition);
+    }
+
+    #[tokio::test]
+    async fn test_min_cash_ratio_blocks_entry() {
+        // $100 cash with $20 entries: the first leaves $80 against $20
+        let config = BotConfig {
+            min_cash_ratio: dec("2"),
+            ..test_config()
+        };
+        let mut bot = TradingBot::new(config).await.unwrap();
+
+        bot.execute_strategy("up-token", dec("0.60"), dec("0.38"), dec("0.40"), 10.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::InPosition);
+
+        // The resting entry fills, leaving $80 against $20 held
+        bot.trading.check_paper_fills("up-token", dec("0.40"), dec("0.38")).await;
+        assert_eq!(bot.trading.get_cash_balance().await, dec("80"));
+
+        // Another $20 would leave $60 against $39 (held shares marked at the bid), under 2:1
+        bot.state = BotState::Scanning;
+        bot.active_order_id = None;
+        bot.execute_strategy("up-token", dec("0.60"), dec("0.38"), dec("0.40"), 10.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::Scanning);
+        assert!(bot.trading.open_order_ids().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_stuck_in_position_resets_to_scanning() {
+        let mut bot = TradingBot::new(test_config()).await.unwrap();
+
+        // A resting entry order legitimately has no position yet
+        let order_id = bot.trading.buy("token", dec("0.40"), dec("10")).await.unwrap();
+        bot.state = BotState::InPosition;
+        bot.execute_strategy("token", dec("0.50"), dec("0.44"), dec("0.46"), 10.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::InPosition);
+
+        // Once it's gone with nothing held, the bot goes back to scanning
+        bot.trading.cancel_order(&order_id).await.unwrap();
+        bot.execute_strategy("token", dec("0.50"), dec("0.44"), dec("0.46"), 10.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::Scanning);
+    }
+
+    #[tokio::test]
+    async fn test_take_profit_trigger_reference() {
+        // Entry 0.50, target 0.51; the bid stays at 0.50 while the ask lifts
+        async fn exits(trigger: models::TakeProfitTrigger, ask: &str, last: Option<&str>) -> bool {
+            let config = BotConfig {
+                take_profit_trigger: trigger,
+                ..test_config()
+            };
+            let mut bot = TradingBot::new(config).await.unwrap();
+            bot.trading
+                .execute_market_order("token", models::OrderSide::BUY, dec("0.50"), dec("10"), None)
+                .await
+                .unwrap();
+            bot.state = BotState::InPosition;
+            bot.last_trade = last.map(dec);
+            bot.execute_strategy("token", dec("0.60"), dec("0.50"), dec(ask), 10.0)
+                .await
+                .unwrap();
+            bot.state == BotState::Scanning
+        }
+
+        use models::TakeProfitTrigger::{Bid, Last, Mid};
+        assert!(!exits(Bid, "0.53", Some("0.53")).await);
+        assert!(!exits(Mid, "0.51", None).await); // mid 0.505
+        assert!(exits(Mid, "0.53", None).await); // mid 0.515
+        assert!(!exits(Last, "0.53", None).await); // no print: the bid decides
+        assert!(exits(Last, "0.53", Some("0.52")).await);
+    }
+
+    #[tokio::test]
+    async fn test_absolute_tp_sl_levels() {
+        // Entry 0.50 either way: relative exits at 0.51 / 0.40, absolute at 0.90 / 0.20
+        async fn exits(mode: models::TpSlMode, bid: &str) -> Option<ExitReason> {
+            let config = BotConfig {
+                tp_sl_mode: mode,
+                take_profit_price: dec("0.90"),
+                stop_loss_price: dec("0.20"),
+                ..test_config()
+            };
+            let mut bot = TradingBot::new(config).await.unwrap();
+            bot.trading
+                .execute_market_order("token", models::OrderSide::BUY, dec("0.50"), dec("10"), None)
+                .await
+                .unwrap();
+            bot.state = BotState::InPosition;
+            let bid = dec(bid);
+            bot.execute_strategy("token", dec("0.60"), bid, bid + dec("0.01"), 10.0)
+                .await
+                .unwrap();
+            bot.trading.check_paper_fills("token", bid + dec("0.01"), bid).await;
+            bot.trading.take_closed_trades().await.first().map(|trade| trade.exit_reason)
+        }
+
+        use models::TpSlMode::{Absolute, Relative};
+        assert_eq!(exits(Relative, "0.55").await, Some(ExitReason::TakeProfit));
+        assert_eq!(exits(Absolute, "0.55").await, None);
+        assert_eq!(exits(Absolute, "0.92").await, Some(ExitReason::TakeProfit));
+        assert_eq!(exits(Relative, "0.35").await, Some(ExitReason::StopLoss));
+        assert_eq!(exits(Absolute, "0.35").await, None);
+        assert_eq!(exits(Absolute, "0.18").await, Some(ExitReason::StopLoss));
+    }
+
+    #[tokio::test]
+    async fn test_trailing_stop_exit() {
+        let config = BotConfig {
+            trailing_stop_distance: dec("0.03"),
+            trailing_stop_activation: dec("0.02"),
+            ..test_config()
+        };
+        let mut bot = TradingBot::new(config).await.unwrap();
+        bot.trading
+            .execute_market_order("token", models::OrderSide::BUY, dec("0.50"), dec("10"), None)
+            .await
+            .unwrap();
+        bot.state = BotState::InPosition;
+
+        // The bid ratchets up past the fixed take profit and the activation,
+        // then pulls back while staying above the trailed trigger
+        for bid in ["0.51", "0.53", "0.56", "0.58", "0.56"] {
+            bot.execute_strategy("token", dec("0.60"), dec(bid), dec("0.60"), 10.0)
+                .await
+                .unwrap();
+            assert_eq!(bot.state, BotState::InPosition, "exited at {}", bid);
+        }
+        assert_eq!(bot.peak_bid, Some(dec("0.58")));
+
+        // Falling to 0.03 below the 0.58 peak exits
+        bot.execute_strategy("token", dec("0.60"), dec("0.55"), dec("0.60"), 10.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::Scanning);
+
+        let trades = bot.trading.take_closed_trades().await;
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].exit_reason, ExitReason::TrailingStop);
+        assert_eq!(trades[0].exit_price, dec("0.55"));
+    }
+
+    #[tokio::test]
+    async fn test_fair_value_exit_band() {
+        let config = BotConfig {
+            fair_value_exit_band: dec("0.80"),
+            ..test_config()
+        };
+        let mut bot = TradingBot::new(config).await.unwrap();
+        bot.trading
+            .execute_market_order("token", models::OrderSide::BUY, dec("0.50"), dec("10"), None)
+            .await
+            .unwrap();
+        bot.state = BotState::InPosition;
+
+        // Below the band with the bid between TP/SL: hold
+        bot.execute_strategy("token", dec("0.79"), dec("0.50"), dec("0.52"), 10.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::InPosition);
+
+        // Fair value crosses into the band: exit regardless of price
+        bot.execute_strategy("token", dec("0.81"), dec("0.50"), dec("0.52"), 10.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::Scanning);
+
+        let trades = bot.trading.take_closed_trades().await;
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].exit_reason, ExitReason::FairValueBand);
+        assert_eq!(trades[0].exit_price, dec("0.50"));
+
+        // Holding DOWN after a cross: UP at 0.85 leaves DOWN at 0.15, nowhere near the band
+        bot.trading
+            .execute_market_order("down-token", models::OrderSide::BUY, dec("0.50"), dec("10"), None)
+            .await
+            .unwrap();
+        bot.state = BotState::InPosition;
+        bot.held_book = Some(models::OrderBook::top("0.48", "0.50"));
+        bot.execute_strategy("up-token", dec("0.85"), dec("0.84"), dec("0.86"), 10.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::InPosition);
+
+        // DOWN's own fair value of 0.82 is: exit DOWN at its bid
+        bot.execute_strategy("up-token", dec("0.18"), dec("0.17"), dec("0.19"), 10.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::Scanning);
+        let trades = bot.trading.take_closed_trades().await;
+        assert_eq!(trades[0].token_id, "down-token");
+        assert_eq!(trades[0].exit_price, dec("0.48"));
+    }
+
+    #[tokio::test]
+    async fn test_slippage_adjusted_edge_skips_thin_book() {
+        use models::OrderBook;
+
+        let config = BotConfig {
+            slippage_adjusted_edge: true,
+            ..test_config()
+        };
+        let mut bot = TradingBot::new(config).await.unwrap();
+        let book = |asks: &[(&str, &str)]| OrderBook::from_levels(&[("0.38", "1000")], asks);
+
+        // The ask clears the 0.52 target, but the 50-share order averages 0.544
+        bot.traded_book = Some(book(&[("0.40", "10"), ("0.58", "100")]));
+        bot.execute_strategy("up-token", dec("0.60"), dec("0.38"), dec("0.40"), 10.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::Scanning);
+        assert!(bot.trading.open_order_ids().await.is_empty());
+
+        // Enough depth at the ask keeps the top-of-book edge
+        bot.traded_book = Some(book(&[("0.40", "100"), ("0.58", "100")]));
+        bot.execute_strategy("up-token", dec("0.60"), dec("0.38"), dec("0.40"), 10.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::InPosition);
+    }
+
+    #[tokio::test]
+    async fn test_depth_limit_caps_entry_size() {
+        use models::OrderBook;
+
+        let config = BotConfig {
+            max_depth_levels: 2,
+            ..test_config()
+        };
+        let mut bot = TradingBot::new(config).await.unwrap();
+        bot.traded_book = Some(OrderBook::from_levels(
+            &[("0.38", "1000")],
+            &[("0.40", "10"), ("0.41", "15"), ("0.42", "500")],
+        ));
+
+        // $20 at 0.40 wants 50 shares; the best two levels hold 25
+        bot.execute_strategy("up-token", dec("0.60"), dec("0.38"), dec("0.40"), 10.0)
+            .await
+            .unwrap();
+        bot.trading.check_paper_fills("up-token", dec("0.40"), dec("0.38")).await;
+        assert_eq!(bot.trading.get_position().await.unwrap().shares, dec("25"));
+    }
+
+    #[tokio::test]
+    async fn test_sub_minimum_entry_skipped() {
+        let config = BotConfig {
+            max_capital_per_trade: dec("2"),
+            min_order_size: dec("5"),
+            ..test_config()
+        };
+        let mut bot = TradingBot::new(config).await.unwrap();
+
+        // $2 at 0.60 buys 3 shares, under the 5 share minimum
+        bot.execute_strategy("up-token", dec("0.90"), dec("0.58"), dec("0.60"), 10.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::Scanning);
+        assert!(bot.trading.open_order_ids().await.is_empty());
+
+        // At 0.30 the same capital buys 6: placed
+        bot.execute_strategy("up-token", dec("0.90"), dec("0.28"), dec("0.30"), 10.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.trading.open_order_ids().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_snipe_entry() {
+        use models::OrderBook;
+
+        let config = BotConfig {
+            execution_mode: ExecutionMode::Snipe,
+            snipe_wait_time: 10,
+            ..test_config()
+        };
+        let mut bot = TradingBot::new(config).await.unwrap();
+
+        // The ask moves above our cushion during the wait: no entry
+        bot.trading.update_paper_book("up-token", OrderBook::top("0.44", "0.46")).await;
+        bot.execute_strategy("up-token", dec("0.60"), dec("0.38"), dec("0.40"), 10.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::Scanning);
+        assert!(bot.trading.open_order_ids().await.is_empty());
+
+        // Within the cushion: filled at ask + SNIPE_CUSHION, nothing left resting
+        bot.trading.update_paper_book("up-token", OrderBook::top("0.40", "0.41")).await;
+        bot.execute_strategy("up-token", dec("0.60"), dec("0.38"), dec("0.40"), 10.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::InPosition);
+        assert!(bot.active_order_id.is_none());
+        assert_eq!(bot.trading.get_position().await.unwrap().entry_price, dec("0.42"));
+    }
+
+    #[tokio::test]
+    async fn test_paper_fill_beyond_live_depth_flagged() {
+        use models::OrderBook;
+
+        let config = BotConfig {
+            validate_paper_fills: true,
+            ..test_config()
+        };
+        let bot = TradingBot::new(config).await.unwrap();
+        let book = |ask_size: &str| OrderBook::from_levels(&[("0.44", "500")], &[("0.45", ask_size), ("0.50", "500")]);
+        let deep = book("500");
+
+        // The paper limit buy fills all 100 shares once the ask touches 0.45...
+        bot.trading.buy("up-token", dec("0.45"), dec("100")).await.unwrap();
+        bot.trading.check_paper_fills("up-token", dec("0.45"), dec("0.44")).await;
+        assert_eq!(bot.trading.get_position().await.unwrap().shares, dec("100"));
+
+        // ...but only 10 rest there live; the 0.50 level is past its limit
+        let thin = book("10");
+        assert_eq!(bot.validate_paper_fills("up-token", "down-token", &thin, &deep).await, Some(false));
+
+        // Nothing filled since: nothing to check
+        assert_eq!(bot.validate_paper_fills("up-token", "down-token", &thin, &deep).await, None);
+
+        // A fill the book could give passes
+        bot.trading.buy("up-token", dec("0.45"), dec("100")).await.unwrap();
+        bot.trading.check_paper_fills("up-token", dec("0.45"), dec("0.44")).await;
+        assert_eq!(bot.validate_paper_fills("up-token", "down-token", &book("100"), &deep).await, Some(true));
+    }
+
+    #[tokio::test]
+    async fn test_health_endpoint_after_tick() {
+        let mut bot = TradingBot::new(BotConfig::default()).await.unwrap();
+        bot.price_source = Arc::new(price_source::MockPriceSource::new(vec![None]));
+        bot.current_market = Some(test_market());
+        let addr = health::start(0, bot.health.clone(), 30, String::new()).await.unwrap();
+        let url = format!("http://127.0.0.1:{}/health", addr.port());
+
+        // Before any tick the probe fails
+        let response = reqwest::get(&url).await.unwrap();
+        assert_eq!(response.status(), 503);
+
+        bot.tick().await.unwrap();
+        let response = reqwest::get(&url).await.unwrap();
+        assert_eq!(response.status(), 200);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["healthy"], true);
+        assert_eq!(body["state"], "SCANNING");
+        assert_eq!(body["tick_count"], 1);
+        assert_eq!(body["price_source_ready"], true);
+        assert_eq!(body["last_spot_price"], serde_json::Value::Null);
+        assert!(body["seconds_since_last_tick"].as_f64().unwrap() < 5.0);
+
+        // A wedged loop goes stale
+        bot.health.write().await.last_tick_ms = Some(chrono::Utc::now().timestamp_millis() - 60_000);
+        assert_eq!(reqwest::get(&url).await.unwrap().status(), 503);
+    }
+
+    #[tokio::test]
+    async fn test_order_book_fetches_use_shared_client() {
+        use wiremock::matchers::{method, path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let body = serde_json::json!({
+            "bids": [{ "price": "0.40", "size": "100" }],
+            "asks": [{ "price": "0.42", "size": "100" }]
+        });
+        Mock::given(method("GET"))
+            .and(path("/book"))
+            .and(query_param("token_id", "up-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .mount(&server)
+            .await;
+        // A book slower than HTTP_TIMEOUT_MS
+        Mock::given(method("GET"))
+            .and(path("/book"))
+            .respond_with(ResponseTemplate::new(200).set_delay(std::time::Duration::from_millis(500)))
+            .mount(&server)
+            .await;
+
+        let config = BotConfig {
+            clob_base_url: server.uri(),
+            http_timeout_ms: 100,
+            ..BotConfig::default()
+        };
+        let bot = TradingBot::new(config).await.unwrap();
+
+        for _ in 0..2 {
+            let book = bot.fetch_order_book_http("up-token").await.unwrap();
+            assert_eq!(book.bids.len(), 1);
+        }
+
+        // The fetch goes through the shared client, so its timeout applies
+        assert!(bot.fetch_order_book_http("down-token").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unrealized_pnl_marks_position_at_bid() {
+        use models::OrderBook;
+
+        let bot = TradingBot::new(test_config()).await.unwrap();
+        let book = |bid: &str| OrderBook::from_levels(&[(bid, "100")], &[("0.99", "100")]);
+        let (up_book, down_book) = (book("0.30"), book("0.58"));
+
+        // Flat: nothing to mark, and equity is the cash
+        assert_eq!(bot.unrealized_pnl("up-token", &up_book, &down_book).await, None);
+        assert_eq!(bot.equity("up-token", &up_book, &down_book).await, Some(dec("100")));
+
+        // 10 DOWN shares bought at 0.50 are marked at the DOWN bid
+        bot.trading
+            .execute_market_order("down-token", models::OrderSide::BUY, dec("0.50"), dec("10"), None)
+            .await
+            .unwrap();
+        let pnl = bot.unrealized_pnl("up-token", &up_book, &down_book).await;
+        assert_eq!(pnl, Some(dec("0.80")));
+        assert_eq!(bot.equity("up-token", &up_book, &down_book).await, Some(dec("100.80")));
+
+        // An empty bid side leaves the mark unknown
+        let no_bids = OrderBook { bids: Vec::new(), ..down_book.clone() };
+        assert_eq!(bot.unrealized_pnl("up-token", &up_book, &no_bids).await, None);
+        assert_eq!(bot.equity("up-token", &up_book, &no_bids).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_notification_sequence_over_session() {
+        let db_path = std::env::temp_dir().join(format!("vulture_notify_{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&db_path);
+        let config = BotConfig {
+            dead_man_interval: 60,
+            db_path: db_path.to_string_lossy().to_string(),
+            ..test_config()
+        };
+        let mut bot = TradingBot::new(config).await.unwrap();
+        let recorder = notify::RecordingSink::default();
+        bot.notifiers = vec![Box::new(recorder.clone())];
+        bot.current_market = Some(test_market());
+        let start = chrono::Utc::now().timestamp_millis();
+
+        // Enter, fill, take profit
+        bot.execute_strategy("up-token", dec("0.60"), dec("0.38"), dec("0.40"), 10.0)
+            .await
+            .unwrap();
+        bot.trading.check_paper_fills("up-token", dec("0.40"), dec("0.38")).await;
+        bot.record_closed_trades().await;
+        bot.execute_strategy("up-token", dec("0.60"), dec("0.45"), dec("0.47"), 10.0)
+            .await
+            .unwrap();
+        bot.trading.check_paper_fills("up-token", dec("0.47"), dec("0.45")).await;
+        bot.record_closed_trades().await;
+
+        // Missed check-in, rotation, shutdown
+        assert!(bot.check_dead_mans_switch(start + 120_000).await.unwrap());
+        bot.rotate_market().await.unwrap();
+        bot.shutdown().await.unwrap();
+
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            vec!["OrderPlaced", "Filled", "Exited", "Halted", "Rotated", "Shutdown"]
+        );
+        let _ = std::fs::remove_file(&db_path);
+    }
+
+    #[tokio::test]
+    async fn test_one_sided_book_allows_exit_not_entry() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        // Near expiry the asks have gone: only bids are left
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/book"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "bids": [{ "price": "0.60", "size": "100" }],
+                "asks": [],
+            })))
+            .mount(&server)
+            .await;
+
+        let config = BotConfig {
+            clob_base_url: server.uri(),
+            ..test_config()
+        };
+        let mut bot = TradingBot::new(config).await.unwrap();
+        bot.price_source = Arc::new(price_source::MockPriceSource::new(vec![Some(dec("99000"))]));
+        bot.current_market = Some(test_market());
+
+        // No ask to lift: the entry is skipped
+        assert_eq!(bot.actionable_quotes(Some(dec("0.60")), None), None);
+        bot.tick().await.unwrap();
+        assert_eq!(bot.state, BotState::Scanning);
+        assert!(!bot.trading.has_position().await);
+        assert!(bot.trading.open_order_ids().await.is_empty());
+
+        // Holding, the bid alone is enough to take profit into
+        let entry_book = models::OrderBook::from_levels(&[], &[("0.50", "100")]);
+        bot.trading.update_paper_book("up-token", entry_book).await;
+        bot.trading
+            .execute_market_order("up-token", models::OrderSide::BUY, dec("0.50"), dec("10"), None)
+            .await
+            .unwrap();
+        bot.state = BotState::InPosition;
+        assert_eq!(
+            bot.actionable_quotes(Some(dec("0.60")), None),
+            Some((dec("0.60"), dec("0.60")))
+        );
+        bot.tick().await.unwrap();
+        assert_eq!(bot.state, BotState::Scanning);
+        assert!(!bot.trading.has_position().await);
+        assert_eq!(bot.total_pnl, dec("1.00"));
+    }
+
+    #[tokio::test]
+    async fn test_session_ends_after_max_markets() {
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        // Discovery finds nothing, but every attempt reaches the server
+        let gamma = MockServer::start().await;
+        Mock::given(wiremock::matchers::any())
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&gamma)
+            .await;
+
+        let config = BotConfig {
+            max_markets_per_session: 2,
+            gamma_api_url: format!("{}/markets", gamma.uri()),
+            ..test_config()
+        };
+        let mut bot = TradingBot::new(config).await.unwrap();
+        bot.price_source = Arc::new(price_source::MockPriceSource::new(vec![None]));
+
+        // First market: rotating out leaves the session running and discovering
+        bot.current_market = Some(test_market());
+        bot.markets_traded = 1;
+        bot.rotate_market().await.unwrap();
+        assert!(!bot.halted);
+        bot.tick().await.unwrap();
+        let attempts = gamma.received_requests().await.unwrap().len();
+        assert!(attempts > 0);
+
+        // Second market: the position is flattened and the session ends
+        bot.current_market = Some(test_market());
+        bot.markets_traded = 2;
+        bot.trading
+            .execute_market_order("up-token", models::OrderSide::BUY, dec("0.50"), dec("10"), None)
+            .await
+            .unwrap();
+        bot.trading.update_paper_book("up-token", models::OrderBook::top("0.50", "0.51")).await;
+        bot.state = BotState::InPosition;
+        bot.rotate_market().await.unwrap();
+        assert!(bot.halted);
+        assert!(!bot.trading.has_position().await);
+
+        // Discovery has stopped
+        bot.tick().await.unwrap();
+        assert!(bot.current_market.is_none());
+        assert_eq!(gamma.received_requests().await.unwrap().len(), attempts);
+    }
+
+    #[tokio::test]
+    async fn test_scale_in_averages_down_and_caps_adds() {
+        let config = BotConfig {
+            max_scale_ins: 1,
+            scale_in_step: dec("0.05"),
+            max_capital_per_trade: dec("4.40"),
+            ..test_config()
+        };
+        let mut bot = TradingBot::new(config).await.unwrap();
+        bot.trading
+            .execute_market_order("token", models::OrderSide::BUY, dec("0.50"), dec("10"), None)
+            .await
+            .unwrap();
+        bot.state = BotState::InPosition;
+
+        // Not yet a full step below the entry
+        bot.execute_strategy("token", dec("0.60"), dec("0.45"), dec("0.46"), 10.0)
+            .await
+            .unwrap();
+        assert!(bot.trading.open_order_ids().await.is_empty());
+
+        // After a flip the other outcome's cheap ask is no step below our entry
+        bot.held_book = Some(models::OrderBook::top("0.45", "0.46"));
+        bot.execute_strategy("other-token", dec("0.60"), dec("0.30"), dec("0.31"), 10.0)
+            .await
+            .unwrap();
+        assert!(bot.trading.open_order_ids().await.is_empty());
+        assert_eq!(bot.scale_ins, 0);
+        bot.held_book = None;
+
+        // 0.44 is a step under 0.50: buy another $4.40 and average down
+        bot.execute_strategy("token", dec("0.60"), dec("0.43"), dec("0.44"), 10.0)
+            .await
+            .unwrap();
+        bot.trading.check_paper_fills("token", dec("0.44"), dec("0.43")).await;
+        let pos = bot.trading.get_position().await.unwrap();
+        assert_eq!(pos.shares, dec("20"));
+        assert_eq!(pos.entry_price, dec("0.47"));
+        assert_eq!(bot.scale_ins, 1);
+        assert_eq!(bot.state, BotState::InPosition);
+
+        // A step under the new average, but the one add is used up
+        bot.execute_strategy("token", dec("0.60"), dec("0.40"), dec("0.41"), 10.0)
+            .await
+            .unwrap();
+        assert!(bot.trading.open_order_ids().await.is_empty());
+        assert_eq!(bot.trading.get_position().await.unwrap().shares, dec("20"));
+    }
+
+    #[tokio::test]
+    async fn test_scale_in_passes_entry_gates() {
+        let config = BotConfig {
+            max_scale_ins: 1,
+            scale_in_step: dec("0.05"),
+            min_entry_price: dec("0.30"),
+            max_capital_per_trade: dec("4.40"),
+            ..test_config()
+        };
+        let mut bot = TradingBot::new(config).await.unwrap();
+        bot.trading
+            .execute_market_order("token", models::OrderSide::BUY, dec("0.50"), dec("10"), None)
+            .await
+            .unwrap();
+        bot.state = BotState::InPosition;
+
+        // A step under the entry, but below MIN_ENTRY_PRICE: no add
+        bot.execute_strategy("token", dec("0.60"), dec("0.27"), dec("0.28"), 10.0)
+            .await
+            .unwrap();
+        assert!(bot.trading.open_order_ids().await.is_empty());
+
+        // Inside the band the add rests and is tracked, so a flatten cancels it
+        bot.execute_strategy("token", dec("0.60"), dec("0.43"), dec("0.44"), 10.0)
+            .await
+            .unwrap();
+        let order_ids = bot.trading.open_order_ids().await;
+        assert_eq!(order_ids.len(), 1);
+        assert_eq!(bot.active_order_id.as_ref(), Some(&order_ids[0]));
+    }
+
+    #[tokio::test]
+    async fn test_min_strike_distance_filters_entries() {
+        let config = BotConfig {
+            min_strike_distance: dec("50"),
+            ..test_config()
+        };
+        let mut bot = TradingBot::new(config).await.unwrap();
+        bot.current_market = Some(test_market());
+
+        // $20 above the 98500 strike: too close to call
+        bot.spot_price = Some(dec("98520"));
+        bot.execute_strategy("up-token", dec("0.60"), dec("0.38"), dec("0.40"), 10.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::Scanning);
+        assert!(bot.trading.open_order_ids().await.is_empty());
+
+        // $100 below it is far enough either way
+        bot.spot_price = Some(dec("98400"));
+        bot.execute_strategy("up-token", dec("0.60"), dec("0.38"), dec("0.40"), 10.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::InPosition);
+        assert_eq!(bot.trading.open_order_ids().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_entry_price_band() {
+        let config = BotConfig {
+            min_entry_price: dec("0.10"),
+            max_entry_price: dec("0.85"),
+            ..test_config()
+        };
+        let mut bot = TradingBot::new(config).await.unwrap();
+
+        // Expensive favorite: plenty of edge, but above the cap
+        bot.execute_strategy("up-token", dec("0.98"), dec("0.86"), dec("0.88"), 10.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::Scanning);
+
+        // Lottery ticket: below the floor
+        bot.execute_strategy("up-token", dec("0.20"), dec("0.04"), dec("0.05"), 10.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::Scanning);
+        assert!(bot.trading.open_order_ids().await.is_empty());
+
+        // Inside the band, the cap itself included
+        bot.execute_strategy("up-token", dec("0.95"), dec("0.84"), dec("0.85"), 10.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::InPosition);
+        assert_eq!(bot.trading.open_order_ids().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_absolute_levels_bound_entries() {
+        let config = BotConfig {
+            tp_sl_mode: TpSlMode::Absolute,
+            take_profit_price: dec("0.80"),
+            stop_loss_price: dec("0.20"),
+            ..test_config()
+        };
+        let mut bot = TradingBot::new(config).await.unwrap();
+
+        // At or past either level the exit would fire on the next tick
+        bot.execute_strategy("up-token", dec("0.98"), dec("0.79"), dec("0.80"), 10.0)
+            .await
+            .unwrap();
+        bot.execute_strategy("up-token", dec("0.40"), dec("0.14"), dec("0.15"), 10.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::Scanning);
+        assert!(bot.trading.open_order_ids().await.is_empty());
+
+        // Strictly inside the band
+        bot.execute_strategy("up-token", dec("0.95"), dec("0.78"), dec("0.79"), 10.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::InPosition);
+    }
+
+    #[tokio::test]
+    async fn test_no_orders_during_warmup() {
+        let config = BotConfig {
+            warmup_ticks: 3,
+            ..test_config()
+        };
+        let mut bot = TradingBot::new(config).await.unwrap();
+
+        // Ticks without a spot price feed nothing, so they don't count
+        bot.price_source = Arc::new(price_source::MockPriceSource::new(vec![None]));
+        bot.current_market = Some(test_market());
+        for _ in 0..3 {
+            bot.tick().await.unwrap();
+        }
+        assert_eq!(bot.tick_count, 3);
+        assert_eq!(bot.price_samples, 0);
+        assert!(bot.warming_up());
+
+        // Plenty of edge, but the first three sampled ticks only observe
+        for tick in 1..=3 {
+            bot.price_samples = tick;
+            bot.execute_strategy("up-token", dec("0.95"), dec("0.84"), dec("0.85"), 10.0)
+                .await
+                .unwrap();
+            assert_eq!(bot.state, BotState::Scanning);
+            assert!(bot.trading.open_order_ids().await.is_empty());
+        }
+
+        bot.price_samples = 4;
+        bot.execute_strategy("up-token", dec("0.95"), dec("0.84"), dec("0.85"), 10.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::InPosition);
+        assert_eq!(bot.trading.open_order_ids().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_drawdown_halts_entries_until_resume() {
+        let config = BotConfig {
+            max_drawdown_pct: dec("10"),
+            ..test_config()
+        };
+        let mut bot = TradingBot::new(config).await.unwrap();
+        bot.price_source = Arc::new(price_source::MockPriceSource::new(vec![None]));
+        bot.current_market = Some(test_market());
+
+        // Held 10 shares ride the curve up to 110, then back down through 99
+        bot.trading
+            .execute_market_order("up-token", models::OrderSide::BUY, dec("0.50"), dec("10"), None)
+            .await
+            .unwrap();
+        bot.state = BotState::InPosition;
+        for equity in ["100", "110", "104", "99"] {
+            bot.check_drawdown(dec(equity)).await;
+        }
+        assert!(bot.risk.entries_halted());
+
+        // The open position can still take profit...
+        bot.execute_strategy("up-token", dec("0.60"), dec("0.55"), dec("0.56"), 10.0)
+            .await
+            .unwrap();
+        bot.trading.check_paper_fills("up-token", dec("0.56"), dec("0.55")).await;
+        assert!(!bot.trading.has_position().await);
+        assert_eq!(bot.state, BotState::Scanning);
+
+        // ...but a cheap ask no longer buys, even after equity recovers
+        bot.check_drawdown(dec("120")).await;
+        for _ in 0..2 {
+            bot.execute_strategy("up-token", dec("0.60"), dec("0.38"), dec("0.40"), 10.0)
+                .await
+                .unwrap();
+        }
+        assert_eq!(bot.state, BotState::Halted);
+        assert!(bot.trading.open_order_ids().await.is_empty());
+
+        // An operator /resume lifts the halt on the next tick
+        bot.health.write().await.resume_requested = true;
+        bot.tick().await.unwrap();
+        assert!(!bot.risk.entries_halted());
+        assert_eq!(bot.state, BotState::Scanning);
+        bot.execute_strategy("up-token", dec("0.60"), dec("0.38"), dec("0.40"), 10.0)
+            .await
+            .unwrap();
+        assert_eq!(bot.state, BotState::InPosition);
+    }
+}
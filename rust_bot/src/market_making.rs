@@ -0,0 +1,88 @@
+/// Two-sided quoting for the spread-capture strategy
+use rust_decimal::{Decimal, RoundingStrategy};
+
+use crate::quant::QuantEngine;
+
+/// A resting quote on one side of the book
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quote {
+    pub order_id: String,
+    pub price: Decimal,
+    pub size: Decimal,
+}
+
+impl Quote {
+    /// Whether the quote should be replaced by one at `price` for `size`
+    pub fn is_stale(&self, price: Decimal, size: Decimal) -> bool {
+        self.size != size || QuantEngine::should_update_order(self.price, price)
+    }
+}
+
+/// Our resting bid and ask in the current market
+#[derive(Debug, Default)]
+pub struct Quotes {
+    pub bid: Option<Quote>,
+    pub ask: Option<Quote>,
+}
+
+/// Bid and ask prices `offset` either side of fair value, skewed by inventory
+///
+/// Rounded outward to the cent and kept off the opposite touch, so neither
+/// quote takes liquidity.
+pub fn quote_prices(
+    fair_value: Decimal,
+    inventory_ratio: Decimal,
+    offset: Decimal,
+    skew: Decimal,
+    best_bid: Decimal,
+    best_ask: Decimal,
+) -> (Decimal, Decimal) {
+    let cent = Decimal::new(1, 2);
+    let (bid, ask) = QuantEngine::skewed_quotes(fair_value, inventory_ratio, offset, skew);
+    let bid = bid
+        .round_dp_with_strategy(2, RoundingStrategy::ToNegativeInfinity)
+        .min(best_ask - cent)
+        .max(cent);
+    let ask = ask
+        .round_dp_with_strategy(2, RoundingStrategy::ToPositiveInfinity)
+        .max(best_bid + cent)
+        .min(Decimal::ONE - cent);
+    (bid, ask)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    #[test]
+    fn test_quote_prices() {
+        // Inside a wide book: offset either side, rounded away from fair
+        assert_eq!(
+            quote_prices(dec("0.505"), Decimal::ZERO, dec("0.02"), Decimal::ZERO, dec("0.40"), dec("0.60")),
+            (dec("0.48"), dec("0.53"))
+        );
+
+        // An ask just under our bid would make it take: stay a cent below
+        assert_eq!(
+            quote_prices(dec("0.50"), Decimal::ZERO, dec("0.02"), Decimal::ZERO, dec("0.45"), dec("0.47")),
+            (dec("0.46"), dec("0.52"))
+        );
+
+        // Never outside the tradable range
+        assert_eq!(
+            quote_prices(dec("0.02"), Decimal::ZERO, dec("0.05"), Decimal::ZERO, dec("0.01"), dec("0.03")),
+            (dec("0.01"), dec("0.07"))
+        );
+
+        // Half-full inventory leans both quotes down
+        assert_eq!(
+            quote_prices(dec("0.50"), dec("0.5"), dec("0.02"), dec("0.04"), dec("0.40"), dec("0.60")),
+            (dec("0.46"), dec("0.50"))
+        );
+    }
+}
@@ -1,6 +1,11 @@
 /// Core data structures for the Polymarket trading bot
+use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::latency::LatencyStats;
+use crate::quant::MINUTES_PER_YEAR;
 
 /// Trading side (BUY or SELL)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -10,8 +15,9 @@ pub enum OrderSide {
 }
 
 /// Order type
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum OrderType {
+    #[default]
     GTC,  // Good-Till-Cancel
     FOK,  // Fill-Or-Kill
     IOC,  // Immediate-Or-Cancel
@@ -25,7 +31,13 @@ pub struct Order {
     pub side: OrderSide,
     pub price: Decimal,
     pub size: Decimal,
+    #[serde(default)]
+    pub order_type: OrderType,
     pub timestamp: i64,
+    #[serde(default)]
+    pub exit_reason: Option<ExitReason>,
+    #[serde(default)]
+    pub placed_tick: u64, // paper tick the order was placed on (0 for live orders)
 }
 
 /// Represents an open position
@@ -35,13 +47,306 @@ pub struct Position {
     pub shares: Decimal,
     pub entry_price: Decimal,
     pub entry_time: i64,
+    pub committed_time: i64, // when the entry order was placed, so resting time counts as lockup
 }
 
 impl Position {
+    /// Open an empty position in a token
+    pub fn new(token_id: &str) -> Self {
+        Self {
+            token_id: token_id.to_string(),
+            shares: Decimal::ZERO,
+            entry_price: Decimal::ZERO,
+            entry_time: chrono::Utc::now().timestamp_millis(),
+            committed_time: chrono::Utc::now().timestamp_millis(),
+        }
+    }
+
+    /// Backdate the capital commitment to when an entry order was placed
+    pub fn committed_since(&mut self, placed_at: i64) {
+        self.committed_time = self.committed_time.min(placed_at);
+    }
+
     /// Calculate P&L for this position at given exit price
     pub fn calculate_pnl(&self, exit_price: Decimal) -> Decimal {
         (exit_price - self.entry_price) * self.shares
     }
+
+    /// Add a fill, recomputing the share-weighted average entry price
+    pub fn add_fill(&mut self, price: Decimal, size: Decimal) {
+        let total_shares = self.shares + size;
+        if total_shares > Decimal::ZERO {
+            self.entry_price = (self.entry_price * self.shares + price * size) / total_shares;
+        }
+        self.shares = total_shares;
+    }
+
+    /// Remove sold shares; the average entry price is unchanged
+    pub fn reduce(&mut self, size: Decimal) {
+        self.shares = (self.shares - size).max(Decimal::ZERO);
+    }
+
+    /// Whether all shares have been sold
+    pub fn is_flat(&self) -> bool {
+        self.shares <= Decimal::ZERO
+    }
+}
+
+/// Why a position was closed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExitReason {
+    TakeProfit,
+    StopLoss,
+    TrailingStop,
+    FairValueBand,
+    ExpiryExit,
+    MaxHold,
+    DeadMan,
+    SpreadCapture,
+    Rotation,
+    Manual,
+}
+
+/// A completed round-trip trade
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClosedTrade {
+    pub token_id: String,
+    pub entry_price: Decimal,
+    pub exit_price: Decimal,
+    pub size: Decimal,
+    pub pnl: Decimal,
+    pub entry_time: i64, // Unix milliseconds
+    pub exit_time: i64,  // Unix milliseconds
+    pub exit_reason: ExitReason,
+    #[serde(default)]
+    pub committed_time: i64, // Unix milliseconds the entry order was placed (0 = unknown)
+}
+
+impl ClosedTrade {
+    /// Build a closed trade from the position being exited
+    pub fn from_position(
+        position: &Position,
+        exit_price: Decimal,
+        size: Decimal,
+        exit_reason: ExitReason,
+    ) -> Self {
+        Self {
+            token_id: position.token_id.clone(),
+            entry_price: position.entry_price,
+            exit_price,
+            size,
+            pnl: (exit_price - position.entry_price) * size,
+            entry_time: position.entry_time,
+            exit_time: chrono::Utc::now().timestamp_millis(),
+            exit_reason,
+            committed_time: position.committed_time,
+        }
+    }
+
+    /// Holding time in seconds
+    pub fn holding_seconds(&self) -> f64 {
+        (self.exit_time - self.entry_time) as f64 / 1000.0
+    }
+
+    /// Capital spent on the entry
+    pub fn capital(&self) -> Decimal {
+        self.entry_price * self.size
+    }
+
+    /// Seconds capital was tied up, from order placement (or entry fill) to exit
+    pub fn lockup_seconds(&self) -> f64 {
+        let start = if self.committed_time > 0 {
+            self.committed_time.min(self.entry_time)
+        } else {
+            self.entry_time
+        };
+        (self.exit_time - start) as f64 / 1000.0
+    }
+
+    /// Return on capital annualized over the lockup time (simple, not compounded)
+    pub fn lockup_apr(&self) -> Option<f64> {
+        let capital = self.capital().to_f64()?;
+        let lockup_years = self.lockup_seconds() / 60.0 / MINUTES_PER_YEAR;
+        if capital <= 0.0 || lockup_years <= 0.0 {
+            return None;
+        }
+        Some(self.pnl.to_f64()? / capital / lockup_years)
+    }
+}
+
+/// Aggregate statistics over closed trades
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TradeStats {
+    pub total_trades: u64,
+    pub wins: u64,
+    pub losses: u64,
+    pub win_rate: f64,                   // 0.0 - 1.0
+    pub avg_win: Decimal,
+    pub avg_loss: Decimal,               // Reported as a negative number
+    pub profit_factor: Option<Decimal>,  // None when there are no losing trades
+    pub avg_holding_seconds: f64,
+    #[serde(default)]
+    pub avg_lockup_seconds: f64,         // includes time the entry order rested unfilled
+    #[serde(default)]
+    pub lockup_apr: Option<f64>,         // P&L over capital-years committed; None without lockup
+}
+
+impl TradeStats {
+    /// Compute statistics from a list of closed trades
+    pub fn from_trades(trades: &[ClosedTrade]) -> Self {
+        if trades.is_empty() {
+            return Self::default();
+        }
+
+        let mut stats = Self {
+            total_trades: trades.len() as u64,
+            ..Self::default()
+        };
+
+        let mut gross_profit = Decimal::ZERO;
+        let mut gross_loss = Decimal::ZERO;
+        let mut total_holding = 0.0;
+        let mut total_lockup = 0.0;
+        let mut capital_years = 0.0;
+        let mut total_pnl = Decimal::ZERO;
+
+        for trade in trades {
+            if trade.pnl > Decimal::ZERO {
+                stats.wins += 1;
+                gross_profit += trade.pnl;
+            } else if trade.pnl < Decimal::ZERO {
+                stats.losses += 1;
+                gross_loss += trade.pnl;
+            }
+            total_holding += trade.holding_seconds();
+            total_lockup += trade.lockup_seconds();
+            capital_years += trade.capital().to_f64().unwrap_or(0.0) * trade.lockup_seconds()
+                / 60.0
+                / MINUTES_PER_YEAR;
+            total_pnl += trade.pnl;
+        }
+
+        stats.win_rate = stats.wins as f64 / stats.total_trades as f64;
+        if stats.wins > 0 {
+            stats.avg_win = gross_profit / Decimal::from(stats.wins);
+        }
+        if stats.losses > 0 {
+            stats.avg_loss = gross_loss / Decimal::from(stats.losses);
+            stats.profit_factor = Some(gross_profit / gross_loss.abs());
+        }
+        stats.avg_holding_seconds = total_holding / stats.total_trades as f64;
+        stats.avg_lockup_seconds = total_lockup / stats.total_trades as f64;
+        if capital_years > 0.0 {
+            stats.lockup_apr = total_pnl.to_f64().map(|pnl| pnl / capital_years);
+        }
+
+        stats
+    }
+}
+
+/// Session equity at one tick: cash plus the open position marked at the bid
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EquityPoint {
+    pub timestamp: i64,
+    pub equity: Decimal,
+}
+
+/// Summary of an equity curve
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EquityStats {
+    pub peak_equity: Decimal,
+    pub max_drawdown: Decimal, // largest fall from a prior peak, in dollars
+    pub max_drawdown_pct: f64, // that fall as a fraction of its peak (0.0 - 1.0)
+}
+
+impl EquityStats {
+    /// Peak equity and the worst peak-to-trough drawdown of a curve
+    pub fn from_curve(curve: &[EquityPoint]) -> Self {
+        let mut stats = Self::default();
+        let Some(first) = curve.first() else {
+            return stats;
+        };
+
+        stats.peak_equity = first.equity;
+        for point in curve {
+            stats.peak_equity = stats.peak_equity.max(point.equity);
+            let drawdown = stats.peak_equity - point.equity;
+            if drawdown > stats.max_drawdown {
+                stats.max_drawdown = drawdown;
+                if stats.peak_equity > Decimal::ZERO {
+                    stats.max_drawdown_pct = (drawdown / stats.peak_equity).to_f64().unwrap_or(0.0);
+                }
+            }
+        }
+        stats
+    }
+}
+
+/// Orders placed vs filled, limit and market orders counted apart
+///
+/// A fill only counts once it is confirmed: matched by the exchange or
+/// seen in the balance, or filled against the paper book.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FillStats {
+    pub placed: u64, // limit orders
+    pub filled: u64,
+    #[serde(default)]
+    pub market_placed: u64,
+    #[serde(default)]
+    pub market_filled: u64,
+}
+
+impl FillStats {
+    /// Fraction of placed limit orders that filled (0.0 - 1.0)
+    pub fn fill_rate(&self) -> f64 {
+        Self::rate(self.filled, self.placed)
+    }
+
+    /// Fraction of market orders that filled (0.0 - 1.0)
+    pub fn market_fill_rate(&self) -> f64 {
+        Self::rate(self.market_filled, self.market_placed)
+    }
+
+    fn rate(filled: u64, placed: u64) -> f64 {
+        if placed == 0 {
+            0.0
+        } else {
+            filled as f64 / placed as f64
+        }
+    }
+
+    /// Count an order placed; `market` for immediate orders
+    pub fn record_placed(&mut self, market: bool) {
+        if market {
+            self.market_placed += 1;
+        } else {
+            self.placed += 1;
+        }
+    }
+
+    /// Count a confirmed fill; `market` for immediate orders
+    pub fn record_filled(&mut self, market: bool) {
+        if market {
+            self.market_filled += 1;
+        } else {
+            self.filled += 1;
+        }
+    }
+
+    pub fn merge(&mut self, other: FillStats) {
+        self.placed += other.placed;
+        self.filled += other.filled;
+        self.market_placed += other.market_placed;
+        self.market_filled += other.market_filled;
+    }
+}
+
+/// Fill statistics for the session, overall and per market slug
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FillReport {
+    pub overall: FillStats,
+    pub fill_rate: f64,
+    pub per_market: BTreeMap<String, FillStats>,
 }
 
 /// Order book data from Polymarket
@@ -51,6 +356,8 @@ pub struct OrderBook {
     pub market: String,
     pub bids: Vec<OrderBookLevel>,
     pub asks: Vec<OrderBookLevel>,
+    #[serde(default)]
+    pub last_trade_price: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -60,16 +367,18 @@ pub struct OrderBookLevel {
 }
 
 impl OrderBook {
-    /// Get best bid price
+    /// Get best bid price (the ladder may arrive in either order)
     pub fn best_bid(&self) -> Option<Decimal> {
-        self.bids.first()
-            .and_then(|level| level.price.parse().ok())
+        self.bids.iter()
+            .filter_map(|level| level.price.parse().ok())
+            .max()
     }
 
     /// Get best ask price
     pub fn best_ask(&self) -> Option<Decimal> {
-        self.asks.first()
-            .and_then(|level| level.price.parse().ok())
+        self.asks.iter()
+            .filter_map(|level| level.price.parse().ok())
+            .min()
     }
 
     /// Calculate spread
@@ -79,6 +388,121 @@ impl OrderBook {
             _ => None,
         }
     }
+
+    /// Midpoint of the best bid and ask
+    pub fn mid(&self) -> Option<Decimal> {
+        Some((self.best_bid()? + self.best_ask()?) / Decimal::from(2))
+    }
+
+    /// Price of the most recent trade, when the source reports it
+    pub fn last_trade(&self) -> Option<Decimal> {
+        self.last_trade_price.as_deref()?.parse().ok()
+    }
+
+    /// Simulate a market order of `size` walking the opposite side of the book
+    ///
+    /// A BUY takes asks from the lowest price up, a SELL takes bids from the
+    /// highest down. Returns None when there is no depth at all; otherwise
+    /// `filled` may fall short of `size` if the book runs out.
+    pub fn walk(&self, side: OrderSide, size: Decimal) -> Option<BookFill> {
+        let mut filled = Decimal::ZERO;
+        let mut notional = Decimal::ZERO;
+        for (price, level_size) in self.levels(side) {
+            if filled >= size {
+                break;
+            }
+            let take = level_size.min(size - filled);
+            filled += take;
+            notional += price * take;
+        }
+
+        if filled <= Decimal::ZERO {
+            return None;
+        }
+
+        Some(BookFill {
+            filled,
+            avg_price: notional / filled,
+        })
+    }
+
+    /// Total size resting in the best `max_levels` levels a market order on `side` would take
+    pub fn depth(&self, side: OrderSide, max_levels: usize) -> Decimal {
+        self.levels(side).into_iter().take(max_levels).map(|(_, size)| size).sum()
+    }
+
+    /// Size an order on `side` could take right away at `price` or better
+    pub fn liquidity_at(&self, side: OrderSide, price: Decimal) -> Decimal {
+        self.levels(side)
+            .into_iter()
+            .take_while(|(level_price, _)| match side {
+                OrderSide::BUY => *level_price <= price,
+                OrderSide::SELL => *level_price >= price,
+            })
+            .map(|(_, size)| size)
+            .sum()
+    }
+
+    /// Levels on the side a market order on `side` takes, best price first
+    fn levels(&self, side: OrderSide) -> Vec<(Decimal, Decimal)> {
+        let mut levels: Vec<(Decimal, Decimal)> = match side {
+            OrderSide::BUY => &self.asks,
+            OrderSide::SELL => &self.bids,
+        }
+        .iter()
+        .filter_map(|level| Some((level.price.parse().ok()?, level.size.parse().ok()?)))
+        .filter(|(_, level_size): &(Decimal, Decimal)| *level_size > Decimal::ZERO)
+        .collect();
+
+        match side {
+            OrderSide::BUY => levels.sort_by(|a, b| a.0.cmp(&b.0)),
+            OrderSide::SELL => levels.sort_by(|a, b| b.0.cmp(&a.0)),
+        }
+        levels
+    }
+}
+
+#[cfg(test)]
+impl OrderBook {
+    /// Test book built from (price, size) levels on each side
+    pub fn from_levels(bids: &[(&str, &str)], asks: &[(&str, &str)]) -> Self {
+        let levels = |side: &[(&str, &str)]| {
+            side.iter()
+                .map(|(price, size)| OrderBookLevel {
+                    price: price.to_string(),
+                    size: size.to_string(),
+                })
+                .collect()
+        };
+        OrderBook {
+            timestamp: 0,
+            market: "token".to_string(),
+            bids: levels(bids),
+            asks: levels(asks),
+            last_trade_price: None,
+        }
+    }
+
+    /// Test book one level deep on each side, 1000 shares at each price
+    pub fn top(bid: &str, ask: &str) -> Self {
+        Self::from_levels(&[(bid, "1000")], &[(ask, "1000")])
+    }
+}
+
+/// A simulated paper fill: the order's limit price and the size it got
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaperFill {
+    pub token_id: String,
+    pub side: OrderSide,
+    pub price: Decimal,
+    pub size: Decimal,
+}
+
+/// Outcome of walking the book for a market order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BookFill {
+    pub filled: Decimal,
+    pub avg_price: Decimal, // volume-weighted
 }
 
 /// Market information
@@ -89,6 +513,8 @@ pub struct MarketInfo {
     pub token_id_down: String,
     pub strike_price: Decimal,
     pub expiry_timestamp: i64,  // Unix milliseconds
+    #[serde(default)]
+    pub strike_finalized: bool, // false while strike_price is a placeholder for the unpublished open price
 }
 
 impl MarketInfo {
@@ -179,6 +605,10 @@ pub struct TickData {
     pub spread: Option<Decimal>,
     pub minutes_remaining: f64,
     pub state: String,
+    #[serde(default)]
+    pub unrealized_pnl: Option<Decimal>, // open position marked at its token's bid
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paper_fill_check: Option<bool>, // VALIDATE_PAPER_FILLS: whether this tick's paper fills fit the live book
 }
 
 /// Session summary
@@ -192,9 +622,354 @@ pub struct SessionSummary {
     pub markets_traded: u64,
     pub total_pnl: Decimal,
     pub final_cash: Decimal,
+    pub trade_stats: TradeStats,
+    #[serde(default)]
+    pub fill_stats: FillReport,
+    pub trades: Vec<ClosedTrade>,
+    pub tick_latency: LatencyStats,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub stage_latency: BTreeMap<String, LatencyStats>, // per tick phase, with STAGE_LATENCY
+    #[serde(default)]
+    pub note: Option<String>, // operator's journal entry for the run
+    #[serde(default)]
+    pub equity_stats: EquityStats,
+    #[serde(default)]
+    pub equity_curve: Vec<EquityPoint>, // paper mode, one point per tick
+    #[serde(default)]
+    pub paper_fill_mismatches: u64, // VALIDATE_PAPER_FILLS: ticks with a paper fill the live book couldn't give
     pub ticks: Vec<TickData>,
 }
 
+/// Manual override of the trading direction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ForceDirection {
+    Auto, // Follow the model's direction selection
+    Up,   // Always trade the UP token
+    Down, // Always trade the DOWN token
+}
+
+impl std::str::FromStr for ForceDirection {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.trim().to_uppercase().as_str() {
+            "AUTO" => Ok(ForceDirection::Auto),
+            "UP" => Ok(ForceDirection::Up),
+            "DOWN" => Ok(ForceDirection::Down),
+            other => anyhow::bail!("Unknown direction '{}' (expected UP, DOWN or AUTO)", other),
+        }
+    }
+}
+
+impl std::fmt::Display for ForceDirection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ForceDirection::Auto => write!(f, "AUTO"),
+            ForceDirection::Up => write!(f, "UP"),
+            ForceDirection::Down => write!(f, "DOWN"),
+        }
+    }
+}
+
+/// Volatility input for the fair value model
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VolSource {
+    Off,      // Use the gamma compressor (no vol input)
+    Realized, // Realized vol of recent spot samples
+    Implied,  // Vol implied by the UP token's book mid
+}
+
+impl std::str::FromStr for VolSource {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.trim().to_uppercase().as_str() {
+            "OFF" => Ok(VolSource::Off),
+            "REALIZED" => Ok(VolSource::Realized),
+            "IMPLIED" => Ok(VolSource::Implied),
+            other => anyhow::bail!("Unknown vol source '{}' (expected OFF, REALIZED or IMPLIED)", other),
+        }
+    }
+}
+
+impl std::fmt::Display for VolSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VolSource::Off => write!(f, "OFF"),
+            VolSource::Realized => write!(f, "REALIZED"),
+            VolSource::Implied => write!(f, "IMPLIED"),
+        }
+    }
+}
+
+/// Which market to trade when several windows are active at once
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MarketOverlap {
+    Current, // Candidate order: the current window first
+    Longest, // The market with the most time remaining
+}
+
+impl std::str::FromStr for MarketOverlap {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.trim().to_uppercase().as_str() {
+            "CURRENT" => Ok(MarketOverlap::Current),
+            "LONGEST" => Ok(MarketOverlap::Longest),
+            other => anyhow::bail!("Unknown market overlap policy '{}' (expected CURRENT or LONGEST)", other),
+        }
+    }
+}
+
+impl std::fmt::Display for MarketOverlap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MarketOverlap::Current => write!(f, "CURRENT"),
+            MarketOverlap::Longest => write!(f, "LONGEST"),
+        }
+    }
+}
+
+/// What a live order does when it would trade against one of our own resting orders
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SelfTradePrevention {
+    Off,    // Submit regardless
+    Cancel, // Cancel our crossing resting orders first, then submit
+    Reject, // Refuse the new order and leave the resting ones alone (exits still cancel)
+}
+
+impl std::str::FromStr for SelfTradePrevention {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.trim().to_uppercase().as_str() {
+            "OFF" => Ok(SelfTradePrevention::Off),
+            "CANCEL" => Ok(SelfTradePrevention::Cancel),
+            "REJECT" => Ok(SelfTradePrevention::Reject),
+            other => anyhow::bail!("Unknown self-trade prevention '{}' (expected OFF, CANCEL or REJECT)", other),
+        }
+    }
+}
+
+impl std::fmt::Display for SelfTradePrevention {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SelfTradePrevention::Off => write!(f, "OFF"),
+            SelfTradePrevention::Cancel => write!(f, "CANCEL"),
+            SelfTradePrevention::Reject => write!(f, "REJECT"),
+        }
+    }
+}
+
+/// Price compared against the take profit target
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TakeProfitTrigger {
+    Bid,  // The bid itself must reach the target (conservative)
+    Mid,  // Midpoint of bid and ask
+    Last, // Last trade price, falling back to the bid when unknown
+}
+
+impl std::str::FromStr for TakeProfitTrigger {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.trim().to_uppercase().as_str() {
+            "BID" => Ok(TakeProfitTrigger::Bid),
+            "MID" => Ok(TakeProfitTrigger::Mid),
+            "LAST" => Ok(TakeProfitTrigger::Last),
+            other => anyhow::bail!("Unknown take profit trigger '{}' (expected BID, MID or LAST)", other),
+        }
+    }
+}
+
+impl std::fmt::Display for TakeProfitTrigger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TakeProfitTrigger::Bid => write!(f, "BID"),
+            TakeProfitTrigger::Mid => write!(f, "MID"),
+            TakeProfitTrigger::Last => write!(f, "LAST"),
+        }
+    }
+}
+
+/// How take profit and stop loss levels are set
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TpSlMode {
+    Relative, // Offsets from the entry price
+    Absolute, // Fixed price levels, whatever the entry
+}
+
+impl std::str::FromStr for TpSlMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.trim().to_uppercase().as_str() {
+            "RELATIVE" => Ok(TpSlMode::Relative),
+            "ABSOLUTE" => Ok(TpSlMode::Absolute),
+            other => anyhow::bail!("Unknown TP/SL mode '{}' (expected RELATIVE or ABSOLUTE)", other),
+        }
+    }
+}
+
+impl std::fmt::Display for TpSlMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TpSlMode::Relative => write!(f, "RELATIVE"),
+            TpSlMode::Absolute => write!(f, "ABSOLUTE"),
+        }
+    }
+}
+
+/// Which trading strategy drives the bot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Strategy {
+    Vulture,      // Buy when the ask drops a panic discount under fair value
+    MarketMaking, // Rest a bid and an ask around fair value to capture the spread
+}
+
+impl Strategy {
+    /// Sub-account name the strategy's capital is booked under
+    pub fn ledger_name(&self) -> &'static str {
+        match self {
+            Strategy::Vulture => "vulture",
+            Strategy::MarketMaking => "mm",
+        }
+    }
+}
+
+impl std::str::FromStr for Strategy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.trim().to_uppercase().as_str() {
+            "VULTURE" => Ok(Strategy::Vulture),
+            "MM" => Ok(Strategy::MarketMaking),
+            other => anyhow::bail!("Unknown strategy '{}' (expected VULTURE or MM)", other),
+        }
+    }
+}
+
+impl std::fmt::Display for Strategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Strategy::Vulture => write!(f, "VULTURE"),
+            Strategy::MarketMaking => write!(f, "MM"),
+        }
+    }
+}
+
+/// How entries and immediate exits are executed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExecutionMode {
+    Limit, // Resting limit entries, market (FAK) exits
+    Snipe, // Aggressive limits through the book, cancelled if unfilled after SNIPE_WAIT_TIME
+}
+
+impl std::str::FromStr for ExecutionMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.trim().to_uppercase().as_str() {
+            "LIMIT" => Ok(ExecutionMode::Limit),
+            "SNIPE" => Ok(ExecutionMode::Snipe),
+            other => anyhow::bail!("Unknown execution mode '{}' (expected LIMIT or SNIPE)", other),
+        }
+    }
+}
+
+impl std::fmt::Display for ExecutionMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExecutionMode::Limit => write!(f, "LIMIT"),
+            ExecutionMode::Snipe => write!(f, "SNIPE"),
+        }
+    }
+}
+
+/// How the capital for an entry is chosen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SizingMode {
+    Fixed,      // MAX_CAPITAL_PER_TRADE on every entry
+    Confidence, // Scaled linearly with the modeled edge, full size at CONFIDENCE_FULL_EDGE
+}
+
+impl std::str::FromStr for SizingMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.trim().to_uppercase().as_str() {
+            "FIXED" => Ok(SizingMode::Fixed),
+            "CONFIDENCE" => Ok(SizingMode::Confidence),
+            other => anyhow::bail!("Unknown sizing mode '{}' (expected FIXED or CONFIDENCE)", other),
+        }
+    }
+}
+
+impl std::fmt::Display for SizingMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SizingMode::Fixed => write!(f, "FIXED"),
+            SizingMode::Confidence => write!(f, "CONFIDENCE"),
+        }
+    }
+}
+
+/// What position reconciliation does with a share count that disagrees with the wallet
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReconcileMode {
+    Log,     // Warn only; the tracked position is left alone
+    Correct, // Warn and adopt the wallet balance as the tracked shares
+}
+
+impl std::str::FromStr for ReconcileMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.trim().to_uppercase().as_str() {
+            "LOG" => Ok(ReconcileMode::Log),
+            "CORRECT" => Ok(ReconcileMode::Correct),
+            other => anyhow::bail!("Unknown reconcile mode '{}' (expected LOG or CORRECT)", other),
+        }
+    }
+}
+
+impl std::fmt::Display for ReconcileMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReconcileMode::Log => write!(f, "LOG"),
+            ReconcileMode::Correct => write!(f, "CORRECT"),
+        }
+    }
+}
+
+/// Log output format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LogFormat {
+    Text, // Human-readable lines
+    Json, // One JSON object per event, for log ingestion
+}
+
+impl std::str::FromStr for LogFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.trim().to_uppercase().as_str() {
+            "TEXT" => Ok(LogFormat::Text),
+            "JSON" => Ok(LogFormat::Json),
+            other => anyhow::bail!("Unknown log format '{}' (expected TEXT or JSON)", other),
+        }
+    }
+}
+
+impl std::fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LogFormat::Text => write!(f, "TEXT"),
+            LogFormat::Json => write!(f, "JSON"),
+        }
+    }
+}
+
 /// Bot state
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum BotState {
@@ -203,6 +978,7 @@ pub enum BotState {
     ExitingProfit,   // Taking profit
     ExitingStopLoss, // Stop loss triggered
     Rotating,        // Market rotation in progress
+    Halted,          // New entries stopped by a risk limit
 }
 
 impl std::fmt::Display for BotState {
@@ -213,6 +989,88 @@ impl std::fmt::Display for BotState {
             BotState::ExitingProfit => write!(f, "EXITING_PROFIT"),
             BotState::ExitingStopLoss => write!(f, "EXITING_STOP_LOSS"),
             BotState::Rotating => write!(f, "ROTATING"),
+            BotState::Halted => write!(f, "HALTED"),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    #[test]
+    fn test_book_walk_vwap() {
+        // Deliberately unsorted ladder
+        let book = OrderBook::from_levels(
+            &[("0.45", "100"), ("0.48", "50"), ("0.47", "100")],
+            &[("0.55", "100"), ("0.50", "100"), ("0.52", "100")],
+        );
+
+        // 250 shares span three ask levels: (50 + 52 + 27.5) / 250
+        let fill = book.walk(OrderSide::BUY, dec("250")).unwrap();
+        assert_eq!(fill.filled, dec("250"));
+        assert_eq!(fill.avg_price, dec("0.518"));
+
+        // Selling more than the bids hold fills partially
+        let fill = book.walk(OrderSide::SELL, dec("300")).unwrap();
+        assert_eq!(fill.filled, dec("250"));
+        assert_eq!(fill.avg_price, dec("0.464"));
+
+        // No depth, no fill
+        assert_eq!(OrderBook::from_levels(&[], &[]).walk(OrderSide::BUY, dec("10")), None);
+    }
+
+    #[test]
+    fn test_book_round_trip() {
+        // CLOB /book levels are strings, bids ascending toward the touch
+        let json = r#"{
+            "timestamp": 1700000000000,
+            "market": "token",
+            "bids": [{"price": "0.44", "size": "120"}, {"price": "0.46", "size": "30"}],
+            "asks": [{"price": "0.51", "size": "15"}, {"price": "0.49", "size": "80"}]
+        }"#;
+        let parsed: OrderBook = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.bids.len(), 2);
+        assert_eq!(parsed.asks[1].size, "80");
+
+        assert_eq!(parsed.best_bid(), Some(dec("0.46")));
+        assert_eq!(parsed.best_ask(), Some(dec("0.49")));
+        assert_eq!(parsed.spread(), Some(dec("0.03")));
+        assert_eq!(parsed.mid(), Some(dec("0.475")));
+
+        let again: OrderBook = serde_json::from_str(&serde_json::to_string(&parsed).unwrap()).unwrap();
+        assert_eq!(again.best_bid(), parsed.best_bid());
+        assert_eq!(again.best_ask(), parsed.best_ask());
+
+        // One-sided book: no spread or mid
+        let one_sided = OrderBook::from_levels(&[("0.40", "10")], &[]);
+        assert_eq!(one_sided.best_bid(), Some(dec("0.40")));
+        assert_eq!(one_sided.spread(), None);
+        assert_eq!(one_sided.mid(), None);
+    }
+
+    #[test]
+    fn test_weighted_average_entry() {
+        let mut position = Position::new("token");
+        position.add_fill(dec("0.40"), dec("100"));
+        position.add_fill(dec("0.55"), dec("50"));
+
+        // (0.40 * 100 + 0.55 * 50) / 150 = 0.45
+        assert_eq!(position.shares, dec("150"));
+        assert_eq!(position.entry_price, dec("0.45"));
+        assert_eq!(position.calculate_pnl(dec("0.50")), dec("7.5"));
+
+        // Selling part of the position keeps the average entry
+        position.reduce(dec("100"));
+        assert_eq!(position.shares, dec("50"));
+        assert_eq!(position.entry_price, dec("0.45"));
+        assert!(!position.is_flat());
+        position.reduce(dec("50"));
+        assert!(position.is_flat());
+    }
+}
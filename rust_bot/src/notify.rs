@@ -0,0 +1,300 @@
+/// Operator notifications: one event stream broadcast to every configured sink
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+use tracing::warn;
+
+use crate::annotations::AnnotationExporter;
+use crate::config::BotConfig;
+use crate::models::ClosedTrade;
+
+const COLOR_GREEN: u32 = 0x2ecc71;
+const COLOR_RED: u32 = 0xe74c3c;
+const COLOR_BLUE: u32 = 0x3498db;
+const COLOR_ORANGE: u32 = 0xe67e22;
+
+/// Key bot events, broadcast once to every sink
+#[derive(Debug, Clone)]
+pub enum BotEvent<'a> {
+    /// An entry order was placed
+    OrderPlaced { token_id: &'a str, price: Decimal, size: Decimal },
+    /// Shares bought into the position, which now averages `entry_price`
+    Filled { token_id: &'a str, entry_price: Decimal, size: Decimal, cash: Decimal },
+    /// A position closed
+    Exited { trade: &'a ClosedTrade, cash: Decimal },
+    /// Left a market for the next window
+    Rotated { slug: &'a str, total_pnl: Decimal },
+    /// A risk guard stopped trading
+    Halted { reason: &'a str },
+    /// End-of-session summary
+    Shutdown { total_pnl: Decimal, final_cash: Decimal, trades: u64, win_rate: f64 },
+}
+
+impl std::fmt::Display for BotEvent<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BotEvent::OrderPlaced { token_id, price, size } => {
+                write!(f, "Placed BUY {} @ {:.4} ({})", size, price, token_id)
+            }
+            BotEvent::Filled { token_id, entry_price, size, cash } => write!(
+                f,
+                "Filled {} ({}), avg entry {:.4} | Cash ${:.2}",
+                size, token_id, entry_price, cash
+            ),
+            BotEvent::Exited { trade, cash } => write!(
+                f,
+                "Exited {} @ {:.4} ({:?}) | P&L ${:.2} | Cash ${:.2}",
+                trade.size, trade.exit_price, trade.exit_reason, trade.pnl, cash
+            ),
+            BotEvent::Rotated { slug, total_pnl } => {
+                write!(f, "Rotated out of {} | Session P&L ${:.2}", slug, total_pnl)
+            }
+            BotEvent::Halted { reason } => write!(f, "Halted: {}", reason),
+            BotEvent::Shutdown { total_pnl, final_cash, trades, win_rate } => write!(
+                f,
+                "Session ended | P&L ${:.2} | Cash ${:.2} | {} trades, {:.1}% won",
+                total_pnl,
+                final_cash,
+                trades,
+                win_rate * 100.0
+            ),
+        }
+    }
+}
+
+/// A destination for bot events
+///
+/// Sinks skip events they have no use for. Failures are logged, never fatal,
+/// and a sink must not hold up the tick loop for long.
+#[async_trait]
+pub trait NotificationSink: Send + Sync {
+    async fn notify(&self, event: &BotEvent<'_>);
+}
+
+/// Build the sinks enabled in the configuration
+pub fn sinks_from_config(config: &BotConfig, client: &reqwest::Client) -> Vec<Box<dyn NotificationSink>> {
+    let mut sinks: Vec<Box<dyn NotificationSink>> = Vec::new();
+    if !config.annotation_target.trim().is_empty() {
+        sinks.push(Box::new(AnnotationExporter::new(
+            &config.annotation_target,
+            &config.grafana_api_key,
+            client.clone(),
+        )));
+    }
+    if !config.discord_webhook_url.trim().is_empty() {
+        sinks.push(Box::new(DiscordNotifier::new(&config.discord_webhook_url, client.clone())));
+    }
+    if config.notify_stdout {
+        sinks.push(Box::new(StdoutSink));
+    }
+    sinks
+}
+
+/// Prints one line per event to stdout, for piping into other tools
+pub struct StdoutSink;
+
+#[async_trait]
+impl NotificationSink for StdoutSink {
+    async fn notify(&self, event: &BotEvent<'_>) {
+        println!("🔔 {}", event);
+    }
+}
+
+/// Posts events as embeds to a Discord webhook
+///
+/// Posts are spawned so a slow webhook never delays the tick loop, except the
+/// shutdown summary, which would otherwise be dropped with the runtime.
+pub struct DiscordNotifier {
+    webhook_url: String,
+    client: reqwest::Client,
+}
+
+impl DiscordNotifier {
+    pub fn new(webhook_url: &str, client: reqwest::Client) -> Self {
+        Self {
+            webhook_url: webhook_url.trim().to_string(),
+            client,
+        }
+    }
+}
+
+#[async_trait]
+impl NotificationSink for DiscordNotifier {
+    async fn notify(&self, event: &BotEvent<'_>) {
+        let Some(embed) = embed(event) else {
+            return;
+        };
+
+        let payload = json!({ "embeds": [embed] });
+        if let BotEvent::Shutdown { .. } = event {
+            post(&self.client, &self.webhook_url, &payload).await;
+        } else {
+            let client = self.client.clone();
+            let url = self.webhook_url.clone();
+            tokio::spawn(async move { post(&client, &url, &payload).await });
+        }
+    }
+}
+
+async fn post(client: &reqwest::Client, url: &str, payload: &Value) {
+    let result = client.post(url).json(payload).send().await;
+    if let Err(e) = result.and_then(|response| response.error_for_status()) {
+        warn!("⚠️ Failed to post Discord notification: {}", e);
+    }
+}
+
+/// The Discord embed for an event (order placements aren't posted)
+fn embed(event: &BotEvent) -> Option<Value> {
+    let field = |name: &str, value: String| json!({ "name": name, "value": value, "inline": true });
+    let dollars = |value: Decimal| format!("${:.2}", value);
+
+    let embed = match event {
+        BotEvent::OrderPlaced { .. } => return None,
+        BotEvent::Filled { token_id, entry_price, size, cash } => json!({
+            "title": "📥 Filled",
+            "color": COLOR_BLUE,
+            "fields": [
+                field("Token", format!("{}...", &token_id[..8.min(token_id.len())])),
+                field("Size", size.to_string()),
+                field("Avg entry", format!("{:.4}", entry_price)),
+                field("Cash", dollars(*cash)),
+            ],
+        }),
+        BotEvent::Exited { trade, cash } => json!({
+            "title": format!("📤 Exit ({:?})", trade.exit_reason),
+            "color": if trade.pnl >= Decimal::ZERO { COLOR_GREEN } else { COLOR_RED },
+            "timestamp": chrono::DateTime::from_timestamp_millis(trade.exit_time).map(|time| time.to_rfc3339()),
+            "fields": [
+                field("Size", trade.size.to_string()),
+                field("Entry", format!("{:.4}", trade.entry_price)),
+                field("Exit", format!("{:.4}", trade.exit_price)),
+                field("P&L", dollars(trade.pnl)),
+                field("Cash", dollars(*cash)),
+            ],
+        }),
+        BotEvent::Rotated { slug, total_pnl } => json!({
+            "title": "🔄 Rotated",
+            "color": COLOR_BLUE,
+            "description": format!("Left {}", slug),
+            "fields": [field("Session P&L", dollars(*total_pnl))],
+        }),
+        BotEvent::Halted { reason } => json!({
+            "title": "🛑 Halted",
+            "color": COLOR_ORANGE,
+            "description": reason,
+        }),
+        BotEvent::Shutdown { total_pnl, final_cash, trades, win_rate } => json!({
+            "title": "🏁 Session ended",
+            "color": if *total_pnl >= Decimal::ZERO { COLOR_GREEN } else { COLOR_RED },
+            "fields": [
+                field("P&L", dollars(*total_pnl)),
+                field("Cash", dollars(*final_cash)),
+                field("Trades", trades.to_string()),
+                field("Win rate", format!("{:.1}%", win_rate * 100.0)),
+            ],
+        }),
+    };
+    Some(embed)
+}
+
+/// Records the kind of every event it receives, for asserting on sequences
+#[cfg(test)]
+#[derive(Clone, Default)]
+pub struct RecordingSink(pub std::sync::Arc<std::sync::Mutex<Vec<&'static str>>>);
+
+#[cfg(test)]
+#[async_trait]
+impl NotificationSink for RecordingSink {
+    async fn notify(&self, event: &BotEvent<'_>) {
+        let kind = match event {
+            BotEvent::OrderPlaced { .. } => "OrderPlaced",
+            BotEvent::Filled { .. } => "Filled",
+            BotEvent::Exited { .. } => "Exited",
+            BotEvent::Rotated { .. } => "Rotated",
+            BotEvent::Halted { .. } => "Halted",
+            BotEvent::Shutdown { .. } => "Shutdown",
+        };
+        self.0.lock().unwrap().push(kind);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ExitReason;
+    use std::str::FromStr;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_take_profit_embed_posted() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/webhook"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let trade = ClosedTrade {
+            token_id: "token".to_string(),
+            entry_price: Decimal::from_str("0.40").unwrap(),
+            exit_price: Decimal::from_str("0.45").unwrap(),
+            size: Decimal::from(10),
+            pnl: Decimal::from_str("0.50").unwrap(),
+            entry_time: 1_700_000_000_000,
+            exit_time: 1_700_000_060_000,
+            exit_reason: ExitReason::TakeProfit,
+            committed_time: 1_700_000_000_000,
+        };
+        let notifier = DiscordNotifier::new(&format!("{}/webhook", server.uri()), reqwest::Client::new());
+        notifier
+            .notify(&BotEvent::Exited { trade: &trade, cash: Decimal::from_str("1000.50").unwrap() })
+            .await;
+
+        // The post happens in the background
+        let mut requests = Vec::new();
+        for _ in 0..50 {
+            requests = server.received_requests().await.unwrap();
+            if !requests.is_empty() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert_eq!(requests.len(), 1);
+
+        let body: Value = serde_json::from_slice(&requests[0].body).unwrap();
+        let field = |name: &str, value: &str| json!({ "name": name, "value": value, "inline": true });
+        assert_eq!(
+            body,
+            json!({
+                "embeds": [{
+                    "title": "📤 Exit (TakeProfit)",
+                    "color": COLOR_GREEN,
+                    "timestamp": "2023-11-14T22:14:20+00:00",
+                    "fields": [
+                        field("Size", "10"),
+                        field("Entry", "0.4000"),
+                        field("Exit", "0.4500"),
+                        field("P&L", "$0.50"),
+                        field("Cash", "$1000.50"),
+                    ],
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn test_sinks_from_config() {
+        let client = reqwest::Client::new();
+        assert!(sinks_from_config(&BotConfig::default(), &client).is_empty());
+
+        let config = BotConfig {
+            annotation_target: "annotations.jsonl".to_string(),
+            discord_webhook_url: "https://discord.com/api/webhooks/1/abc".to_string(),
+            notify_stdout: true,
+            ..BotConfig::default()
+        };
+        assert_eq!(sinks_from_config(&config, &client).len(), 3);
+    }
+}
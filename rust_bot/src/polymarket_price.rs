@@ -1,37 +1,253 @@
 /// Polymarket Price Scraper - Gets BTC price from Polymarket UI (same as app.py)
 use anyhow::{Context, Result};
-use headless_chrome::{Browser, LaunchOptions};
+use async_trait::async_trait;
+use headless_chrome::{Browser, LaunchOptions, Tab};
 use regex::Regex;
 use rust_decimal::Decimal;
 use std::ffi::OsString;
+use std::path::PathBuf;
 use std::str::FromStr;
-use std::sync::Arc;
-use std::time::Duration;
+#[cfg(test)]
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use tracing::{info, warn};
 
+use crate::price_source::PriceSource;
+
+/// Element holding the live BTC price (same as app.py)
+const PRICE_SELECTOR: &str = "number-flow-react";
+/// How often to look for a rendered price while the page loads
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Where to find Chrome and what to launch it with (`CHROME_PATH`, `CHROME_ARGS`)
+#[derive(Debug, Clone, Default)]
+pub struct ChromeOptions {
+    pub path: Option<PathBuf>, // None = auto-detect
+    pub args: Vec<OsString>,   // extra flags, e.g. --no-sandbox in containers
+}
+
+impl ChromeOptions {
+    /// From a binary path (empty = auto-detect) and whitespace-separated flags
+    pub fn new(path: &str, args: &str) -> Self {
+        let path = path.trim();
+        Self {
+            path: (!path.is_empty()).then(|| PathBuf::from(path)),
+            args: args.split_whitespace().map(OsString::from).collect(),
+        }
+    }
+
+    /// Headless launch options with the configured binary and flags
+    fn launch_options(&self) -> LaunchOptions<'_> {
+        LaunchOptions {
+            headless: true,
+            path: self.path.clone(),
+            args: self.args.iter().map(OsString::as_os_str).collect(),
+            ..Default::default()
+        }
+    }
+}
+
+/// An open market page the price is read from
+pub trait PricePage {
+    /// Navigate to `url`
+    fn open(&self, url: &str) -> Result<()>;
+
+    /// Text of the price element, once it has rendered
+    fn price_text(&self) -> Option<String>;
+}
+
+/// Starts the browser behind a `PricePage`
+pub trait BrowserLauncher: Clone + Send + Sync + 'static {
+    type Page: PricePage + Send;
+
+    fn launch(&self) -> Result<Self::Page>;
+}
+
+/// A headless Chrome tab, kept alive with its browser
+pub struct ChromePage {
+    _browser: Browser,
+    tab: Arc<Tab>,
+}
+
+impl PricePage for ChromePage {
+    fn open(&self, url: &str) -> Result<()> {
+        self.tab.navigate_to(url).context("Failed to navigate to market page")?;
+        Ok(())
+    }
+
+    fn price_text(&self) -> Option<String> {
+        let element = self.tab.find_element(PRICE_SELECTOR).ok()?;
+
+        // Try multiple methods to extract text (headless_chrome quirk)
+        let text = element
+            .get_inner_text()
+            .or_else(|_| element.get_content())
+            .or_else(|_| {
+                // Fallback: try getting via JavaScript evaluation
+                self.tab
+                    .evaluate("document.querySelector('number-flow-react').textContent.trim()", false)
+                    .map(|val| val.value.map(|v| v.to_string()).unwrap_or_default())
+            })
+            .ok()?;
+        Some(text.trim().to_string())
+    }
+}
+
+impl BrowserLauncher for ChromeOptions {
+    type Page = ChromePage;
+
+    /// Launch headless Chrome (same as app.py: options.add_argument("--headless"))
+    fn launch(&self) -> Result<ChromePage> {
+        let browser = Browser::new(self.launch_options()).context("Failed to launch headless browser")?;
+        let tab = browser.new_tab().context("Failed to create new tab")?;
+        Ok(ChromePage { _browser: browser, tab })
+    }
+}
+
+/// Reads prices from one page, reused across scrapes
+///
+/// The browser is launched once and the page only re-navigated when the
+/// market changes; any failure drops it so the next scrape starts fresh.
+struct Scraper<L: BrowserLauncher> {
+    launcher: L,
+    page: Option<L::Page>,
+    slug: Option<String>, // market the page is showing
+}
+
+impl<L: BrowserLauncher> Scraper<L> {
+    fn new(launcher: L) -> Self {
+        Self {
+            launcher,
+            page: None,
+            slug: None,
+        }
+    }
+
+    /// The open page, launching a browser if there isn't one
+    fn page(&mut self) -> Result<&L::Page> {
+        if self.page.is_none() {
+            self.page = Some(self.launcher.launch()?);
+            self.slug = None;
+        }
+        Ok(self.page.as_ref().expect("page just launched"))
+    }
+
+    /// Scrape price from Polymarket UI (like app.py does), giving up after `timeout`
+    fn scrape(&mut self, market_slug: &str, timeout: Duration) -> Result<Decimal> {
+        let result = self.read_price(market_slug, timeout);
+        if result.is_err() {
+            self.page = None;
+            self.slug = None;
+        }
+        result
+    }
+
+    fn read_price(&mut self, market_slug: &str, timeout: Duration) -> Result<Decimal> {
+        let deadline = Instant::now() + timeout;
+        let navigate = self.slug.as_deref() != Some(market_slug);
+        let page = self.page()?;
+
+        // Navigate to market page (same as app.py); the price then updates in place
+        if navigate {
+            let url = format!("https://polymarket.com/event/{}?tid={}", market_slug, chrono::Utc::now().timestamp_millis());
+            page.open(&url)?;
+        }
+
+        // Poll for the price element until it renders a price, instead of app.py's fixed sleep
+        let mut last_text = String::new();
+        let price = PolymarketPriceService::poll_until(deadline, POLL_INTERVAL, || {
+            let text = page.price_text()?;
+            let price = PolymarketPriceService::parse_price_text(&text);
+            last_text = text;
+            price
+        })
+        .with_context(|| {
+            format!(
+                "No price rendered within {:?} (last text: '{}')",
+                timeout, last_text
+            )
+        })?;
+
+        self.slug = Some(market_slug.to_string());
+        Ok(price)
+    }
+}
+
 /// Polymarket price service - scrapes live price from UI
-pub struct PolymarketPriceService {
+pub struct PolymarketPriceService<L: BrowserLauncher = ChromeOptions> {
     price: Arc<RwLock<Option<Decimal>>>,
     is_ready: Arc<RwLock<bool>>,
     current_market_slug: Arc<RwLock<Option<String>>>,
+    scrape_timeout: Duration, // bound on one scrape attempt
+    launcher: L,
 }
 
-impl PolymarketPriceService {
-    /// Create a new Polymarket price service
-    pub fn new() -> Self {
+impl<L: BrowserLauncher> PolymarketPriceService<L> {
+    /// Create a new Polymarket price service, opening pages with `launcher`
+    pub fn new(scrape_timeout: Duration, launcher: L) -> Self {
         Self {
             price: Arc::new(RwLock::new(None)),
             is_ready: Arc::new(RwLock::new(false)),
             current_market_slug: Arc::new(RwLock::new(None)),
+            scrape_timeout,
+            launcher,
         }
     }
+}
+
+impl PolymarketPriceService {
+
+    /// Call `probe` every `interval` until it yields a value or `deadline` passes
+    fn poll_until<T>(deadline: Instant, interval: Duration, mut probe: impl FnMut() -> Option<T>) -> Option<T> {
+        loop {
+            if let Some(value) = probe() {
+                return Some(value);
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return None;
+            }
+            std::thread::sleep(interval.min(deadline - now));
+        }
+    }
+
+    /// Parse the displayed price (matches $88,263.40 format - same as app.py)
+    fn parse_price_text(text: &str) -> Option<Decimal> {
+        static PRICE_REGEX: OnceLock<Regex> = OnceLock::new();
+        let price_regex =
+            PRICE_REGEX.get_or_init(|| Regex::new(r"^\$\d{1,3}(,\d{3})*(\.\d+)?$").expect("valid price regex"));
+        if !price_regex.is_match(text) {
+            return None;
+        }
+        // Remove $ and commas, then parse
+        Decimal::from_str(&text.replace("$", "").replace(",", "")).ok()
+    }
+}
+
+#[async_trait]
+impl<L: BrowserLauncher> PriceSource for PolymarketPriceService<L> {
+    fn name(&self) -> &'static str {
+        "scraper"
+    }
 
     /// Start the price scraping service
-    pub async fn start(&self) -> Result<()> {
+    async fn start(&self) -> Result<()> {
+        // Make sure Chrome can actually be launched before committing to this source
+        let launcher = self.launcher.clone();
+        let mut scraper = tokio::task::spawn_blocking(move || {
+            let mut scraper = Scraper::new(launcher);
+            scraper.page()?;
+            Ok::<_, anyhow::Error>(scraper)
+        })
+        .await
+        .context("Browser probe task failed")??;
+
         let price_clone = self.price.clone();
         let ready_clone = self.is_ready.clone();
         let slug_clone = self.current_market_slug.clone();
+        let scrape_timeout = self.scrape_timeout;
 
         // Spawn scraping task
         tokio::task::spawn_blocking(move || {
@@ -44,7 +260,7 @@ impl PolymarketPriceService {
                 };
 
                 if let Some(market_slug) = slug {
-                    match Self::scrape_price(&market_slug) {
+                    match scraper.scrape(&market_slug, scrape_timeout) {
                         Ok(price) => {
                             tokio::runtime::Handle::current().block_on(async {
                                 *price_clone.write().await = Some(price);
@@ -65,72 +281,129 @@ impl PolymarketPriceService {
         Ok(())
     }
 
-    /// Scrape price from Polymarket UI (like app.py does)
-    fn scrape_price(market_slug: &str) -> Result<Decimal> {
-        // Launch headless Chrome (same as app.py: options.add_argument("--headless"))
-        let browser = Browser::new(LaunchOptions {
-            headless: true,
-            ..Default::default()
-        })
-        .context("Failed to launch headless browser")?;
-
-        let tab = browser.new_tab().context("Failed to create new tab")?;
-
-        // Navigate to market page (same as app.py)
-        let url = format!("https://polymarket.com/event/{}?tid={}", market_slug, chrono::Utc::now().timestamp_millis());
-        tab.navigate_to(&url)
-            .context("Failed to navigate to market page")?;
-
-        // Wait for page to load (same as app.py: time.sleep(3))
-        std::thread::sleep(Duration::from_secs(3));
-
-        // Find the price element (number-flow-react tag) - same as app.py
-        let element = tab
-            .wait_for_element("number-flow-react")
-            .context("Failed to find price element")?;
-
-        // Try multiple methods to extract text (headless_chrome quirk)
-        let text = element
-            .get_inner_text()
-            .or_else(|_| element.get_content())
-            .or_else(|_| {
-                // Fallback: try getting via JavaScript evaluation
-                tab.evaluate("document.querySelector('number-flow-react').textContent.trim()", false)
-                    .map(|val| val.value.map(|v| v.to_string()).unwrap_or_default())
-            })
-            .context("Failed to get price text from element")?
-            .trim()
-            .to_string();
-
-        // Parse price with regex (matches $88,263.40 format - same as app.py)
-        let price_regex = Regex::new(r"^\$\d{1,3}(,\d{3})*(\.\d+)?$")?;
-
-        if price_regex.is_match(&text) {
-            // Remove $ and commas, then parse
-            let clean_text = text.replace("$", "").replace(",", "");
-            let price = Decimal::from_str(&clean_text)
-                .context("Failed to parse price as decimal")?;
-            Ok(price)
-        } else {
-            anyhow::bail!("Price text doesn't match expected format: '{}' (empty or wrong format)", text)
-        }
-    }
-
     /// Update the market slug to scrape
-    pub async fn set_market_slug(&self, slug: String) {
+    async fn set_market_slug(&self, slug: String) {
         let mut slug_guard = self.current_market_slug.write().await;
         *slug_guard = Some(slug);
     }
 
     /// Get the current BTC price
-    pub async fn get_price(&self) -> Option<Decimal> {
+    async fn get_price(&self) -> Option<Decimal> {
         let price_guard = self.price.read().await;
         *price_guard
     }
 
     /// Check if price service is ready
-    pub async fn is_ready(&self) -> bool {
+    async fn is_ready(&self) -> bool {
         let ready_guard = self.is_ready.read().await;
         *ready_guard
     }
 }
+
+/// Serves a fixed price text, counting launches and navigations
+#[cfg(test)]
+#[derive(Clone, Default)]
+pub struct FakeBrowser {
+    pub launches: Arc<AtomicUsize>,
+    pub opens: Arc<AtomicUsize>,
+    pub text: Arc<std::sync::Mutex<Option<String>>>,
+}
+
+#[cfg(test)]
+impl PricePage for FakeBrowser {
+    fn open(&self, _url: &str) -> Result<()> {
+        self.opens.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn price_text(&self) -> Option<String> {
+        self.text.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+impl BrowserLauncher for FakeBrowser {
+    type Page = FakeBrowser;
+
+    fn launch(&self) -> Result<FakeBrowser> {
+        self.launches.fetch_add(1, Ordering::Relaxed);
+        Ok(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_poll_until_gives_up_at_deadline() {
+        let timeout = Duration::from_millis(100);
+
+        // Found on the third look, well before the deadline
+        let mut calls = 0;
+        let found = PolymarketPriceService::poll_until(Instant::now() + timeout, Duration::from_millis(5), || {
+            calls += 1;
+            (calls == 3).then_some(calls)
+        });
+        assert_eq!(found, Some(3));
+
+        // Never found: returns None at the deadline rather than hanging
+        let started = Instant::now();
+        let missing: Option<()> =
+            PolymarketPriceService::poll_until(started + timeout, Duration::from_millis(30), || None);
+        assert_eq!(missing, None);
+        assert!(started.elapsed() >= timeout);
+        assert!(started.elapsed() < timeout * 3);
+    }
+
+    #[test]
+    fn test_scraper_reuses_browser() {
+        let browser = FakeBrowser::default();
+        *browser.text.lock().unwrap() = Some("$88,263.40".to_string());
+        let mut scraper = Scraper::new(browser.clone());
+        let timeout = Duration::from_millis(50);
+
+        for _ in 0..3 {
+            assert_eq!(scraper.scrape("btc-a", timeout).unwrap(), Decimal::from_str("88263.40").unwrap());
+        }
+        assert_eq!(browser.launches.load(Ordering::Relaxed), 1);
+        assert_eq!(browser.opens.load(Ordering::Relaxed), 1);
+
+        // A new market re-navigates the same browser
+        scraper.scrape("btc-b", timeout).unwrap();
+        assert_eq!(browser.launches.load(Ordering::Relaxed), 1);
+        assert_eq!(browser.opens.load(Ordering::Relaxed), 2);
+
+        // A failed scrape drops the browser; the next one relaunches and reloads
+        *browser.text.lock().unwrap() = None;
+        assert!(scraper.scrape("btc-b", timeout).is_err());
+        *browser.text.lock().unwrap() = Some("$88,300.00".to_string());
+        scraper.scrape("btc-b", timeout).unwrap();
+        assert_eq!(browser.launches.load(Ordering::Relaxed), 2);
+        assert_eq!(browser.opens.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn test_launch_options_use_configured_chrome() {
+        let chrome = ChromeOptions::new(" /usr/bin/chromium ", "--no-sandbox  --disable-gpu");
+        let options = chrome.launch_options();
+        assert!(options.headless);
+        assert_eq!(options.path, Some(PathBuf::from("/usr/bin/chromium")));
+        assert_eq!(options.args, ["--no-sandbox", "--disable-gpu"]);
+
+        // Unset: auto-detect Chrome, no extra flags
+        let options = ChromeOptions::new("", "");
+        assert_eq!(options.launch_options().path, None);
+        assert!(options.launch_options().args.is_empty());
+    }
+
+    #[test]
+    fn test_parse_price_text() {
+        assert_eq!(
+            PolymarketPriceService::parse_price_text("$88,263.40"),
+            Some(Decimal::from_str("88263.40").unwrap())
+        );
+        assert_eq!(PolymarketPriceService::parse_price_text(""), None);
+        assert_eq!(PolymarketPriceService::parse_price_text("$0.00 loading"), None);
+    }
+}
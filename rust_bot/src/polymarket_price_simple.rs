@@ -1,5 +1,8 @@
 /// Polymarket Price Service - Simple HTTP approach (no browser needed)
 use anyhow::{Context, Result};
+use async_trait::async_trait;
+use rand::{Rng, SeedableRng};
+use reqwest::StatusCode;
 use rust_decimal::Decimal;
 use std::str::FromStr;
 use std::sync::Arc;
@@ -7,39 +10,114 @@ use tokio::sync::RwLock;
 use tokio::time::{interval, Duration};
 use tracing::{info, warn};
 
+use crate::backoff::Backoff;
+use crate::price_source::PriceSource;
+use crate::rate_limit::parse_retry_after;
+
+/// Result of one CoinGecko poll
+enum Poll {
+    Price(Decimal),
+    RateLimited(Option<Duration>), // Retry-After, when the server sent one
+}
+
+/// Wait before polling again after a 429: the backoff, but never shorter than Retry-After
+fn rate_limited_wait<R: Rng>(backoff: &mut Backoff, retry_after: Option<Duration>, rng: &mut R) -> Duration {
+    backoff.next_delay(rng).max(retry_after.unwrap_or_default())
+}
+
 /// Polymarket price service - uses same price feed as UI
 pub struct PolymarketPriceService {
     price: Arc<RwLock<Option<Decimal>>>,
     is_ready: Arc<RwLock<bool>>,
+    max_backoff: Duration,
+    client: reqwest::Client,
 }
 
 impl PolymarketPriceService {
-    /// Create a new Polymarket price service
-    pub fn new() -> Self {
+    /// Create a new Polymarket price service, backing off up to `max_backoff` on 429s
+    pub fn new(max_backoff: Duration, client: reqwest::Client) -> Self {
         Self {
             price: Arc::new(RwLock::new(None)),
             is_ready: Arc::new(RwLock::new(false)),
+            max_backoff,
+            client,
+        }
+    }
+
+    /// Fetch BTC price from CoinGecko (free, reliable, same as many DeFi apps use)
+    /// This is what most prediction markets reference for "BTC price"
+    async fn fetch_price(client: &reqwest::Client) -> Result<Poll> {
+        #[derive(serde::Deserialize)]
+        struct CoinGeckoResponse {
+            bitcoin: CoinGeckoBitcoin,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct CoinGeckoBitcoin {
+            usd: f64,
+        }
+
+        // CoinGecko public API (no auth needed, widely used)
+        let response = client
+            .get("https://api.coingecko.com/api/v3/simple/price?ids=bitcoin&vs_currencies=usd")
+            .timeout(Duration::from_secs(5))
+            .send()
+            .await
+            .context("Failed to fetch from CoinGecko")?;
+
+        if response.status() == StatusCode::TOO_MANY_REQUESTS {
+            return Ok(Poll::RateLimited(parse_retry_after(
+                response
+                    .headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|value| value.to_str().ok()),
+            )));
         }
+
+        let response: CoinGeckoResponse = response
+            .json()
+            .await
+            .context("Failed to parse CoinGecko response")?;
+
+        let price_str = format!("{:.2}", response.bitcoin.usd);
+        let price = Decimal::from_str(&price_str).context("Failed to parse price")?;
+        Ok(Poll::Price(price))
+    }
+}
+
+#[async_trait]
+impl PriceSource for PolymarketPriceService {
+    fn name(&self) -> &'static str {
+        "http"
     }
 
     /// Start the price fetching service
-    pub async fn start(&self) -> Result<()> {
+    async fn start(&self) -> Result<()> {
         let price_clone = self.price.clone();
         let ready_clone = self.is_ready.clone();
+        let mut backoff = Backoff::new(self.max_backoff);
+        let client = self.client.clone();
 
         // Spawn price fetching task
         tokio::spawn(async move {
             let mut tick = interval(Duration::from_millis(200));
-            let client = reqwest::Client::new();
+            let mut rng = rand::rngs::StdRng::from_entropy();
 
             loop {
                 tick.tick().await;
 
                 match Self::fetch_price(&client).await {
-                    Ok(price) => {
+                    Ok(Poll::Price(price)) => {
+                        backoff.reset();
                         *price_clone.write().await = Some(price);
                         *ready_clone.write().await = true;
                     }
+                    Ok(Poll::RateLimited(retry_after)) => {
+                        let wait = rate_limited_wait(&mut backoff, retry_after, &mut rng);
+                        warn!("⚠️ Rate limited by CoinGecko - backing off {:?}", wait);
+                        tokio::time::sleep(wait).await;
+                        tick.reset();
+                    }
                     Err(e) => {
                         warn!("Failed to fetch BTC price: {}", e);
                     }
@@ -51,48 +129,47 @@ impl PolymarketPriceService {
         Ok(())
     }
 
-    /// Fetch BTC price from CoinGecko (free, reliable, same as many DeFi apps use)
-    /// This is what most prediction markets reference for "BTC price"
-    async fn fetch_price(client: &reqwest::Client) -> Result<Decimal> {
-        #[derive(serde::Deserialize)]
-        struct CoinGeckoResponse {
-            bitcoin: CoinGeckoBitcoin,
-        }
-
-        #[derive(serde::Deserialize)]
-        struct CoinGeckoBitcoin {
-            usd: f64,
-        }
-
-        // CoinGecko public API (no auth needed, widely used)
-        let response: CoinGeckoResponse = client
-            .get("https://api.coingecko.com/api/v3/simple/price?ids=bitcoin&vs_currencies=usd")
-            .timeout(Duration::from_secs(5))
-            .send()
-            .await
-            .context("Failed to fetch from CoinGecko")?
-            .json()
-            .await
-            .context("Failed to parse CoinGecko response")?;
-
-        let price_str = format!("{:.2}", response.bitcoin.usd);
-        Decimal::from_str(&price_str).context("Failed to parse price")
-    }
-
     /// Get the current BTC price
-    pub async fn get_price(&self) -> Option<Decimal> {
+    async fn get_price(&self) -> Option<Decimal> {
         let price_guard = self.price.read().await;
         *price_guard
     }
 
     /// Check if price service is ready
-    pub async fn is_ready(&self) -> bool {
+    async fn is_ready(&self) -> bool {
         let ready_guard = self.is_ready.read().await;
         *ready_guard
     }
 
     /// Set market slug (not needed for this simple version)
-    pub async fn set_market_slug(&self, _slug: String) {
+    async fn set_market_slug(&self, _slug: String) {
         // No-op for simple version
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backoff::BASE_DELAY;
+
+    #[test]
+    fn test_rate_limited_wait_honors_retry_after() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let mut backoff = Backoff::new(Duration::from_secs(5));
+
+        // Consecutive 429s double the backoff up to the cap; jitter keeps each wait in [d/2, d]
+        for expected in [1, 2, 4, 5, 5] {
+            let wait = rate_limited_wait(&mut backoff, None, &mut rng);
+            let full = Duration::from_secs(expected);
+            assert!(wait >= full / 2 && wait <= full, "{:?} outside jitter range of {:?}", wait, full);
+        }
+
+        // Retry-After is a floor
+        let wait = rate_limited_wait(&mut backoff, Some(Duration::from_secs(30)), &mut rng);
+        assert_eq!(wait, Duration::from_secs(30));
+
+        // A success resets to full-speed polling, then the next 429 starts over
+        backoff.reset();
+        assert!(rate_limited_wait(&mut backoff, None, &mut rng) <= BASE_DELAY);
+    }
+}
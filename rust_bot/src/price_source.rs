@@ -0,0 +1,389 @@
+/// Common interface over the BTC spot price backends
+use anyhow::Result;
+use async_trait::async_trait;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::binance::BinanceService;
+use crate::config::BotConfig;
+use crate::polymarket_price_simple;
+use crate::simulated_price::SimulatedPriceSource;
+
+/// A live BTC spot price feed
+#[async_trait]
+pub trait PriceSource: Send + Sync {
+    /// Short name used in logs
+    fn name(&self) -> &'static str;
+
+    /// Start fetching prices in the background
+    async fn start(&self) -> Result<()>;
+
+    /// Get the latest BTC price
+    async fn get_price(&self) -> Option<Decimal>;
+
+    /// Check if at least one price has been received
+    async fn is_ready(&self) -> bool;
+
+    /// Update the market slug the price is tied to
+    async fn set_market_slug(&self, slug: String);
+
+    /// Top of an external spot order book, for sources that stream one
+    async fn reference_book(&self) -> Option<ReferenceBook> {
+        None
+    }
+}
+
+/// Best bid/ask of an external BTC spot order book
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReferenceBook {
+    pub best_bid: Decimal,
+    pub best_ask: Decimal,
+    pub bid_size: Decimal,
+    pub ask_size: Decimal,
+}
+
+impl ReferenceBook {
+    pub fn mid(&self) -> Decimal {
+        (self.best_bid + self.best_ask) / Decimal::from(2)
+    }
+
+    /// Size-weighted mid, leaning toward the side with less resting size
+    pub fn microprice(&self) -> Decimal {
+        let total = self.bid_size + self.ask_size;
+        if total <= Decimal::ZERO {
+            return self.mid();
+        }
+        (self.best_bid * self.ask_size + self.best_ask * self.bid_size) / total
+    }
+}
+
+/// Which price backend to use
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriceSourceKind {
+    Scraper,   // Headless Chrome scrape of the Polymarket UI
+    Http,      // CoinGecko HTTP polling
+    Binance,   // Binance WebSocket + REST fallback
+    Simulated, // Seeded GBM path, for offline runs
+}
+
+impl std::str::FromStr for PriceSourceKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "scraper" => Ok(PriceSourceKind::Scraper),
+            "http" => Ok(PriceSourceKind::Http),
+            "binance" => Ok(PriceSourceKind::Binance),
+            "simulated" => Ok(PriceSourceKind::Simulated),
+            other => anyhow::bail!("Unknown price source '{}' (expected scraper, http, binance or simulated)", other),
+        }
+    }
+}
+
+impl std::fmt::Display for PriceSourceKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PriceSourceKind::Scraper => write!(f, "scraper"),
+            PriceSourceKind::Http => write!(f, "http"),
+            PriceSourceKind::Binance => write!(f, "binance"),
+            PriceSourceKind::Simulated => write!(f, "simulated"),
+        }
+    }
+}
+
+/// Build the configured price source (not yet started)
+///
+/// With `SPOT_BLEND_BINANCE_WEIGHT` the source is paired with Binance and the
+/// two prices are blended.
+pub fn create_price_source(config: &BotConfig, client: &reqwest::Client) -> Arc<dyn PriceSource> {
+    let source = configured_price_source(config, client, || scraper_price_source(config, client));
+    with_binance_blend(config, client, source)
+}
+
+/// The HTTP source standing in for a scraper that can't launch, blended like the scraper would have been
+pub fn scraper_fallback(config: &BotConfig, client: &reqwest::Client) -> Arc<dyn PriceSource> {
+    with_binance_blend(config, client, http_price_source(config, client))
+}
+
+fn with_binance_blend(
+    config: &BotConfig,
+    client: &reqwest::Client,
+    source: Arc<dyn PriceSource>,
+) -> Arc<dyn PriceSource> {
+    if config.blends_binance() {
+        return Arc::new(BlendedPriceSource::new(
+            source,
+            binance_price_source(config, client),
+            config.spot_blend_binance_weight,
+        ));
+    }
+    source
+}
+
+/// The source `PRICE_SOURCE` names, building the scraper with `scraper` only if it is chosen
+fn configured_price_source(
+    config: &BotConfig,
+    client: &reqwest::Client,
+    scraper: impl FnOnce() -> Arc<dyn PriceSource>,
+) -> Arc<dyn PriceSource> {
+    match config.price_source {
+        PriceSourceKind::Scraper => scraper(),
+        PriceSourceKind::Http => http_price_source(config, client),
+        PriceSourceKind::Binance => binance_price_source(config, client),
+        PriceSourceKind::Simulated => Arc::new(SimulatedPriceSource::new(
+            config.sim_start_price,
+            config.sim_drift.to_f64().unwrap_or(0.0),
+            config.sim_vol.to_f64().unwrap_or(0.0),
+            std::time::Duration::from_millis(config.tick_interval),
+            config.sim_seed,
+        )),
+    }
+}
+
+fn binance_price_source(config: &BotConfig, client: &reqwest::Client) -> Arc<dyn PriceSource> {
+    Arc::new(BinanceService::new(
+        config.binance_depth,
+        std::time::Duration::from_secs(config.binance_max_backoff),
+        std::time::Duration::from_secs(config.binance_stale_timeout),
+        client.clone(),
+    ))
+}
+
+/// The headless Chrome scraper, or the HTTP source when built without it
+#[cfg(feature = "scraper")]
+fn scraper_price_source(config: &BotConfig, _client: &reqwest::Client) -> Arc<dyn PriceSource> {
+    Arc::new(crate::polymarket_price::PolymarketPriceService::new(
+        std::time::Duration::from_millis(config.scrape_timeout_ms),
+        crate::polymarket_price::ChromeOptions::new(&config.chrome_path, &config.chrome_args),
+    ))
+}
+
+#[cfg(not(feature = "scraper"))]
+fn scraper_price_source(config: &BotConfig, client: &reqwest::Client) -> Arc<dyn PriceSource> {
+    tracing::warn!("⚠️ Built without the 'scraper' feature - using HTTP price source");
+    http_price_source(config, client)
+}
+
+/// The HTTP polling source, used directly and as the scraper fallback
+fn http_price_source(config: &BotConfig, client: &reqwest::Client) -> Arc<dyn PriceSource> {
+    Arc::new(polymarket_price_simple::PolymarketPriceService::new(
+        std::time::Duration::from_secs(config.http_price_max_backoff),
+        client.clone(),
+    ))
+}
+
+/// The configured source's price pulled toward Binance by a fixed weight
+///
+/// The configured source (e.g. the scraped Polymarket reference) anchors the
+/// price; Binance reacts faster. Whichever feed has a price is used alone
+/// while the other is down, but the configured source must start: without
+/// it the blend would quietly become a Binance-only feed.
+pub struct BlendedPriceSource {
+    reference: Arc<dyn PriceSource>,
+    binance: Arc<dyn PriceSource>,
+    binance_weight: Decimal, // 0..=1
+}
+
+impl BlendedPriceSource {
+    pub fn new(reference: Arc<dyn PriceSource>, binance: Arc<dyn PriceSource>, binance_weight: Decimal) -> Self {
+        Self {
+            reference,
+            binance,
+            binance_weight,
+        }
+    }
+
+    /// Weighted average of the two prices
+    pub fn blend(reference: Decimal, binance: Decimal, binance_weight: Decimal) -> Decimal {
+        reference + (binance - reference) * binance_weight
+    }
+}
+
+#[async_trait]
+impl PriceSource for BlendedPriceSource {
+    fn name(&self) -> &'static str {
+        "blended"
+    }
+
+    /// Start the configured feed, then Binance; only Binance may fail
+    async fn start(&self) -> Result<()> {
+        if let Err(e) = self.reference.start().await {
+            warn!("⚠️ {} leg of the Binance blend failed to start: {}", self.reference.name(), e);
+            return Err(e);
+        }
+        if let Err(e) = self.binance.start().await {
+            warn!(
+                "⚠️ Binance price source failed to start: {} - blend degraded to {} alone",
+                e,
+                self.reference.name()
+            );
+        }
+        Ok(())
+    }
+
+    async fn get_price(&self) -> Option<Decimal> {
+        match (self.reference.get_price().await, self.binance.get_price().await) {
+            (Some(reference), Some(binance)) => Some(Self::blend(reference, binance, self.binance_weight)),
+            (reference, binance) => binance.or(reference),
+        }
+    }
+
+    async fn is_ready(&self) -> bool {
+        self.reference.is_ready().await || self.binance.is_ready().await
+    }
+
+    async fn set_market_slug(&self, slug: String) {
+        self.reference.set_market_slug(slug.clone()).await;
+        self.binance.set_market_slug(slug).await;
+    }
+
+    async fn reference_book(&self) -> Option<ReferenceBook> {
+        self.binance.reference_book().await
+    }
+}
+
+/// Scripted price source for tests
+///
+/// Each `get_price` call returns the next scripted price; the last one repeats.
+#[cfg(test)]
+pub struct MockPriceSource {
+    prices: Vec<Option<Decimal>>,
+    calls: std::sync::atomic::AtomicUsize,
+    fails_to_start: bool,
+}
+
+#[cfg(test)]
+impl MockPriceSource {
+    pub fn new(prices: Vec<Option<Decimal>>) -> Self {
+        Self {
+            prices,
+            calls: std::sync::atomic::AtomicUsize::new(0),
+            fails_to_start: false,
+        }
+    }
+
+    /// A source whose `start` fails
+    pub fn failing() -> Self {
+        Self {
+            fails_to_start: true,
+            ..Self::new(vec![None])
+        }
+    }
+
+    /// Number of `get_price` calls made so far
+    pub fn calls(&self) -> usize {
+        self.calls.load(std::sync::atomic::Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl PriceSource for MockPriceSource {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    async fn start(&self) -> Result<()> {
+        if self.fails_to_start {
+            anyhow::bail!("mock source failed to start");
+        }
+        Ok(())
+    }
+
+    async fn get_price(&self) -> Option<Decimal> {
+        let call = self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        self.prices
+            .get(call)
+            .or(self.prices.last())
+            .copied()
+            .flatten()
+    }
+
+    async fn is_ready(&self) -> bool {
+        self.calls() > 0
+    }
+
+    async fn set_market_slug(&self, _slug: String) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_price_source() {
+        assert_eq!("HTTP".parse::<PriceSourceKind>().unwrap(), PriceSourceKind::Http);
+        assert_eq!("binance".parse::<PriceSourceKind>().unwrap(), PriceSourceKind::Binance);
+        assert_eq!("simulated".parse::<PriceSourceKind>().unwrap(), PriceSourceKind::Simulated);
+        assert!("chrome".parse::<PriceSourceKind>().is_err());
+    }
+
+    #[test]
+    fn test_reference_book_microprice() {
+        let book = ReferenceBook {
+            best_bid: Decimal::from(100_000),
+            best_ask: Decimal::from(100_010),
+            bid_size: Decimal::from(3),
+            ask_size: Decimal::from(1),
+        };
+        assert_eq!(book.mid(), Decimal::from(100_005));
+        // Heavy bids push the microprice toward the ask
+        assert_eq!(book.microprice(), Decimal::from_str_exact("100007.5").unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_mock_price_sequence() {
+        let source = MockPriceSource::new(vec![None, Some(Decimal::from(98000)), Some(Decimal::from(98100))]);
+        assert_eq!(source.get_price().await, None);
+        assert_eq!(source.get_price().await, Some(Decimal::from(98000)));
+        assert_eq!(source.get_price().await, Some(Decimal::from(98100)));
+        assert_eq!(source.get_price().await, Some(Decimal::from(98100)));
+        assert_eq!(source.calls(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_blended_price() {
+        let price = |value: Option<i64>| Arc::new(MockPriceSource::new(vec![value.map(Decimal::from)]));
+        let weight = Decimal::from_str_exact("0.75").unwrap();
+
+        // 98,000 reference pulled three quarters of the way to Binance's 98,100
+        let blended = BlendedPriceSource::new(price(Some(98_000)), price(Some(98_100)), weight);
+        assert_eq!(blended.get_price().await, Some(Decimal::from(98_075)));
+
+        // Either feed alone stands in for the blend
+        let binance_down = BlendedPriceSource::new(price(Some(98_000)), price(None), weight);
+        assert_eq!(binance_down.get_price().await, Some(Decimal::from(98_000)));
+        let reference_down = BlendedPriceSource::new(price(None), price(Some(98_100)), weight);
+        assert_eq!(reference_down.get_price().await, Some(Decimal::from(98_100)));
+        assert_eq!(BlendedPriceSource::new(price(None), price(None), weight).get_price().await, None);
+
+        // The configured leg must start; Binance failing only degrades the blend
+        let failing = || Arc::new(MockPriceSource::failing());
+        assert!(BlendedPriceSource::new(failing(), price(None), weight).start().await.is_err());
+        assert!(BlendedPriceSource::new(price(None), failing(), weight).start().await.is_ok());
+    }
+
+    #[cfg(feature = "scraper")]
+    #[tokio::test]
+    async fn test_http_source_skips_browser() {
+        let config = BotConfig {
+            price_source: PriceSourceKind::Http,
+            ..BotConfig::default()
+        };
+        let browser = crate::polymarket_price::FakeBrowser::default();
+        let scraper = || -> Arc<dyn PriceSource> {
+            Arc::new(crate::polymarket_price::PolymarketPriceService::new(
+                std::time::Duration::from_millis(100),
+                browser.clone(),
+            ))
+        };
+        let source = configured_price_source(&config, &reqwest::Client::new(), scraper);
+        assert_eq!(source.name(), "http");
+
+        source.set_market_slug("btc-updown-15m-test".to_string()).await;
+        assert!(!source.is_ready().await);
+        assert_eq!(browser.launches.load(std::sync::atomic::Ordering::Relaxed), 0);
+    }
+}
@@ -3,6 +3,18 @@ use rust_decimal::Decimal;
 use rust_decimal::prelude::*;
 use std::cmp;
 
+use crate::models::{ForceDirection, OrderBook, OrderSide, TakeProfitTrigger, TpSlMode};
+
+/// Minutes in a (365 day) year, for annualizing volatility
+pub const MINUTES_PER_YEAR: f64 = 525_600.0;
+
+/// Length of a market window, over which the dynamic entry discount is scaled
+const WINDOW_MINUTES: f64 = 15.0;
+
+/// Volatility bounds searched when solving for implied vol (annualized)
+const MIN_IMPLIED_VOL: f64 = 0.0001;
+const MAX_IMPLIED_VOL: f64 = 20.0;
+
 /// The "Gamma Compressor" - calculates fair value for prediction market tokens
 pub struct QuantEngine;
 
@@ -42,6 +54,114 @@ impl QuantEngine {
         )
     }
 
+    /// Calculate fair value from a volatility input (binary option model)
+    ///
+    /// Prices the UP token as P(spot ends above strike) for a driftless
+    /// lognormal spot with annualized volatility `vol`.
+    pub fn calculate_fair_value_with_vol(
+        spot_price: Decimal,
+        strike_price: Decimal,
+        minutes_remaining: f64,
+        vol: f64,
+    ) -> Decimal {
+        let prob_up = match (spot_price.to_f64(), strike_price.to_f64()) {
+            (Some(spot), Some(strike)) if spot > 0.0 && strike > 0.0 => {
+                Self::prob_up_with_vol(spot, strike, minutes_remaining, vol)
+            }
+            _ => 0.5,
+        };
+
+        Self::clamp(
+            Decimal::from_f64(prob_up).unwrap_or(Decimal::from_str("0.50").unwrap()),
+            Decimal::from_str("0.01").unwrap(),
+            Decimal::from_str("0.99").unwrap(),
+        )
+    }
+
+    /// Fair value using the vol model when a volatility is available,
+    /// otherwise the gamma compressor
+    pub fn calculate_model_fair_value(
+        spot_price: Decimal,
+        strike_price: Decimal,
+        minutes_remaining: f64,
+        vol: Option<f64>,
+    ) -> Decimal {
+        match vol {
+            Some(vol) if vol > 0.0 => {
+                Self::calculate_fair_value_with_vol(spot_price, strike_price, minutes_remaining, vol)
+            }
+            _ => Self::calculate_fair_value(spot_price, strike_price, minutes_remaining),
+        }
+    }
+
+    /// Solve for the volatility at which the model's UP fair value equals `mid`
+    ///
+    /// Returns None when no volatility reproduces the price, e.g. at the
+    /// strike or when the mid sits on the opposite side of 0.50 from spot.
+    pub fn implied_vol(
+        mid: Decimal,
+        spot_price: Decimal,
+        strike_price: Decimal,
+        minutes_remaining: f64,
+    ) -> Option<f64> {
+        let mid = mid.to_f64()?;
+        let spot = spot_price.to_f64()?;
+        let strike = strike_price.to_f64()?;
+        if mid <= 0.0 || mid >= 1.0 || spot <= 0.0 || strike <= 0.0 || minutes_remaining <= 0.0 {
+            return None;
+        }
+
+        // The model price moves monotonically towards 0.50 as vol rises
+        let price_at = |vol: f64| Self::prob_up_with_vol(spot, strike, minutes_remaining, vol);
+        let (low_price, high_price) = (price_at(MIN_IMPLIED_VOL), price_at(MAX_IMPLIED_VOL));
+        if mid < low_price.min(high_price) || mid > low_price.max(high_price) || low_price == high_price {
+            return None;
+        }
+
+        // Bisection
+        let (mut low, mut high) = (MIN_IMPLIED_VOL, MAX_IMPLIED_VOL);
+        for _ in 0..100 {
+            let vol = (low + high) / 2.0;
+            let above_target = (price_at(vol) - mid) * (low_price - high_price) > 0.0;
+            if above_target {
+                low = vol;
+            } else {
+                high = vol;
+            }
+        }
+
+        Some((low + high) / 2.0)
+    }
+
+    /// Raw UP probability under the vol model
+    fn prob_up_with_vol(spot: f64, strike: f64, minutes_remaining: f64, vol: f64) -> f64 {
+        let std_dev = vol * (minutes_remaining.max(0.0) / MINUTES_PER_YEAR).sqrt();
+        if std_dev <= 0.0 {
+            return match spot.partial_cmp(&strike) {
+                Some(cmp::Ordering::Greater) => 1.0,
+                Some(cmp::Ordering::Less) => 0.0,
+                _ => 0.5,
+            };
+        }
+
+        Self::normal_cdf((spot / strike).ln() / std_dev)
+    }
+
+    /// Standard normal CDF (Abramowitz & Stegun 7.1.26, error < 1.5e-7)
+    fn normal_cdf(x: f64) -> f64 {
+        let z = x.abs() / std::f64::consts::SQRT_2;
+        let t = 1.0 / (1.0 + 0.3275911 * z);
+        let poly = t * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+        let erf = 1.0 - poly * (-z * z).exp();
+
+        if x >= 0.0 {
+            0.5 * (1.0 + erf)
+        } else {
+            0.5 * (1.0 - erf)
+        }
+    }
+
     /// Determine which token to trade and its fair value
     ///
     /// Returns (token_to_trade, fair_value, direction)
@@ -66,6 +186,32 @@ impl QuantEngine {
         }
     }
 
+    /// Determine which token to trade, honouring a manual direction override
+    ///
+    /// `ForceDirection::Auto` picks the side spot is on, like
+    /// `select_trading_direction`. A forced side still uses the model's fair
+    /// value for that side. `vol` selects the vol model (see
+    /// `calculate_model_fair_value`).
+    pub fn select_direction_with_override(
+        spot_price: Decimal,
+        strike_price: Decimal,
+        minutes_remaining: f64,
+        force_direction: ForceDirection,
+        vol: Option<f64>,
+    ) -> (String, Decimal, String) {
+        let prob_up =
+            Self::calculate_model_fair_value(spot_price, strike_price, minutes_remaining, vol);
+
+        match force_direction {
+            ForceDirection::Auto if spot_price >= strike_price => {
+                ("UP".to_string(), prob_up, "LONG".to_string())
+            }
+            ForceDirection::Auto => ("DOWN".to_string(), Decimal::ONE - prob_up, "LONG".to_string()),
+            ForceDirection::Up => ("UP".to_string(), prob_up, "LONG".to_string()),
+            ForceDirection::Down => ("DOWN".to_string(), Decimal::ONE - prob_up, "LONG".to_string()),
+        }
+    }
+
     /// Calculate entry target price (fair value - discount)
     pub fn calculate_entry_price(fair_value: Decimal, panic_discount: Decimal) -> Decimal {
         let target = fair_value - panic_discount;
@@ -76,9 +222,125 @@ impl QuantEngine {
         )
     }
 
+    /// Entry discount widened by realized volatility: `base + k * vol`
+    ///
+    /// `vol` is annualized; without an estimate the base discount applies.
+    /// The result is clamped to [0, max_discount].
+    pub fn effective_discount(
+        base_discount: Decimal,
+        vol_coefficient: Decimal,
+        vol: Option<f64>,
+        max_discount: Decimal,
+    ) -> Decimal {
+        let vol = vol.and_then(Decimal::from_f64).unwrap_or(Decimal::ZERO);
+        Self::clamp(base_discount + vol_coefficient * vol, Decimal::ZERO, max_discount)
+    }
+
+    /// Entry discount scaled by time to expiry
+    ///
+    /// The multiplier on `base_discount` runs linearly from `late_scale` at
+    /// expiry to `early_scale` with the full window left, so entries early in
+    /// a window wait for a deeper discount. Never negative.
+    pub fn dynamic_entry_discount(
+        base_discount: Decimal,
+        minutes_remaining: f64,
+        early_scale: Decimal,
+        late_scale: Decimal,
+    ) -> Decimal {
+        let progress = Decimal::from_f64((minutes_remaining / WINDOW_MINUTES).clamp(0.0, 1.0))
+            .unwrap_or(Decimal::ZERO);
+        let scale = late_scale + (early_scale - late_scale) * progress;
+        (base_discount * scale).max(Decimal::ZERO)
+    }
+
+    /// Market-making bid and ask `offset` either side of fair value, skewed by inventory
+    ///
+    /// Both quotes shift down by `skew * inventory_ratio`, where the ratio is
+    /// held shares over the most we'll hold: the longer we are, the more
+    /// eagerly we sell and the less eagerly we buy.
+    pub fn skewed_quotes(
+        fair_value: Decimal,
+        inventory_ratio: Decimal,
+        offset: Decimal,
+        skew: Decimal,
+    ) -> (Decimal, Decimal) {
+        let center = fair_value - skew * inventory_ratio;
+        (center - offset, center + offset)
+    }
+
+    /// Snap an order price onto the exchange's tick grid
+    ///
+    /// Buys round down and sells round up, so snapping never pays more or
+    /// accepts less than the price asked for. A zero tick leaves it as is.
+    pub fn round_to_tick(price: Decimal, tick_size: Decimal, side: OrderSide) -> Decimal {
+        if tick_size <= Decimal::ZERO {
+            return price;
+        }
+        let ticks = price / tick_size;
+        let ticks = match side {
+            OrderSide::BUY => ticks.floor(),
+            OrderSide::SELL => ticks.ceil(),
+        };
+        ticks * tick_size
+    }
+
+    /// Check the modeled edge over the ask clears the minimum
+    pub fn has_min_edge(fair_value: Decimal, best_ask: Decimal, min_edge: Decimal) -> bool {
+        fair_value - best_ask >= min_edge
+    }
+
+    /// Expected average price for buying `size` shares, walking the ask depth
+    ///
+    /// Returns None when the asks can't fill the whole size, since the
+    /// shortfall has no price to be judged at.
+    pub fn expected_fill_price(book: &OrderBook, size: Decimal) -> Option<Decimal> {
+        book.walk(OrderSide::BUY, size)
+            .filter(|fill| fill.filled >= size)
+            .map(|fill| fill.avg_price)
+    }
+
+    /// Shrink an entry of `size` shares to the depth it may take from the book
+    ///
+    /// `max_levels` limits how many ask levels the order reaches into and
+    /// `max_shares` caps the shares taken; zero leaves either unlimited.
+    pub fn cap_to_depth(book: &OrderBook, size: Decimal, max_levels: usize, max_shares: Decimal) -> Decimal {
+        let mut capped = size;
+        if max_levels > 0 {
+            capped = capped.min(book.depth(OrderSide::BUY, max_levels));
+        }
+        if max_shares > Decimal::ZERO {
+            capped = capped.min(max_shares);
+        }
+        capped.floor()
+    }
+
+    /// Price checked against the take profit target; the exit itself still sells at the bid
+    pub fn take_profit_reference(
+        trigger: TakeProfitTrigger,
+        best_bid: Decimal,
+        best_ask: Decimal,
+        last_trade: Option<Decimal>,
+    ) -> Decimal {
+        match trigger {
+            TakeProfitTrigger::Bid => best_bid,
+            TakeProfitTrigger::Mid => (best_bid + best_ask) / Decimal::from(2),
+            TakeProfitTrigger::Last => last_trade.unwrap_or(best_bid),
+        }
+    }
+
     /// Calculate take profit target
-    pub fn calculate_take_profit(entry_price: Decimal, scalp_profit: Decimal) -> Decimal {
-        let target = entry_price + scalp_profit;
+    ///
+    /// `scalp_profit` above entry, or `absolute_price` itself in absolute mode.
+    pub fn calculate_take_profit(
+        mode: TpSlMode,
+        entry_price: Decimal,
+        scalp_profit: Decimal,
+        absolute_price: Decimal,
+    ) -> Decimal {
+        let target = match mode {
+            TpSlMode::Relative => entry_price + scalp_profit,
+            TpSlMode::Absolute => absolute_price,
+        };
         Self::clamp(
             target,
             Decimal::from_str("0.01").unwrap(),
@@ -87,8 +349,18 @@ impl QuantEngine {
     }
 
     /// Calculate stop loss trigger price
-    pub fn calculate_stop_loss(entry_price: Decimal, stop_loss_threshold: Decimal) -> Decimal {
-        let target = entry_price - stop_loss_threshold;
+    ///
+    /// `stop_loss_threshold` below entry, or `absolute_price` itself in absolute mode.
+    pub fn calculate_stop_loss(
+        mode: TpSlMode,
+        entry_price: Decimal,
+        stop_loss_threshold: Decimal,
+        absolute_price: Decimal,
+    ) -> Decimal {
+        let target = match mode {
+            TpSlMode::Relative => entry_price - stop_loss_threshold,
+            TpSlMode::Absolute => absolute_price,
+        };
         Self::clamp(
             target,
             Decimal::from_str("0.01").unwrap(),
@@ -96,6 +368,49 @@ impl QuantEngine {
         )
     }
 
+    /// Calculate the trailing stop trigger from the peak bid seen in position
+    ///
+    /// Returns None until the peak has cleared entry by `activation`; after
+    /// that the trigger sits `distance` below the peak.
+    pub fn calculate_trailing_stop(
+        entry_price: Decimal,
+        peak_bid: Decimal,
+        activation: Decimal,
+        distance: Decimal,
+    ) -> Option<Decimal> {
+        if distance <= Decimal::ZERO || peak_bid < entry_price + activation {
+            return None;
+        }
+
+        Some(Self::clamp(
+            peak_bid - distance,
+            Decimal::from_str("0.01").unwrap(),
+            Decimal::from_str("0.99").unwrap(),
+        ))
+    }
+
+    /// Check whether the model is confident enough to exit on fair value alone
+    ///
+    /// A band of 0 disables the exit.
+    pub fn in_exit_band(fair_value: Decimal, exit_band: Decimal) -> bool {
+        exit_band > Decimal::ZERO && fair_value >= exit_band
+    }
+
+    /// Check whether an open position must be flattened ahead of expiry
+    ///
+    /// A threshold of 0 disables the forced exit.
+    pub fn should_force_exit(minutes_remaining: f64, force_exit_seconds: i64) -> bool {
+        force_exit_seconds > 0 && minutes_remaining * 60.0 < force_exit_seconds as f64
+    }
+
+    /// Round an order size down to a multiple of `step`
+    pub fn round_to_step(size: Decimal, step: Decimal) -> Decimal {
+        if step <= Decimal::ZERO {
+            return size;
+        }
+        (size / step).floor() * step
+    }
+
     /// Calculate position size based on capital and price
     pub fn calculate_position_size(
         max_capital: Decimal,
@@ -109,6 +424,38 @@ impl QuantEngine {
         size.floor() // Round down to whole shares
     }
 
+    /// Capital for an entry scaled by its edge: `max_capital * min(1, edge / full_edge)`
+    ///
+    /// Thin edges size small and fat ones full, clamped to `[min_capital, max_capital]`.
+    pub fn confidence_capital(
+        max_capital: Decimal,
+        min_capital: Decimal,
+        edge: Decimal,
+        full_edge: Decimal,
+    ) -> Decimal {
+        if full_edge <= Decimal::ZERO {
+            return max_capital;
+        }
+        let confidence = (edge / full_edge).clamp(Decimal::ZERO, Decimal::ONE);
+        (max_capital * confidence).max(min_capital).min(max_capital)
+    }
+
+    /// Check that an entry leaves at least `min_ratio` of cash per dollar of positions
+    ///
+    /// `position_value` marks what is already held; the entry moves `cost`
+    /// from cash into positions. A ratio of 0 disables the check.
+    pub fn preserves_cash_ratio(
+        cash: Decimal,
+        position_value: Decimal,
+        cost: Decimal,
+        min_ratio: Decimal,
+    ) -> bool {
+        let positions_after = position_value + cost;
+        min_ratio <= Decimal::ZERO
+            || positions_after <= Decimal::ZERO
+            || cash - cost >= min_ratio * positions_after
+    }
+
     /// Check if order price needs updating (> 2 cent drift)
     pub fn should_update_order(current_price: Decimal, new_target_price: Decimal) -> bool {
         let drift = (current_price - new_target_price).abs();
@@ -193,6 +540,283 @@ mod tests {
         assert!(fair > Decimal::from_str("0.50").unwrap());
     }
 
+    #[test]
+    fn test_forced_direction() {
+        let spot = Decimal::from(99000);
+        let strike = Decimal::from(98500);
+        let minutes = 10.0;
+
+        let (auto_token, auto_fair, _) =
+            QuantEngine::select_direction_with_override(spot, strike, minutes, ForceDirection::Auto, None);
+        assert_eq!(auto_token, "UP");
+        assert_eq!(
+            auto_fair,
+            QuantEngine::select_trading_direction(spot, strike, minutes).1
+        );
+
+        // Forcing DOWN above strike trades DOWN at the complementary fair value
+        let (token, fair, _) =
+            QuantEngine::select_direction_with_override(spot, strike, minutes, ForceDirection::Down, None);
+        assert_eq!(token, "DOWN");
+        assert_eq!(fair, Decimal::ONE - auto_fair);
+    }
+
+    #[test]
+    fn test_fair_value_with_vol() {
+        let strike = Decimal::from(98500);
+
+        // At the strike the vol model is a coin flip
+        let at_strike = QuantEngine::calculate_fair_value_with_vol(strike, strike, 10.0, 0.5);
+        assert!((at_strike - Decimal::from_str("0.50").unwrap()).abs() < Decimal::from_str("0.0001").unwrap());
+
+        // Higher vol pulls an in-the-money token back towards 0.50
+        let spot = Decimal::from(98600);
+        let low_vol = QuantEngine::calculate_fair_value_with_vol(spot, strike, 10.0, 0.3);
+        let high_vol = QuantEngine::calculate_fair_value_with_vol(spot, strike, 10.0, 0.9);
+        assert!(low_vol > high_vol);
+        assert!(high_vol > Decimal::from_str("0.50").unwrap());
+
+        // No vol input falls back to the gamma compressor
+        assert_eq!(
+            QuantEngine::calculate_model_fair_value(spot, strike, 10.0, None),
+            QuantEngine::calculate_fair_value(spot, strike, 10.0)
+        );
+    }
+
+    #[test]
+    fn test_implied_vol_round_trip() {
+        let strike = Decimal::from(98500);
+        let tolerance = Decimal::from_str("0.0001").unwrap();
+
+        for (spot, mid) in [(98600, "0.68"), (98450, "0.35"), (98510, "0.97")] {
+            let spot = Decimal::from(spot);
+            let mid = Decimal::from_str(mid).unwrap();
+
+            let vol = QuantEngine::implied_vol(mid, spot, strike, 10.0).unwrap();
+            let fair = QuantEngine::calculate_fair_value_with_vol(spot, strike, 10.0, vol);
+            assert!((fair - mid).abs() < tolerance, "mid {} came back as {}", mid, fair);
+        }
+
+        // Unreachable prices have no implied vol
+        let above = Decimal::from(98600);
+        assert_eq!(QuantEngine::implied_vol(Decimal::from_str("0.40").unwrap(), above, strike, 10.0), None);
+        assert_eq!(QuantEngine::implied_vol(Decimal::from_str("0.60").unwrap(), strike, strike, 10.0), None);
+        assert_eq!(QuantEngine::implied_vol(Decimal::from_str("0.60").unwrap(), above, strike, 0.0), None);
+    }
+
+    #[test]
+    fn test_effective_discount_scales_with_vol() {
+        let base = Decimal::from_str("0.08").unwrap();
+        let k = Decimal::from_str("0.10").unwrap();
+        let max = Decimal::from_str("0.30").unwrap();
+
+        let calm = QuantEngine::effective_discount(base, k, Some(0.2), max);
+        let wild = QuantEngine::effective_discount(base, k, Some(1.0), max);
+        assert_eq!(calm, Decimal::from_str("0.10").unwrap());
+        assert_eq!(wild, Decimal::from_str("0.18").unwrap());
+        assert!(wild > calm);
+
+        // Clamped at the cap, and unchanged without a vol estimate or coefficient
+        assert_eq!(QuantEngine::effective_discount(base, k, Some(5.0), max), max);
+        assert_eq!(QuantEngine::effective_discount(base, k, None, max), base);
+        assert_eq!(QuantEngine::effective_discount(base, Decimal::ZERO, Some(1.0), max), base);
+    }
+
+    #[test]
+    fn test_dynamic_entry_discount() {
+        let base = Decimal::from_str("0.08").unwrap();
+        let early = Decimal::from_str("1.5").unwrap();
+        let late = Decimal::from_str("0.5").unwrap();
+
+        let at_14 = QuantEngine::dynamic_entry_discount(base, 14.0, early, late);
+        let at_2 = QuantEngine::dynamic_entry_discount(base, 2.0, early, late);
+        assert!(at_14 > at_2);
+        assert_eq!(QuantEngine::dynamic_entry_discount(base, 15.0, early, late), Decimal::from_str("0.12").unwrap());
+        assert_eq!(QuantEngine::dynamic_entry_discount(base, 0.0, early, late), Decimal::from_str("0.04").unwrap());
+
+        // Flat scales leave the base discount unchanged
+        assert_eq!(QuantEngine::dynamic_entry_discount(base, 7.5, Decimal::ONE, Decimal::ONE), base);
+
+        // Never negative, even past expiry or with a zero late scale
+        for minutes in [-1.0, 0.0, 1.0, 30.0] {
+            assert!(QuantEngine::dynamic_entry_discount(base, minutes, early, Decimal::ZERO) >= Decimal::ZERO);
+        }
+        assert_eq!(QuantEngine::dynamic_entry_discount(base, -1.0, early, Decimal::ZERO), Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_tp_sl_modes() {
+        let entry = Decimal::from_str("0.50").unwrap();
+        let offset = Decimal::from_str("0.10").unwrap();
+        let take = Decimal::from_str("0.90").unwrap();
+        let stop = Decimal::from_str("0.20").unwrap();
+
+        assert_eq!(
+            QuantEngine::calculate_take_profit(TpSlMode::Relative, entry, offset, take),
+            Decimal::from_str("0.60").unwrap()
+        );
+        assert_eq!(QuantEngine::calculate_take_profit(TpSlMode::Absolute, entry, offset, take), take);
+        assert_eq!(
+            QuantEngine::calculate_stop_loss(TpSlMode::Relative, entry, offset, stop),
+            Decimal::from_str("0.40").unwrap()
+        );
+        assert_eq!(QuantEngine::calculate_stop_loss(TpSlMode::Absolute, entry, offset, stop), stop);
+    }
+
+    #[test]
+    fn test_min_edge() {
+        let fair = Decimal::from_str("0.50").unwrap();
+        let min_edge = Decimal::from_str("0.10").unwrap();
+
+        // 0.41 clears the 0.08 panic discount but only has 0.09 of edge
+        let ask = Decimal::from_str("0.41").unwrap();
+        assert!(ask <= QuantEngine::calculate_entry_price(fair, Decimal::from_str("0.08").unwrap()));
+        assert!(!QuantEngine::has_min_edge(fair, ask, min_edge));
+
+        assert!(QuantEngine::has_min_edge(fair, Decimal::from_str("0.40").unwrap(), min_edge));
+        assert!(QuantEngine::has_min_edge(fair, ask, Decimal::ZERO));
+    }
+
+    #[test]
+    fn test_trailing_stop_ratchets() {
+        let entry = Decimal::from_str("0.50").unwrap();
+        let activation = Decimal::from_str("0.02").unwrap();
+        let distance = Decimal::from_str("0.03").unwrap();
+        let trail = |peak: &str| {
+            QuantEngine::calculate_trailing_stop(entry, Decimal::from_str(peak).unwrap(), activation, distance)
+        };
+
+        // Not armed until the peak clears entry + activation
+        assert_eq!(trail("0.51"), None);
+        assert_eq!(trail("0.52"), Some(Decimal::from_str("0.49").unwrap()));
+
+        // The trigger follows the peak up
+        assert_eq!(trail("0.60"), Some(Decimal::from_str("0.57").unwrap()));
+        assert_eq!(trail("0.99"), Some(Decimal::from_str("0.96").unwrap()));
+
+        // Disabled with a zero distance
+        assert_eq!(
+            QuantEngine::calculate_trailing_stop(entry, Decimal::from_str("0.60").unwrap(), activation, Decimal::ZERO),
+            None
+        );
+    }
+
+    #[test]
+    fn test_exit_band() {
+        let band = Decimal::from_str("0.80").unwrap();
+        assert!(!QuantEngine::in_exit_band(Decimal::from_str("0.79").unwrap(), band));
+        assert!(QuantEngine::in_exit_band(Decimal::from_str("0.80").unwrap(), band));
+        assert!(QuantEngine::in_exit_band(Decimal::from_str("0.95").unwrap(), band));
+
+        // Disabled when the band is zero
+        assert!(!QuantEngine::in_exit_band(Decimal::from_str("0.95").unwrap(), Decimal::ZERO));
+    }
+
+    #[test]
+    fn test_expected_fill_price_vs_top_of_book() {
+        let dec = |value: &str| Decimal::from_str(value).unwrap();
+        let fair = dec("0.60");
+        let thin = OrderBook::from_levels(&[], &[("0.45", "20"), ("0.55", "50"), ("0.70", "500")]);
+        let top_edge = fair - thin.best_ask().unwrap();
+
+        // A small order keeps the top-of-book edge
+        assert_eq!(QuantEngine::expected_fill_price(&thin, dec("10")), Some(dec("0.45")));
+
+        // 60 shares reach the second level: (9 + 22) / 60
+        let fill = QuantEngine::expected_fill_price(&thin, dec("60")).unwrap();
+        assert!(fair - fill < top_edge);
+        assert!(fair - fill > Decimal::ZERO);
+
+        // 200 shares mostly fill at 0.70: the edge turns negative
+        let fill = QuantEngine::expected_fill_price(&thin, dec("200")).unwrap();
+        assert!(fair - fill < Decimal::ZERO);
+
+        // More than the asks hold can't be priced
+        assert_eq!(QuantEngine::expected_fill_price(&thin, dec("1000")), None);
+    }
+
+    #[test]
+    fn test_cap_to_depth() {
+        let dec = |value: &str| Decimal::from_str(value).unwrap();
+        let book = OrderBook::from_levels(&[], &[("0.47", "500"), ("0.45", "20"), ("0.46", "30.5")]);
+
+        // Unlimited by default
+        assert_eq!(QuantEngine::cap_to_depth(&book, dec("200"), 0, Decimal::ZERO), dec("200"));
+
+        // Two best levels hold 50.5 shares: whole shares only
+        assert_eq!(QuantEngine::cap_to_depth(&book, dec("200"), 2, Decimal::ZERO), dec("50"));
+
+        // The tighter of the two limits wins
+        assert_eq!(QuantEngine::cap_to_depth(&book, dec("200"), 2, dec("40")), dec("40"));
+
+        // Orders already inside the limit are untouched
+        assert_eq!(QuantEngine::cap_to_depth(&book, dec("10"), 1, dec("40")), dec("10"));
+    }
+
+    #[test]
+    fn test_skewed_quotes() {
+        let dec = |value: &str| Decimal::from_str(value).unwrap();
+        let (fair, offset, skew) = (dec("0.50"), dec("0.02"), dec("0.04"));
+
+        // Flat: symmetric around fair value
+        assert_eq!(QuantEngine::skewed_quotes(fair, Decimal::ZERO, offset, skew), (dec("0.48"), dec("0.52")));
+
+        // Longer inventory lowers both quotes in proportion, keeping the width
+        assert_eq!(QuantEngine::skewed_quotes(fair, dec("0.25"), offset, skew), (dec("0.47"), dec("0.51")));
+        assert_eq!(QuantEngine::skewed_quotes(fair, dec("0.5"), offset, skew), (dec("0.46"), dec("0.50")));
+        assert_eq!(QuantEngine::skewed_quotes(fair, Decimal::ONE, offset, skew), (dec("0.44"), dec("0.48")));
+
+        // No skew coefficient: inventory is ignored
+        assert_eq!(QuantEngine::skewed_quotes(fair, Decimal::ONE, offset, Decimal::ZERO), (dec("0.48"), dec("0.52")));
+    }
+
+    #[test]
+    fn test_round_to_tick() {
+        let dec = |value: &str| Decimal::from_str(value).unwrap();
+        let round = QuantEngine::round_to_tick;
+
+        // Off-grid prices: buys bid less, sells ask more
+        assert_eq!(round(dec("0.4567"), dec("0.01"), OrderSide::BUY), dec("0.45"));
+        assert_eq!(round(dec("0.4567"), dec("0.01"), OrderSide::SELL), dec("0.46"));
+        assert_eq!(round(dec("0.4567"), dec("0.001"), OrderSide::BUY), dec("0.456"));
+        assert_eq!(round(dec("0.4567"), dec("0.001"), OrderSide::SELL), dec("0.457"));
+        assert_eq!(round(dec("0.52"), dec("0.05"), OrderSide::BUY), dec("0.50"));
+        assert_eq!(round(dec("0.52"), dec("0.05"), OrderSide::SELL), dec("0.55"));
+
+        // Prices already on the grid are untouched either way
+        assert_eq!(round(dec("0.45"), dec("0.01"), OrderSide::BUY), dec("0.45"));
+        assert_eq!(round(dec("0.45"), dec("0.01"), OrderSide::SELL), dec("0.45"));
+
+        // No tick size: no rounding
+        assert_eq!(round(dec("0.4567"), Decimal::ZERO, OrderSide::SELL), dec("0.4567"));
+    }
+
+    #[test]
+    fn test_round_to_step() {
+        let dec = |value: &str| Decimal::from_str(value).unwrap();
+        assert_eq!(QuantEngine::round_to_step(dec("33.7"), Decimal::ONE), dec("33"));
+        assert_eq!(QuantEngine::round_to_step(dec("33.789"), dec("0.01")), dec("33.78"));
+        assert_eq!(QuantEngine::round_to_step(dec("33"), dec("5")), dec("30"));
+        assert_eq!(QuantEngine::round_to_step(dec("4.9"), dec("5")), Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_take_profit_reference() {
+        let bid = Decimal::from_str("0.50").unwrap();
+        let ask = Decimal::from_str("0.54").unwrap();
+        let last = Some(Decimal::from_str("0.53").unwrap());
+
+        assert_eq!(QuantEngine::take_profit_reference(TakeProfitTrigger::Bid, bid, ask, last), bid);
+        assert_eq!(
+            QuantEngine::take_profit_reference(TakeProfitTrigger::Mid, bid, ask, last),
+            Decimal::from_str("0.52").unwrap()
+        );
+        assert_eq!(QuantEngine::take_profit_reference(TakeProfitTrigger::Last, bid, ask, last), last.unwrap());
+
+        // No trade print yet: fall back to the bid
+        assert_eq!(QuantEngine::take_profit_reference(TakeProfitTrigger::Last, bid, ask, None), bid);
+    }
+
     #[test]
     fn test_position_sizing() {
         let capital = Decimal::from(100);
@@ -202,6 +826,41 @@ mod tests {
         assert_eq!(size, Decimal::from(222)); // 100 / 0.45 = 222.22... -> 222
     }
 
+    #[test]
+    fn test_confidence_capital_scales_with_edge() {
+        let max = Decimal::from(20);
+        let min = Decimal::from(2);
+        let full = Decimal::from_str("0.10").unwrap();
+        let capital = |edge: &str| QuantEngine::confidence_capital(max, min, Decimal::from_str(edge).unwrap(), full);
+
+        // Linear up to the full-size edge
+        assert_eq!(capital("0.05"), Decimal::from(10));
+        assert_eq!(capital("0.10"), max);
+
+        let sizes: Vec<Decimal> = ["-0.02", "0", "0.005", "0.02", "0.05", "0.08", "0.10", "0.30"]
+            .iter()
+            .map(|edge| capital(edge))
+            .collect();
+        assert!(sizes.windows(2).all(|pair| pair[0] <= pair[1]));
+
+        // Never under the floor or over MAX_CAPITAL_PER_TRADE
+        assert_eq!(sizes[0], min);
+        assert_eq!(capital("0.005"), min);
+        assert_eq!(*sizes.last().unwrap(), max);
+    }
+
+    #[test]
+    fn test_force_exit_timing() {
+        // 60 second window: fires inside it, not before
+        assert!(!QuantEngine::should_force_exit(1.5, 60));
+        assert!(!QuantEngine::should_force_exit(1.0, 60));
+        assert!(QuantEngine::should_force_exit(0.99, 60));
+        assert!(QuantEngine::should_force_exit(0.1, 60));
+
+        // Disabled when threshold is zero
+        assert!(!QuantEngine::should_force_exit(0.1, 0));
+    }
+
     #[test]
     fn test_order_update_logic() {
         let current = Decimal::from_str("0.45").unwrap();
@@ -0,0 +1,156 @@
+/// Shared throttle for outbound HTTP to Polymarket
+use anyhow::{Context, Result};
+use reqwest::StatusCode;
+use tokio::sync::Mutex;
+use tokio::time::{Duration, Instant};
+use tracing::warn;
+
+/// Retries of a request answered with 429 before giving the response back
+const MAX_RETRIES: u32 = 3;
+
+/// Wait used when a 429 carries no usable Retry-After
+const DEFAULT_BACKOFF: Duration = Duration::from_secs(1);
+
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+    paused_until: Option<Instant>,
+}
+
+/// Token bucket limiting requests per second across all callers
+///
+/// The bucket holds up to one second's worth of requests, so short bursts
+/// (like parallel discovery lookups) go out immediately.
+pub struct RateLimiter {
+    rate: f64, // requests per second, 0 = unlimited
+    bucket: Mutex<Bucket>,
+}
+
+impl RateLimiter {
+    pub fn new(requests_per_second: f64) -> Self {
+        let rate = requests_per_second.max(0.0);
+        Self {
+            rate,
+            bucket: Mutex::new(Bucket {
+                tokens: rate.max(1.0),
+                last_refill: Instant::now(),
+                paused_until: None,
+            }),
+        }
+    }
+
+    /// Wait until a request may be sent
+    pub async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut bucket = self.bucket.lock().await;
+                let now = Instant::now();
+
+                match bucket.paused_until {
+                    Some(until) if until > now => until - now,
+                    _ => {
+                        bucket.paused_until = None;
+                        if self.rate <= 0.0 {
+                            return;
+                        }
+
+                        let elapsed = (now - bucket.last_refill).as_secs_f64();
+                        bucket.tokens = (bucket.tokens + elapsed * self.rate).min(self.rate.max(1.0));
+                        bucket.last_refill = now;
+
+                        if bucket.tokens >= 1.0 {
+                            bucket.tokens -= 1.0;
+                            return;
+                        }
+                        Duration::from_secs_f64((1.0 - bucket.tokens) / self.rate)
+                    }
+                }
+            };
+
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Hold back every caller for `duration` (after a 429)
+    pub async fn pause_for(&self, duration: Duration) {
+        let until = Instant::now() + duration;
+        let mut bucket = self.bucket.lock().await;
+        bucket.paused_until = Some(bucket.paused_until.map_or(until, |current| current.max(until)));
+    }
+
+    /// Send a request through the limiter, honouring Retry-After on 429
+    pub async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        let mut attempt = 0;
+        loop {
+            self.acquire().await;
+
+            let response = request
+                .try_clone()
+                .context("Request cannot be retried")?
+                .send()
+                .await?;
+            if response.status() != StatusCode::TOO_MANY_REQUESTS || attempt >= MAX_RETRIES {
+                return Ok(response);
+            }
+
+            let wait = parse_retry_after(
+                response
+                    .headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|value| value.to_str().ok()),
+            )
+            .unwrap_or(DEFAULT_BACKOFF);
+            warn!("⚠️ Rate limited by {} - backing off {:?}", response.url().host_str().unwrap_or("server"), wait);
+
+            self.pause_for(wait).await;
+            attempt += 1;
+        }
+    }
+}
+
+/// Parse a Retry-After header given in seconds
+pub fn parse_retry_after(value: Option<&str>) -> Option<Duration> {
+    let seconds: f64 = value?.trim().parse().ok()?;
+    if seconds.is_finite() && seconds >= 0.0 {
+        Some(Duration::from_secs_f64(seconds))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_requests_are_paced() {
+        let limiter = RateLimiter::new(50.0);
+        let started = Instant::now();
+
+        // The first 50 fill the bucket's burst; the next 10 wait ~20ms each
+        for _ in 0..60 {
+            limiter.acquire().await;
+        }
+        let elapsed = started.elapsed();
+        assert!(elapsed >= Duration::from_millis(180), "too fast: {:?}", elapsed);
+        assert!(elapsed < Duration::from_millis(1000), "too slow: {:?}", elapsed);
+    }
+
+    #[tokio::test]
+    async fn test_pause_blocks_callers() {
+        let limiter = RateLimiter::new(0.0);
+        limiter.pause_for(Duration::from_millis(100)).await;
+
+        let started = Instant::now();
+        limiter.acquire().await;
+        assert!(started.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        assert_eq!(parse_retry_after(Some("2")), Some(Duration::from_secs(2)));
+        assert_eq!(parse_retry_after(Some(" 0.5 ")), Some(Duration::from_millis(500)));
+        assert_eq!(parse_retry_after(Some("Wed, 21 Oct 2015 07:28:00 GMT")), None);
+        assert_eq!(parse_retry_after(None), None);
+    }
+}
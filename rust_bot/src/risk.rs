@@ -0,0 +1,87 @@
+/// Session risk limits: the drawdown halt on new entries
+use rust_decimal::Decimal;
+
+/// Halts new entries once equity falls `MAX_DRAWDOWN_PCT` below its session peak
+///
+/// The halt latches: a recovery doesn't lift it, only an operator `/resume`.
+/// Exits are never blocked, so open positions can still be managed out.
+pub struct RiskManager {
+    max_drawdown_pct: Decimal, // 0 = disabled
+    peak_equity: Option<Decimal>,
+    halted: bool,
+}
+
+impl RiskManager {
+    pub fn new(max_drawdown_pct: Decimal) -> Self {
+        Self {
+            max_drawdown_pct,
+            peak_equity: None,
+            halted: false,
+        }
+    }
+
+    /// Record this tick's equity, returning true if it trips the halt
+    pub fn record_equity(&mut self, equity: Decimal) -> bool {
+        let peak = self.peak_equity.map_or(equity, |peak| peak.max(equity));
+        self.peak_equity = Some(peak);
+
+        if self.halted || self.max_drawdown_pct <= Decimal::ZERO {
+            return false;
+        }
+        self.halted = self.drawdown_pct(equity) >= self.max_drawdown_pct;
+        self.halted
+    }
+
+    /// How far `equity` sits below the session peak, in percent
+    pub fn drawdown_pct(&self, equity: Decimal) -> Decimal {
+        match self.peak_equity {
+            Some(peak) if peak > Decimal::ZERO => ((peak - equity) / peak * Decimal::ONE_HUNDRED).max(Decimal::ZERO),
+            _ => Decimal::ZERO,
+        }
+    }
+
+    pub fn peak_equity(&self) -> Option<Decimal> {
+        self.peak_equity
+    }
+
+    pub fn entries_halted(&self) -> bool {
+        self.halted
+    }
+
+    /// Lift the halt; the next equity sample starts a fresh peak
+    pub fn resume(&mut self) {
+        self.halted = false;
+        self.peak_equity = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drawdown_halt_latches() {
+        let mut risk = RiskManager::new(Decimal::from(10));
+        for equity in [100, 110, 104, 100] {
+            assert!(!risk.record_equity(Decimal::from(equity)));
+        }
+        assert_eq!(risk.peak_equity(), Some(Decimal::from(110)));
+
+        // 99 is 10% under the 110 peak
+        assert!(risk.record_equity(Decimal::from(99)));
+        assert!(risk.entries_halted());
+
+        // Recovering doesn't resume; the operator does
+        assert!(!risk.record_equity(Decimal::from(115)));
+        assert!(risk.entries_halted());
+        risk.resume();
+        assert!(!risk.entries_halted());
+        assert!(!risk.record_equity(Decimal::from(104)));
+        assert_eq!(risk.peak_equity(), Some(Decimal::from(104)));
+
+        // Disabled: no drop halts
+        let mut off = RiskManager::new(Decimal::ZERO);
+        off.record_equity(Decimal::from(100));
+        assert!(!off.record_equity(Decimal::from(1)));
+    }
+}
@@ -1,49 +1,141 @@
 /// Automatic market discovery for Polymarket 15-minute BTC Gamma markets
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use futures_util::{stream, StreamExt};
 use rust_decimal::Decimal;
 use serde_json::Value;
+use std::collections::HashMap;
 use std::str::FromStr;
-use tracing::{error, info, warn};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{debug, error, info, warn};
 
-use crate::models::{CryptoPriceResponse, GammaMarket, MarketInfo};
-
-const GAMMA_API_URL: &str = "https://gamma-api.polymarket.com/markets";
-const CRYPTO_PRICE_API_URL: &str = "https://polymarket.com/api/crypto/crypto-price";
+use crate::config::BotConfig;
+use crate::models::{CryptoPriceResponse, GammaMarket, MarketInfo, MarketOverlap};
+use crate::rate_limit::RateLimiter;
 
 /// Market discovery service
 pub struct SlugOracle {
     client: reqwest::Client,
+    rate_limiter: Arc<RateLimiter>,
+    debug_api: bool,
+
+    // Slugs traded or skipped recently, mapped to when they may be selected again (Unix ms)
+    recent_markets: RwLock<HashMap<String, i64>>,
+    recent_market_ttl_ms: i64,
+    market_overlap: MarketOverlap,
+    concurrency: usize, // candidate lookups in flight at once
+    windows: i64,       // windows searched either side of the current one
+    strike_max_deviation_pct: Decimal,
+    gamma_api_url: String,
+    crypto_price_url: String,
+    display_timezone: Tz,
 }
 
 impl SlugOracle {
     /// Create a new SlugOracle
-    pub fn new() -> Self {
+    pub fn new(config: &BotConfig, client: reqwest::Client, rate_limiter: Arc<RateLimiter>) -> Self {
         Self {
-            client: reqwest::Client::builder()
-                .timeout(std::time::Duration::from_secs(10))
-                .build()
-                .expect("Failed to build HTTP client"),
+            client,
+            rate_limiter,
+            debug_api: config.debug_api,
+            recent_markets: RwLock::new(HashMap::new()),
+            recent_market_ttl_ms: config.recent_market_ttl * 1000,
+            market_overlap: config.market_overlap,
+            concurrency: config.discovery_concurrency.max(1),
+            windows: config.discovery_windows.max(1),
+            strike_max_deviation_pct: config.strike_max_deviation_pct,
+            gamma_api_url: config.gamma_api_url.clone(),
+            crypto_price_url: config.crypto_price_api_url.clone(),
+            display_timezone: config.display_timezone,
+        }
+    }
+
+    /// Remember a market we just left so discovery doesn't pick it again
+    pub async fn mark_recent(&self, slug: &str) {
+        if self.recent_market_ttl_ms <= 0 {
+            return;
+        }
+        let until = Utc::now().timestamp_millis() + self.recent_market_ttl_ms;
+        self.recent_markets.write().await.insert(slug.to_string(), until);
+    }
+
+    /// Check whether a slug is still within its recently-traded window
+    async fn is_recent(&self, slug: &str, now_ms: i64) -> bool {
+        let mut recent = self.recent_markets.write().await;
+        recent.retain(|_, until| *until > now_ms);
+        recent.contains_key(slug)
+    }
+
+    /// Pick an active candidate that wasn't traded recently, resolving
+    /// overlapping windows with the configured policy
+    async fn select_market(
+        &self,
+        candidates: Vec<(String, GammaMarket)>,
+        now_ms: i64,
+    ) -> Option<(String, GammaMarket)> {
+        let mut eligible = Vec::new();
+        for (slug, market) in candidates {
+            if !Self::is_market_active(&market) {
+                continue;
+            }
+            if self.is_recent(&slug, now_ms).await {
+                info!("⏭️ Skipping recently traded market: {}", slug);
+                continue;
+            }
+            eligible.push((slug, market));
+        }
+
+        if eligible.len() > 1 {
+            let slugs: Vec<&str> = eligible.iter().map(|(slug, _)| slug.as_str()).collect();
+            warn!(
+                "🔀 {} markets active at once ({}), choosing by {}",
+                eligible.len(),
+                slugs.join(", "),
+                self.market_overlap
+            );
+            if self.market_overlap == MarketOverlap::Longest {
+                // Stable sort: unparseable expiries go last, ties keep candidate order
+                eligible.sort_by_key(|(_, market)| {
+                    let expiry = Self::parse_expiry_timestamp(&market.end_date_iso).unwrap_or(i64::MIN);
+                    std::cmp::Reverse(expiry)
+                });
+            }
         }
+
+        eligible.into_iter().next()
     }
 
     /// Discover the current active 15-minute BTC market
     ///
-    /// Returns MarketInfo with slug, token IDs, strike price, and expiry
-    pub async fn discover_active_market(&self) -> Result<MarketInfo> {
+    /// Returns MarketInfo with slug, token IDs, strike price, and expiry.
+    /// `spot` is the BTC price now, used to sanity-check the fetched strike.
+    pub async fn discover_active_market(&self, spot: Option<Decimal>) -> Result<MarketInfo> {
         info!("🔍 Discovering active 15-minute BTC market...");
 
-        // Generate candidate timestamps (current, next, previous, -2 windows)
+        // Generate candidate timestamps (current, then outward either side)
         let now = Utc::now().timestamp();
         let candidates = self.generate_candidate_timestamps(now);
 
-        // Try all candidates in parallel
-        let mut tasks = Vec::new();
-        for timestamp in candidates {
-            let slug = format!("btc-updown-15m-{}", timestamp);
-            let client = self.client.clone();
-            tasks.push(tokio::spawn(async move {
-                match Self::fetch_market_static(&client, &slug).await {
+        if let Some((slug, market)) = self.find_market(candidates).await {
+            return self.build_market_info(&slug, &market, spot).await;
+        }
+
+        anyhow::bail!("No active 15-minute BTC market found");
+    }
+
+    /// Look up candidate windows, at most `DISCOVERY_CONCURRENCY` at a time
+    ///
+    /// Results arrive in candidate order, so with `MARKET_OVERLAP=current` the
+    /// first eligible market wins and the remaining lookups are dropped.
+    async fn find_market(&self, candidates: Vec<i64>) -> Option<(String, GammaMarket)> {
+        let mut lookups = stream::iter(candidates)
+            .map(|timestamp| async move {
+                let slug = format!("btc-updown-15m-{}", timestamp);
+                match Self::fetch_market_static(&self.client, &self.rate_limiter, &self.gamma_api_url, &slug, self.debug_api)
+                    .await
+                {
                     Ok(Some(market)) => Some((slug, market)),
                     Ok(None) => None,
                     Err(e) => {
@@ -51,19 +143,26 @@ impl SlugOracle {
                         None
                     }
                 }
-            }));
-        }
-
-        // Wait for all tasks and find first valid market
-        for task in tasks {
-            if let Ok(Some((slug, market))) = task.await {
-                if Self::is_market_active(&market) {
-                    return self.build_market_info(&slug, &market).await;
-                }
+            })
+            .buffered(self.concurrency);
+
+        let now_ms = Utc::now().timestamp_millis();
+        let mut found = Vec::new();
+        while let Some(lookup) = lookups.next().await {
+            let Some((slug, market)) = lookup else {
+                continue;
+            };
+            if self.market_overlap == MarketOverlap::Current
+                && Self::is_market_active(&market)
+                && !self.is_recent(&slug, now_ms).await
+            {
+                return Some((slug, market));
             }
+            found.push((slug, market));
         }
 
-        anyhow::bail!("No active 15-minute BTC market found");
+        // Pick the first active market we haven't just left
+        self.select_market(found, now_ms).await
     }
 
     /// Generate candidate timestamps for market discovery
@@ -74,28 +173,66 @@ impl SlugOracle {
         let interval = 15 * 60; // 15 minutes in seconds
         let base = (now / interval) * interval;
 
-        // Try: current window FIRST, then next, then previous windows
-        candidates.push(base);             // Current window (PRIORITY)
-        candidates.push(base + interval);  // Next window
-        candidates.push(base - interval);  // -1 window
-        candidates.push(base - interval * 2); // -2 windows
+        // Try: current window FIRST, then next / previous pairs moving outward
+        candidates.push(base); // Current window (PRIORITY)
+        for offset in 1..=self.windows {
+            candidates.push(base + interval * offset);
+            candidates.push(base - interval * offset);
+        }
 
         candidates
     }
 
     /// Fetch market metadata from Gamma API
-    async fn fetch_market_static(client: &reqwest::Client, slug: &str) -> Result<Option<GammaMarket>> {
-        let url = format!("{}?slug={}", GAMMA_API_URL, slug);
+    async fn fetch_market_static(
+        client: &reqwest::Client,
+        rate_limiter: &RateLimiter,
+        gamma_api_url: &str,
+        slug: &str,
+        debug_api: bool,
+    ) -> Result<Option<GammaMarket>> {
+        let url = format!("{}?slug={}", gamma_api_url, slug);
 
-        let response = client.get(&url).send().await?;
+        let response = rate_limiter.send(client.get(&url)).await?;
 
         if !response.status().is_success() {
             return Ok(None);
         }
 
-        let markets: Vec<GammaMarket> = response.json().await?;
+        let body = response.text().await?;
+        let markets: Vec<GammaMarket> = Self::parse_response("Gamma", &body, debug_api)?;
 
-        Ok(markets.into_iter().next())
+        Ok(Self::pick_gamma_market(slug, markets))
+    }
+
+    /// Choose among the markets Gamma returned for a slug query
+    ///
+    /// Around window transitions the query can match several markets, so take
+    /// the tradable one with this exact slug and the soonest expiry rather than
+    /// whichever came first.
+    fn pick_gamma_market(slug: &str, markets: Vec<GammaMarket>) -> Option<GammaMarket> {
+        if markets.len() > 1 {
+            let returned: Vec<&str> = markets.iter().map(|market| market.market_slug.as_str()).collect();
+            warn!(
+                "🔀 Gamma returned {} markets for {} ({})",
+                markets.len(),
+                slug,
+                returned.join(", ")
+            );
+        }
+
+        markets
+            .into_iter()
+            .filter(|market| market.market_slug == slug && Self::is_market_active(market))
+            .min_by_key(|market| Self::parse_expiry_timestamp(&market.end_date_iso).unwrap_or(i64::MAX))
+    }
+
+    /// Deserialize an API response body, logging it raw first when `debug_api` is on
+    fn parse_response<T: serde::de::DeserializeOwned>(api: &str, body: &str, debug_api: bool) -> Result<T> {
+        if debug_api {
+            debug!("🐛 Raw {} API response: {}", api, body);
+        }
+        serde_json::from_str(body).with_context(|| format!("Failed to parse {} API response", api))
     }
 
     /// Check if market is currently active
@@ -105,7 +242,7 @@ impl SlugOracle {
     }
 
     /// Build MarketInfo from GammaMarket
-    async fn build_market_info(&self, slug: &str, market: &GammaMarket) -> Result<MarketInfo> {
+    async fn build_market_info(&self, slug: &str, market: &GammaMarket, spot: Option<Decimal>) -> Result<MarketInfo> {
         // Extract token IDs
         if market.clob_token_ids.len() < 2 {
             anyhow::bail!("Market {} has insufficient token IDs", slug);
@@ -118,8 +255,15 @@ impl SlugOracle {
         let expiry_timestamp = Self::parse_expiry_timestamp(&market.end_date_iso)?;
 
         // Try to fetch strike price from API, fallback to parsing from slug
-        let strike_price = match self.fetch_strike_price(slug, &market.game_start_time).await {
-            Ok(price) => price,
+        let strike = self.fetch_strike_price(slug, &market.game_start_time).await;
+        let strike_finalized = strike.is_ok();
+        let strike_price = match strike {
+            Ok(price) => {
+                if let Some(spot) = spot {
+                    self.check_strike(price, spot);
+                }
+                price
+            }
             Err(_) => {
                 // Extract timestamp from slug and use as approximate strike
                 // Format: btc-updown-15m-1766223000
@@ -139,7 +283,7 @@ impl SlugOracle {
         };
 
         info!("✅ Found Active Market: {}", slug);
-        info!("⏳ Expires: {}", Self::format_timestamp(expiry_timestamp));
+        info!("⏳ Expires: {}", Self::format_timestamp(expiry_timestamp, self.display_timezone));
         info!("🎯 Strike: ${:.2}", strike_price);
 
         Ok(MarketInfo {
@@ -148,9 +292,42 @@ impl SlugOracle {
             token_id_down,
             strike_price,
             expiry_timestamp,
+            strike_finalized,
         })
     }
 
+    /// Warn if a fetched strike is further from spot than `STRIKE_MAX_DEVIATION_PCT`
+    ///
+    /// Discovery runs within a window, so the open price should sit close to
+    /// spot; a large gap points at a parsing or window-alignment bug rather
+    /// than a real move. Returns whether the warning fired.
+    fn check_strike(&self, strike: Decimal, spot: Decimal) -> bool {
+        if self.strike_max_deviation_pct <= Decimal::ZERO || spot <= Decimal::ZERO {
+            return false;
+        }
+        let deviation_pct = ((strike - spot) / spot * Decimal::ONE_HUNDRED).abs();
+        if deviation_pct <= self.strike_max_deviation_pct {
+            return false;
+        }
+        warn!(
+            "⚠️ Strike ${:.2} is {:.2}% from spot ${:.2} (max {}%) - check the open price lookup",
+            strike, deviation_pct, spot, self.strike_max_deviation_pct
+        );
+        true
+    }
+
+    /// Retry the open price for a market whose strike is still a placeholder
+    ///
+    /// On success the strike is replaced and marked final; on failure the
+    /// market is left as it was for the next attempt.
+    pub async fn refresh_strike(&self, market: &mut MarketInfo) -> Result<()> {
+        let start = DateTime::from_timestamp_millis(market.expiry_timestamp - 15 * 60 * 1000)
+            .context("Invalid market expiry")?;
+        market.strike_price = self.fetch_strike_price(&market.slug, &start.to_rfc3339()).await?;
+        market.strike_finalized = true;
+        Ok(())
+    }
+
     /// Fetch opening strike price from crypto-price API
     async fn fetch_strike_price(&self, slug: &str, game_start_time: &str) -> Result<Decimal> {
         // Parse game start time
@@ -169,16 +346,16 @@ impl SlugOracle {
         ];
 
         // Fetch from API
-        let response: CryptoPriceResponse = self
-            .client
-            .get(CRYPTO_PRICE_API_URL)
-            .query(&params)
-            .send()
+        let body = self
+            .rate_limiter
+            .send(self.client.get(&self.crypto_price_url).query(&params))
             .await
             .context("Failed to fetch crypto price")?
-            .json()
+            .text()
             .await
-            .context("Failed to parse crypto price response")?;
+            .context("Failed to read crypto price response")?;
+        let response: CryptoPriceResponse =
+            Self::parse_response("crypto-price", &body, self.debug_api)?;
 
         // Parse price from openPrice field
         if let Some(price_f64) = response.open_price {
@@ -198,11 +375,11 @@ impl SlugOracle {
         Ok(dt.timestamp_millis())
     }
 
-    /// Format Unix milliseconds as human-readable timestamp
-    fn format_timestamp(millis: i64) -> String {
+    /// Format Unix milliseconds as human-readable timestamp in `DISPLAY_TIMEZONE`
+    fn format_timestamp(millis: i64, timezone: Tz) -> String {
         let dt = DateTime::from_timestamp_millis(millis)
             .unwrap_or_else(|| Utc::now());
-        dt.format("%m/%d/%Y, %I:%M:%S %p").to_string()
+        dt.with_timezone(&timezone).format("%m/%d/%Y, %I:%M:%S %p").to_string()
     }
 }
 
@@ -210,10 +387,326 @@ impl SlugOracle {
 mod tests {
     use super::*;
 
+    fn gamma_market(slug: &str, active: bool) -> GammaMarket {
+        GammaMarket {
+            condition_id: "0xcondition".to_string(),
+            question_id: "0xquestion".to_string(),
+            question: "Bitcoin Up or Down?".to_string(),
+            market_slug: slug.to_string(),
+            end_date_iso: "2025-12-12T15:15:00Z".to_string(),
+            game_start_time: "2025-12-12T15:00:00Z".to_string(),
+            clob_token_ids: vec!["up".to_string(), "down".to_string()],
+            accepting_orders: active,
+            closed: !active,
+            active,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_overlapping_markets_pick_longest() {
+        let current = "btc-updown-15m-1734015300";
+        let next = "btc-updown-15m-1734016200";
+        let candidates = || {
+            let mut later = gamma_market(next, true);
+            later.end_date_iso = "2025-12-12T15:30:00Z".to_string();
+            vec![
+                (current.to_string(), gamma_market(current, true)),
+                (next.to_string(), later),
+            ]
+        };
+        let now = Utc::now().timestamp_millis();
+
+        let oracle = SlugOracle::new(&BotConfig::default(), reqwest::Client::new(), Arc::new(RateLimiter::new(0.0)));
+        let (slug, _) = oracle.select_market(candidates(), now).await.unwrap();
+        assert_eq!(slug, current);
+
+        let config = BotConfig {
+            market_overlap: MarketOverlap::Longest,
+            ..BotConfig::default()
+        };
+        let oracle = SlugOracle::new(&config, reqwest::Client::new(), Arc::new(RateLimiter::new(0.0)));
+        let (slug, _) = oracle.select_market(candidates(), now).await.unwrap();
+        assert_eq!(slug, next);
+    }
+
+    /// Gamma stand-in answering each slug after `delay`, noting when each request arrived
+    struct GammaResponder {
+        active: bool,
+        delay: std::time::Duration,
+        arrivals: Arc<std::sync::Mutex<Vec<std::time::Instant>>>,
+    }
+
+    impl wiremock::Respond for GammaResponder {
+        fn respond(&self, request: &wiremock::Request) -> wiremock::ResponseTemplate {
+            self.arrivals.lock().unwrap().push(std::time::Instant::now());
+            let (_, slug) = request.url.query_pairs().find(|(key, _)| key == "slug").unwrap();
+            wiremock::ResponseTemplate::new(200)
+                .set_body_json([gamma_market(&slug, self.active)])
+                .set_delay(self.delay)
+        }
+    }
+
+    /// Serve Gamma at the returned URL, with the log of request arrivals
+    async fn serve_gamma(
+        active: bool,
+        delay: std::time::Duration,
+    ) -> (wiremock::MockServer, String, Arc<std::sync::Mutex<Vec<std::time::Instant>>>) {
+        let server = wiremock::MockServer::start().await;
+        let arrivals = Arc::new(std::sync::Mutex::new(Vec::new()));
+        wiremock::Mock::given(wiremock::matchers::path("/markets"))
+            .respond_with(GammaResponder { active, delay, arrivals: arrivals.clone() })
+            .mount(&server)
+            .await;
+        let url = format!("{}/markets", server.uri());
+        (server, url, arrivals)
+    }
+
+    /// Most requests ever awaiting a response at once, each taking `delay`
+    fn peak_in_flight(arrivals: &[std::time::Instant], delay: std::time::Duration) -> usize {
+        arrivals
+            .iter()
+            .map(|&at| arrivals.iter().filter(|&&other| other <= at && at < other + delay).count())
+            .max()
+            .unwrap_or(0)
+    }
+
+    #[tokio::test]
+    async fn test_discovery_concurrency_capped() {
+        let candidates: Vec<i64> = (0..20).map(|n| 1_734_015_300 + n * 900).collect();
+        let delay = std::time::Duration::from_millis(30);
+
+        // Nothing active: all 20 are looked up, never more than 3 at once
+        let (_server, url, arrivals) = serve_gamma(false, delay).await;
+        let config = BotConfig {
+            gamma_api_url: url,
+            discovery_concurrency: 3,
+            ..BotConfig::default()
+        };
+        let oracle = SlugOracle::new(&config, reqwest::Client::new(), Arc::new(RateLimiter::new(0.0)));
+        assert!(oracle.find_market(candidates.clone()).await.is_none());
+        let arrivals = arrivals.lock().unwrap().clone();
+        assert_eq!(arrivals.len(), 20);
+        assert!(peak_in_flight(&arrivals, delay) <= 3);
+        assert!(peak_in_flight(&arrivals, delay) > 1);
+
+        // The first candidate is active: the rest are abandoned
+        let (_server, url, arrivals) = serve_gamma(true, delay).await;
+        let config = BotConfig { gamma_api_url: url, ..config };
+        let oracle = SlugOracle::new(&config, reqwest::Client::new(), Arc::new(RateLimiter::new(0.0)));
+        let (slug, _) = oracle.find_market(candidates).await.unwrap();
+        assert_eq!(slug, "btc-updown-15m-1734015300");
+        assert!(arrivals.lock().unwrap().len() <= 3);
+    }
+
+    #[tokio::test]
+    async fn test_wide_search_picks_longest_lived_market() {
+        let market = |n: i64, ends: &str, active: bool| {
+            let slug = format!("btc-updown-15m-{}", 1_734_015_300 + n * 900);
+            let mut market = gamma_market(&slug, active);
+            market.end_date_iso = ends.to_string();
+            (slug, market)
+        };
+        // Candidate order, as a wide search returns them: current, +1, -1, +2, -2
+        let candidates = vec![
+            market(0, "2025-12-12T15:15:00Z", true),
+            market(1, "2025-12-12T15:30:00Z", true),
+            market(-1, "2025-12-12T15:00:00Z", true),
+            market(2, "2025-12-12T15:45:00Z", false), // listed but not trading yet
+            market(-2, "2025-12-12T14:45:00Z", true),
+        ];
+
+        let config = BotConfig {
+            market_overlap: MarketOverlap::Longest,
+            discovery_windows: 2,
+            ..BotConfig::default()
+        };
+        let oracle = SlugOracle::new(&config, reqwest::Client::new(), Arc::new(RateLimiter::new(0.0)));
+        let (slug, _) = oracle.select_market(candidates.clone(), Utc::now().timestamp_millis()).await.unwrap();
+        assert_eq!(slug, "btc-updown-15m-1734016200");
+
+        // Once that one was traded, the next longest-lived active market
+        oracle.mark_recent("btc-updown-15m-1734016200").await;
+        let (slug, _) = oracle.select_market(candidates, Utc::now().timestamp_millis()).await.unwrap();
+        assert_eq!(slug, "btc-updown-15m-1734015300");
+    }
+
+    #[test]
+    fn test_gamma_multiple_results_pick_active() {
+        let slug = "btc-updown-15m-1734015300";
+        let mut later = gamma_market(slug, true);
+        later.end_date_iso = "2025-12-12T15:30:00Z".to_string();
+        later.condition_id = "0xlater".to_string();
+        let mut soonest = gamma_market(slug, true);
+        soonest.condition_id = "0xsoonest".to_string();
+
+        // A closed market first, a different slug, then two tradable matches
+        let markets = vec![
+            gamma_market(slug, false),
+            gamma_market("btc-updown-15m-1734016200", true),
+            later,
+            soonest,
+        ];
+        let market = SlugOracle::pick_gamma_market(slug, markets).unwrap();
+        assert_eq!(market.condition_id, "0xsoonest");
+        assert!(SlugOracle::is_market_active(&market));
+
+        // Nothing tradable with this slug
+        assert!(SlugOracle::pick_gamma_market(slug, vec![gamma_market(slug, false)]).is_none());
+    }
+
+    /// Serve each body once, in order, to successive HTTP requests
+    async fn serve_bodies(bodies: Vec<&'static str>) -> (wiremock::MockServer, String) {
+        let server = wiremock::MockServer::start().await;
+        for body in bodies {
+            wiremock::Mock::given(wiremock::matchers::path("/crypto-price"))
+                .respond_with(wiremock::ResponseTemplate::new(200).set_body_raw(body, "application/json"))
+                .up_to_n_times(1)
+                .mount(&server)
+                .await;
+        }
+        let url = format!("{}/crypto-price", server.uri());
+        (server, url)
+    }
+
+    #[tokio::test]
+    async fn test_strike_backfilled_once_published() {
+        let (_server, url) = serve_bodies(vec![
+            r#"{"openPrice":null,"closePrice":null}"#,
+            r#"{"openPrice":98765.43,"closePrice":null}"#,
+        ])
+        .await;
+        let config = BotConfig {
+            crypto_price_api_url: url,
+            ..BotConfig::default()
+        };
+        let oracle = SlugOracle::new(&config, reqwest::Client::new(), Arc::new(RateLimiter::new(0.0)));
+
+        // Discovery fell back to a spot placeholder
+        let mut market = MarketInfo {
+            slug: "btc-updown-15m-1734015300".to_string(),
+            token_id_up: "up".to_string(),
+            token_id_down: "down".to_string(),
+            strike_price: Decimal::from(98_500),
+            expiry_timestamp: 1_734_016_200_000,
+            strike_finalized: false,
+        };
+
+        // The window hasn't opened yet: the placeholder stays
+        assert!(oracle.refresh_strike(&mut market).await.is_err());
+        assert_eq!(market.strike_price, Decimal::from(98_500));
+        assert!(!market.strike_finalized);
+
+        // A later tick gets the real open price
+        oracle.refresh_strike(&mut market).await.unwrap();
+        assert_eq!(market.strike_price, Decimal::from_str("98765.43").unwrap());
+        assert!(market.strike_finalized);
+    }
+
+    #[test]
+    fn test_strike_far_from_spot_warns() {
+        let oracle = SlugOracle::new(&BotConfig::default(), reqwest::Client::new(), Arc::new(RateLimiter::new(0.0)));
+        let spot = Decimal::from(98_000);
+
+        // An ordinary move inside the window passes quietly
+        assert!(!oracle.check_strike(Decimal::from(97_500), spot));
+        // A strike 10% off can only be a bad lookup
+        assert!(oracle.check_strike(Decimal::from(88_200), spot));
+        assert!(oracle.check_strike(Decimal::from(107_800), spot));
+
+        let config = BotConfig {
+            strike_max_deviation_pct: Decimal::ZERO,
+            ..BotConfig::default()
+        };
+        let disabled = SlugOracle::new(&config, reqwest::Client::new(), Arc::new(RateLimiter::new(0.0)));
+        assert!(!disabled.check_strike(Decimal::from(88_200), spot));
+    }
+
+    #[tokio::test]
+    async fn test_discover_market_from_mock_gamma() {
+        use wiremock::matchers::{method, path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let window = Utc::now().timestamp() / 900 * 900;
+        let slug = format!("btc-updown-15m-{}", window);
+        let market = serde_json::json!([{
+            "conditionId": "0xcondition",
+            "questionID": "0xquestion",
+            "question": "Bitcoin Up or Down?",
+            "slug": slug,
+            "endDate": "2025-12-12T15:15:00Z",
+            "eventStartTime": "2025-12-12T15:00:00Z",
+            "clobTokenIds": "[\"up-token\", \"down-token\"]",
+            "acceptingOrders": true,
+            "closed": false,
+            "active": true
+        }]);
+        Mock::given(method("GET"))
+            .and(path("/markets"))
+            .and(query_param("slug", slug.as_str()))
+            .respond_with(ResponseTemplate::new(200).set_body_json(market))
+            .mount(&server)
+            .await;
+        // Every other candidate window has no market yet
+        Mock::given(method("GET"))
+            .and(path("/markets"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/crypto-price"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "openPrice": 98765.43 })))
+            .mount(&server)
+            .await;
+
+        let config = BotConfig {
+            gamma_api_url: format!("{}/markets", server.uri()),
+            crypto_price_api_url: format!("{}/crypto-price", server.uri()),
+            ..BotConfig::default()
+        };
+        let oracle = SlugOracle::new(&config, reqwest::Client::new(), Arc::new(RateLimiter::new(0.0)));
+        let market = oracle.discover_active_market(None).await.unwrap();
+        assert_eq!(market.slug, slug);
+        assert_eq!(market.token_id_up, "up-token");
+        assert_eq!(market.token_id_down, "down-token");
+        assert_eq!(market.strike_price, Decimal::from_str("98765.43").unwrap());
+        assert!(market.strike_finalized);
+        assert_eq!(market.expiry_timestamp, 1_765_552_500_000);
+    }
+
+    #[tokio::test]
+    async fn test_recent_market_skipped() {
+        let oracle = SlugOracle::new(&BotConfig::default(), reqwest::Client::new(), Arc::new(RateLimiter::new(0.0)));
+        let old = "btc-updown-15m-1734015300";
+        let new = "btc-updown-15m-1734016200";
+        let candidates = || {
+            vec![
+                (old.to_string(), gamma_market(old, true)),
+                (new.to_string(), gamma_market(new, true)),
+            ]
+        };
+
+        // Nothing recent: first active candidate wins
+        let now = Utc::now().timestamp_millis();
+        let (slug, _) = oracle.select_market(candidates(), now).await.unwrap();
+        assert_eq!(slug, old);
+
+        // After rotating away from it, the old market is skipped
+        oracle.mark_recent(old).await;
+        let (slug, _) = oracle.select_market(candidates(), now).await.unwrap();
+        assert_eq!(slug, new);
+
+        // Once the TTL elapses it becomes selectable again
+        let later = now + oracle.recent_market_ttl_ms + 1;
+        let (slug, _) = oracle.select_market(candidates(), later).await.unwrap();
+        assert_eq!(slug, old);
+        assert!(oracle.recent_markets.read().await.is_empty());
+    }
+
     #[tokio::test]
     async fn test_discover_market() {
-        let oracle = SlugOracle::new();
-        match oracle.discover_active_market().await {
+        let oracle = SlugOracle::new(&BotConfig::default(), reqwest::Client::new(), Arc::new(RateLimiter::new(0.0)));
+        match oracle.discover_active_market(None).await {
             Ok(market) => {
                 println!("Found market: {}", market.slug);
                 println!("Strike: ${:.2}", market.strike_price);
@@ -229,13 +722,63 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_format_timestamp_in_display_timezone() {
+        let millis = 1734016200000; // 2024-12-12 15:10:00 UTC
+        assert_eq!(SlugOracle::format_timestamp(millis, Tz::UTC), "12/12/2024, 03:10:00 PM");
+
+        let new_york: Tz = "America/New_York".parse().unwrap();
+        assert_eq!(SlugOracle::format_timestamp(millis, new_york), "12/12/2024, 10:10:00 AM");
+        let kolkata: Tz = "Asia/Kolkata".parse().unwrap();
+        assert_eq!(SlugOracle::format_timestamp(millis, kolkata), "12/12/2024, 08:40:00 PM");
+    }
+
     #[test]
     fn test_timestamp_generation() {
-        let oracle = SlugOracle::new();
+        let oracle = SlugOracle::new(&BotConfig::default(), reqwest::Client::new(), Arc::new(RateLimiter::new(0.0)));
         let now = 1734016200; // Example timestamp
         let candidates = oracle.generate_candidate_timestamps(now);
 
-        assert_eq!(candidates.len(), 4);
+        let base = now / 900 * 900;
+        assert_eq!(candidates, vec![base, base + 900, base - 900, base + 1800, base - 1800]);
         println!("Candidates: {:?}", candidates);
+
+        // A wider search keeps the current window first
+        let config = BotConfig {
+            discovery_windows: 4,
+            ..BotConfig::default()
+        };
+        let oracle = SlugOracle::new(&config, reqwest::Client::new(), Arc::new(RateLimiter::new(0.0)));
+        let candidates = oracle.generate_candidate_timestamps(base + 450);
+        assert_eq!(candidates.len(), 9);
+        assert_eq!(candidates[0], base);
+        assert_eq!(candidates[8], base - 4 * 900);
+    }
+
+    #[test]
+    fn test_debug_api_logs_raw_body() {
+        let capture = crate::logger::Capture::default();
+        let writer = capture.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        let body = r#"{"openPrice":98500.5,"closePrice":null}"#;
+
+        tracing::subscriber::with_default(subscriber, || {
+            // Off: nothing logged
+            let _: CryptoPriceResponse = SlugOracle::parse_response("crypto-price", body, false).unwrap();
+            assert!(capture.output().is_empty());
+
+            // On: the raw body appears before it is parsed
+            let response: CryptoPriceResponse =
+                SlugOracle::parse_response("crypto-price", body, true).unwrap();
+            assert_eq!(response.open_price, Some(98500.5));
+        });
+
+        let logged = capture.output();
+        assert!(logged.contains("Raw crypto-price API response"));
+        assert!(logged.contains(body));
     }
 }
@@ -0,0 +1,67 @@
+/// Noise smoothing of the BTC spot feed
+use rust_decimal::Decimal;
+
+/// Exponential moving average of spot prices
+///
+/// Each sample moves the average by `alpha` of the gap, so small alphas damp
+/// jitter near the strike at the cost of lag; `alpha = 1` passes the raw price.
+pub struct SpotEma {
+    alpha: Decimal,
+    value: Option<Decimal>,
+}
+
+impl SpotEma {
+    /// Create a smoother weighting each new sample by `alpha` (0 < alpha <= 1)
+    pub fn new(alpha: Decimal) -> Self {
+        Self { alpha, value: None }
+    }
+
+    /// Fold in a spot sample and return the smoothed price
+    pub fn update(&mut self, price: Decimal) -> Decimal {
+        let smoothed = match self.value {
+            Some(prev) => prev + self.alpha * (price - prev),
+            None => price,
+        };
+        self.value = Some(smoothed);
+        smoothed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::prelude::ToPrimitive;
+    use std::str::FromStr;
+
+    fn variance(values: &[Decimal]) -> f64 {
+        let values: Vec<f64> = values.iter().map(|v| v.to_f64().unwrap()).collect();
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64
+    }
+
+    #[test]
+    fn test_ema_smooths_noise() {
+        // Deterministic jitter of up to ±$40 around $98,500
+        let mean = Decimal::from(98_500);
+        let noise = [40, -25, 10, -40, 30, -5, 20, -30];
+        let series: Vec<Decimal> = (0..200)
+            .map(|i| mean + Decimal::from(noise[i % noise.len()]))
+            .collect();
+
+        let mut ema = SpotEma::new(Decimal::from_str("0.1").unwrap());
+        let smoothed: Vec<Decimal> = series.iter().map(|&p| ema.update(p)).collect();
+
+        // Skip the warm-up before comparing
+        assert!(variance(&smoothed[50..]) < variance(&series[50..]) / 4.0);
+        let last = *smoothed.last().unwrap();
+        assert!((last - mean).abs() < Decimal::from(10), "EMA ended at {}", last);
+    }
+
+    #[test]
+    fn test_alpha_one_is_raw_price() {
+        let mut ema = SpotEma::new(Decimal::ONE);
+        for price in [98_500, 98_540, 98_410] {
+            assert_eq!(ema.update(Decimal::from(price)), Decimal::from(price));
+        }
+    }
+}
@@ -1,48 +1,315 @@
 /// Trading service with paper and live modes using polyfill-rs
 use anyhow::{Context, Result};
-use polyfill_rs::{ClobClient, Side as ClobSide, OrderArgs};
+use async_trait::async_trait;
+use polyfill_rs::{
+    ApiCredentials, AssetType, BalanceAllowanceParams, ClobClient, OrderArgs, OrderSummary,
+    OrderType as ClobOrderType, Side as ClobSide,
+};
 use rust_decimal::Decimal;
 use std::collections::HashMap;
+use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
-use tracing::{error, info, warn};
+use tracing::{debug, error, info, warn};
 
 use crate::config::BotConfig;
-use crate::models::{Order, OrderSide, Position};
+use crate::quant::QuantEngine;
+use crate::rate_limit::RateLimiter;
+use crate::user_feed::UserEvent;
+use crate::models::{
+    ClosedTrade, ExitReason, FillStats, MarketInfo, Order, OrderBook, OrderBookLevel, OrderSide,
+    OrderType, PaperFill, Position, SelfTradePrevention,
+};
+
+/// Why a trading call failed
+///
+/// Callers match on the variant to decide whether to retry on the next tick
+/// or give up on the action.
+#[derive(Debug, thiserror::Error)]
+pub enum TradingError {
+    #[error("Insufficient paper cash: need ${needed:.2}, ${available:.2} available after open orders")]
+    InsufficientFunds { needed: Decimal, available: Decimal },
+
+    #[error("No position to sell in token {0}")]
+    NoPosition(String),
+
+    #[error("CLOB client not initialized")]
+    ClientUnavailable,
+
+    #[error("Order {0} not found")]
+    OrderNotFound(String),
+
+    #[error("Open order limit reached ({0} resting)")]
+    OrderLimit(u64),
+
+    #[error("Order rejected: {0}")]
+    Rejected(String),
+
+    #[error("Duplicate order {0} submitted within the dedup window")]
+    Duplicate(String),
+
+    #[error("Order would trade against our own resting order {0}")]
+    SelfTrade(String),
+
+    #[error("Network error: {0}")]
+    Network(#[source] reqwest::Error),
+
+    #[error(transparent)]
+    Other(anyhow::Error),
+}
+
+impl TradingError {
+    /// Whether the same call may succeed on a later tick
+    pub fn is_transient(&self) -> bool {
+        matches!(self, TradingError::Network(_))
+    }
+}
+
+impl From<anyhow::Error> for TradingError {
+    fn from(err: anyhow::Error) -> Self {
+        match err.downcast::<reqwest::Error>() {
+            Ok(err) => TradingError::Network(err),
+            Err(err) => TradingError::Other(err),
+        }
+    }
+}
+
+pub type TradingResult<T> = std::result::Result<T, TradingError>;
+
+/// The CLOB's record of one of our orders
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderStatus {
+    pub original_size: Decimal,
+    pub size_matched: Decimal,
+    pub open: bool, // still resting on the book
+}
+
+/// The CLOB calls live trading depends on, so tests can substitute a mock
+#[async_trait]
+pub trait ClobApi: Send + Sync {
+    /// Sign and post an order, returning the raw CLOB response
+    ///
+    /// `expiration` (Unix seconds) is signed into GTD orders; None means no expiry.
+    async fn post_order(
+        &self,
+        args: &OrderArgs,
+        order_type: ClobOrderType,
+        expiration: Option<u64>,
+    ) -> Result<serde_json::Value>;
+
+    /// Cancel orders by ID
+    async fn cancel_orders(&self, order_ids: &[String]) -> Result<()>;
+
+    /// Order book depth for a token
+    async fn get_order_book(&self, token_id: &str) -> Result<OrderBook>;
+
+    /// Our resting orders across all markets
+    async fn get_open_orders(&self) -> Result<Vec<Order>>;
+
+    /// Matched size and state of one of our orders
+    async fn get_order(&self, order_id: &str) -> Result<OrderStatus>;
+
+    /// Shares of a conditional token held by the wallet
+    async fn get_token_balance(&self, token_id: &str) -> Result<Decimal>;
+}
+
+#[async_trait]
+impl ClobApi for ClobClient {
+    async fn post_order(
+        &self,
+        args: &OrderArgs,
+        order_type: ClobOrderType,
+        expiration: Option<u64>,
+    ) -> Result<serde_json::Value> {
+        // polyfill-rs handles EIP-712 signing
+        let signed_order = self.create_order(args, expiration, None, None).await?;
+        Ok(ClobClient::post_order(self, signed_order, order_type).await?)
+    }
+
+    async fn cancel_orders(&self, order_ids: &[String]) -> Result<()> {
+        ClobClient::cancel_orders(self, order_ids).await?;
+        Ok(())
+    }
+
+    async fn get_order_book(&self, token_id: &str) -> Result<OrderBook> {
+        let book = ClobClient::get_order_book(self, token_id).await?;
+
+        let levels = |levels: Vec<OrderSummary>| -> Vec<OrderBookLevel> {
+            levels
+                .into_iter()
+                .map(|level| OrderBookLevel {
+                    price: level.price.to_string(),
+                    size: level.size.to_string(),
+                })
+                .collect()
+        };
+        Ok(OrderBook {
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            market: token_id.to_string(),
+            bids: levels(book.bids),
+            asks: levels(book.asks),
+            last_trade_price: None, // not in the summary, so TAKE_PROFIT_TRIGGER=LAST is paper-only
+        })
+    }
+
+    async fn get_open_orders(&self) -> Result<Vec<Order>> {
+        let orders = self.get_orders(None, None).await?;
+
+        Ok(orders
+            .into_iter()
+            .map(|order| Order {
+                id: order.id,
+                token_id: order.asset_id,
+                side: match order.side {
+                    ClobSide::BUY => OrderSide::BUY,
+                    ClobSide::SELL => OrderSide::SELL,
+                },
+                price: order.price,
+                size: order.original_size - order.size_matched,
+                order_type: OrderType::GTC,
+                timestamp: order.created_at as i64 * 1000,
+                exit_reason: None,
+                placed_tick: 0,
+            })
+            .collect())
+    }
+
+    async fn get_order(&self, order_id: &str) -> Result<OrderStatus> {
+        let order = ClobClient::get_order(self, order_id).await?;
+        Ok(OrderStatus {
+            original_size: order.original_size,
+            size_matched: order.size_matched,
+            open: order.status.eq_ignore_ascii_case("live"),
+        })
+    }
+
+    async fn get_token_balance(&self, token_id: &str) -> Result<Decimal> {
+        let params = BalanceAllowanceParams {
+            asset_type: Some(AssetType::CONDITIONAL),
+            token_id: Some(token_id.to_string()),
+            signature_type: None,
+        };
+        let response = self.get_balance_allowance(Some(params)).await?;
+
+        // Balances are reported in base units (6 decimals)
+        let raw = response
+            .get("balance")
+            .and_then(|v| v.as_str())
+            .context("No balance in CLOB response")?;
+        Ok(Decimal::from_str(raw)? / Decimal::from(1_000_000))
+    }
+}
+
+/// Passes every CLOB call through the shared rate limiter
+struct RateLimitedClob {
+    inner: Box<dyn ClobApi>,
+    rate_limiter: Arc<RateLimiter>,
+}
+
+#[async_trait]
+impl ClobApi for RateLimitedClob {
+    async fn post_order(
+        &self,
+        args: &OrderArgs,
+        order_type: ClobOrderType,
+        expiration: Option<u64>,
+    ) -> Result<serde_json::Value> {
+        self.rate_limiter.acquire().await;
+        self.inner.post_order(args, order_type, expiration).await
+    }
+
+    async fn cancel_orders(&self, order_ids: &[String]) -> Result<()> {
+        self.rate_limiter.acquire().await;
+        self.inner.cancel_orders(order_ids).await
+    }
+
+    async fn get_order_book(&self, token_id: &str) -> Result<OrderBook> {
+        self.rate_limiter.acquire().await;
+        self.inner.get_order_book(token_id).await
+    }
+
+    async fn get_open_orders(&self) -> Result<Vec<Order>> {
+        self.rate_limiter.acquire().await;
+        self.inner.get_open_orders().await
+    }
+
+    async fn get_order(&self, order_id: &str) -> Result<OrderStatus> {
+        self.rate_limiter.acquire().await;
+        self.inner.get_order(order_id).await
+    }
+
+    async fn get_token_balance(&self, token_id: &str) -> Result<Decimal> {
+        self.rate_limiter.acquire().await;
+        self.inner.get_token_balance(token_id).await
+    }
+}
 
 /// Trading service supporting both paper and live trading
 pub struct TradingService {
     config: BotConfig,
-    clob_client: Option<ClobClient>,
+    clob_client: Option<Box<dyn ClobApi>>,
 
     // Paper trading state
     paper_cash: Arc<RwLock<Decimal>>,
     paper_position: Arc<RwLock<Option<Position>>>,
     paper_orders: Arc<RwLock<HashMap<String, Order>>>,
     paper_order_counter: Arc<RwLock<u64>>,
+    paper_books: Arc<RwLock<HashMap<String, OrderBook>>>, // latest depth per token, for market fills
+    paper_tick: Arc<RwLock<u64>>, // fill checks so far, the clock for PAPER_FILL_LATENCY_TICKS
+
+    // Recent submissions by dedup key, with when they were sent (Unix ms)
+    recent_submissions: Arc<RwLock<HashMap<String, i64>>>,
+
+    // Live state recovered from the CLOB
+    live_position: Arc<RwLock<Option<Position>>>,
+    live_orders: Arc<RwLock<HashMap<String, Order>>>,
+
+    // Closed trades not yet consumed by the session logger
+    closed_trades: Arc<RwLock<Vec<ClosedTrade>>>,
+
+    // Limit orders placed vs filled since the last take
+    fill_stats: Arc<RwLock<FillStats>>,
+
+    // Paper fills not yet checked against the live book (VALIDATE_PAPER_FILLS)
+    paper_fills: Arc<RwLock<Vec<PaperFill>>>,
 }
 
 impl TradingService {
     /// Create a new trading service
-    pub fn new(config: BotConfig) -> Result<Self> {
+    pub fn new(config: BotConfig, rate_limiter: Arc<RateLimiter>) -> Result<Self> {
         let clob_client = if !config.paper_trade {
             // Initialize live CLOB client with L1 headers (signatures)
             // Uses optimized HTTP/2 connection for internet connectivity
-            let client = ClobClient::with_l1_headers(
-                "https://clob.polymarket.com",
+            let mut client = ClobClient::with_l1_headers(
+                &config.clob_base_url,
                 &config.signer_private_key,
                 137, // Polygon Mainnet chain ID
             );
 
-            // Note: In production, you'd want to derive API credentials:
-            // let api_creds = client.create_or_derive_api_key(None).await?;
-            // client.set_api_creds(api_creds);
+            // L2 credentials authenticate order posting and cancels
+            if !config.clob_api_key.is_empty() {
+                client.set_api_creds(ApiCredentials {
+                    api_key: config.clob_api_key.clone(),
+                    secret: config.clob_api_secret.clone(),
+                    passphrase: config.clob_api_passphrase.clone(),
+                });
+            }
 
-            Some(client)
+            Some(Box::new(client) as Box<dyn ClobApi>)
         } else {
             None
         };
 
+        Ok(Self::with_client(config, clob_client, rate_limiter))
+    }
+
+    /// Create a trading service around an existing CLOB client
+    pub fn with_client(
+        config: BotConfig,
+        clob_client: Option<Box<dyn ClobApi>>,
+        rate_limiter: Arc<RateLimiter>,
+    ) -> Self {
         info!("⚡ Trading Service initialized");
         info!(
             "💼 Mode: {}",
@@ -50,48 +317,191 @@ impl TradingService {
         );
 
         if config.paper_trade {
-            info!("💵 Paper Cash: $100.00");
+            info!("💵 Paper Cash: ${:.2}", config.paper_starting_cash);
         }
 
-        Ok(Self {
+        let clob_client = clob_client.map(|inner| {
+            Box::new(RateLimitedClob { inner, rate_limiter }) as Box<dyn ClobApi>
+        });
+
+        Self {
+            paper_cash: Arc::new(RwLock::new(config.paper_starting_cash)),
             config,
             clob_client,
-            paper_cash: Arc::new(RwLock::new(Decimal::from(100))),
             paper_position: Arc::new(RwLock::new(None)),
             paper_orders: Arc::new(RwLock::new(HashMap::new())),
             paper_order_counter: Arc::new(RwLock::new(0)),
-        })
+            paper_books: Arc::new(RwLock::new(HashMap::new())),
+            paper_tick: Arc::new(RwLock::new(0)),
+            recent_submissions: Arc::new(RwLock::new(HashMap::new())),
+            live_position: Arc::new(RwLock::new(None)),
+            live_orders: Arc::new(RwLock::new(HashMap::new())),
+            closed_trades: Arc::new(RwLock::new(Vec::new())),
+            fill_stats: Arc::new(RwLock::new(FillStats::default())),
+            paper_fills: Arc::new(RwLock::new(Vec::new())),
+        }
     }
 
     /// Place a BUY order
-    pub async fn buy(&self, token_id: &str, price: Decimal, size: Decimal) -> Result<String> {
-        self.place_limit_order(token_id, OrderSide::BUY, price, size)
+    pub async fn buy(&self, token_id: &str, price: Decimal, size: Decimal) -> TradingResult<String> {
+        self.place_limit_order(token_id, OrderSide::BUY, price, size, OrderType::GTC, None)
             .await
     }
 
     /// Place a SELL order
-    pub async fn sell(&self, token_id: &str, price: Decimal, size: Decimal) -> Result<String> {
-        self.place_limit_order(token_id, OrderSide::SELL, price, size)
-            .await
+    pub async fn sell(
+        &self,
+        token_id: &str,
+        price: Decimal,
+        size: Decimal,
+        exit_reason: ExitReason,
+    ) -> TradingResult<String> {
+        self.place_limit_order(
+            token_id,
+            OrderSide::SELL,
+            price,
+            size,
+            OrderType::GTC,
+            Some(exit_reason),
+        )
+        .await
     }
 
-    /// Place a limit order (GTC)
+    /// Place a limit order with the given time-in-force
     async fn place_limit_order(
         &self,
         token_id: &str,
         side: OrderSide,
         price: Decimal,
         size: Decimal,
-    ) -> Result<String> {
-        if self.config.paper_trade {
-            self.place_paper_order(token_id, side, price, size).await
+        order_type: OrderType,
+        exit_reason: Option<ExitReason>,
+    ) -> TradingResult<String> {
+        let max_open = self.config.max_open_orders;
+        if max_open > 0 && self.open_order_ids().await.len() as u64 >= max_open {
+            // Live, some slots may be held by orders that left the book unnoticed
+            if !self.config.paper_trade {
+                self.prune_live_orders().await?;
+            }
+            if self.open_order_ids().await.len() as u64 >= max_open {
+                return Err(TradingError::OrderLimit(max_open));
+            }
+        }
+
+        let price = self.round_to_tick(side, price);
+        let size = self.reduce_only(token_id, side, size).await?;
+        let key = self.claim_submission(token_id, side, price).await?;
+        let result = if self.config.paper_trade {
+            self.place_paper_order(token_id, side, price, size, order_type, exit_reason)
+                .await
         } else {
-            self.place_live_order(token_id, side, price, size).await
+            // Only once the guards above pass, so a refused order never cancels anything
+            match self.prevent_self_trade(token_id, side, price, exit_reason).await {
+                Ok(()) => {
+                    self.place_live_order(token_id, side, price, size, order_type, exit_reason)
+                        .await
+                }
+                Err(e) => Err(e),
+            }
+        };
+        self.settle_submission(key, &result).await;
+        result
+    }
+
+    /// Snap an order price onto the `PRICE_TICK_SIZE` grid
+    fn round_to_tick(&self, side: OrderSide, price: Decimal) -> Decimal {
+        let rounded = QuantEngine::round_to_tick(price, self.config.price_tick_size, side);
+        if rounded != price {
+            debug!("Rounded {:?} price {} to {} on the {} tick", side, price, rounded, self.config.price_tick_size);
+        }
+        rounded
+    }
+
+    /// Cap a sell at the shares held in `token_id`, so an exit can never go short
+    ///
+    /// Shares already committed to resting sells on the token don't count as
+    /// held. Nothing left to sell is a `NoPosition` error.
+    async fn reduce_only(&self, token_id: &str, side: OrderSide, size: Decimal) -> TradingResult<Decimal> {
+        if side != OrderSide::SELL {
+            return Ok(size);
+        }
+        let held = match self.get_position().await {
+            Some(pos) if pos.token_id == token_id => pos.shares,
+            _ => Decimal::ZERO,
+        };
+        let resting = self.resting_sell_size(token_id).await;
+        let available = (held - resting).max(Decimal::ZERO);
+        if available.is_zero() {
+            return Err(TradingError::NoPosition(token_id.to_string()));
+        }
+        if size > available {
+            warn!(
+                "⚠️ SELL of {} exceeds the {} shares held ({} already resting) - reduced to {}",
+                size, held, resting, available
+            );
+            return Ok(available);
+        }
+        Ok(size)
+    }
+
+    /// Shares of `token_id` already offered by open sell orders
+    async fn resting_sell_size(&self, token_id: &str) -> Decimal {
+        let orders = if self.config.paper_trade { &self.paper_orders } else { &self.live_orders };
+        orders
+            .read()
+            .await
+            .values()
+            .filter(|order| order.side == OrderSide::SELL && order.token_id == token_id)
+            .map(|order| order.size)
+            .sum()
+    }
+
+    /// Record a submission, refusing it if an identical one went out within `ORDER_DEDUP_WINDOW_MS`
+    ///
+    /// Guards against an overlapping tick or a retry firing the same signal
+    /// twice. Returns the dedup key, or None when the guard is disabled.
+    async fn claim_submission(
+        &self,
+        token_id: &str,
+        side: OrderSide,
+        price: Decimal,
+    ) -> TradingResult<Option<String>> {
+        let window = self.config.order_dedup_window_ms as i64;
+        if window == 0 {
+            return Ok(None);
+        }
+
+        let now = chrono::Utc::now().timestamp_millis();
+        // The price is already on the tick grid; normalize so 0.45 and 0.450 match
+        let key = format!("{}:{:?}:{}", token_id, side, price.normalize());
+        let mut recent = self.recent_submissions.write().await;
+        recent.retain(|_, sent_at| now - *sent_at < window);
+        if recent.contains_key(&key) {
+            warn!("⚠️ Duplicate {:?} @ {:.4} within {}ms - rejected", side, price, window);
+            return Err(TradingError::Duplicate(key));
+        }
+        recent.insert(key.clone(), now);
+        Ok(Some(key))
+    }
+
+    /// Release the dedup key of a submission that was refused before reaching the book
+    ///
+    /// A network failure keeps the key: the order may have gone through.
+    async fn settle_submission<T>(&self, key: Option<String>, result: &TradingResult<T>) {
+        if matches!(result, Err(err) if !err.is_transient()) {
+            self.release_submission(key).await;
+        }
+    }
+
+    /// Free a dedup key so an identical submission can go out at once
+    async fn release_submission(&self, key: Option<String>) {
+        if let Some(key) = key {
+            self.recent_submissions.write().await.remove(&key);
         }
     }
 
     /// Cancel an order
-    pub async fn cancel_order(&self, order_id: &str) -> Result<()> {
+    pub async fn cancel_order(&self, order_id: &str) -> TradingResult<()> {
         if self.config.paper_trade {
             self.cancel_paper_order(order_id).await
         } else {
@@ -99,6 +509,25 @@ impl TradingService {
         }
     }
 
+    /// Cancel the resting sells on `token_id`, freeing their shares for another exit
+    pub async fn cancel_resting_sells(&self, token_id: &str) -> TradingResult<()> {
+        let orders = if self.config.paper_trade { &self.paper_orders } else { &self.live_orders };
+        let order_ids: Vec<String> = orders
+            .read()
+            .await
+            .values()
+            .filter(|order| order.side == OrderSide::SELL && order.token_id == token_id)
+            .map(|order| order.id.clone())
+            .collect();
+        for order_id in order_ids {
+            self.cancel_order(&order_id).await?;
+        }
+        Ok(())
+    }
+
+    /// Order type used for immediate market orders
+    pub const MARKET_ORDER_TYPE: OrderType = OrderType::IOC;
+
     /// Execute immediate market order
     pub async fn execute_market_order(
         &self,
@@ -106,17 +535,48 @@ impl TradingService {
         side: OrderSide,
         price: Decimal,
         size: Decimal,
-    ) -> Result<bool> {
-        if self.config.paper_trade {
-            self.execute_paper_fak(token_id, side, price, size).await
+        exit_reason: Option<ExitReason>,
+    ) -> TradingResult<bool> {
+        let price = self.round_to_tick(side, price);
+        let size = self.reduce_only(token_id, side, size).await?;
+        let key = self.claim_submission(token_id, side, price).await?;
+        let result = if self.config.paper_trade {
+            self.execute_paper_fak(token_id, side, price, size, exit_reason).await
+        } else {
+            match self.prevent_self_trade(token_id, side, price, exit_reason).await {
+                Ok(()) => self.execute_live_fak(token_id, side, price, size, exit_reason).await,
+                Err(e) => Err(e),
+            }
+        };
+        // Nothing filled means nothing traded, so the same order may go straight out again
+        if matches!(result, Ok(false)) {
+            self.release_submission(key).await;
         } else {
-            self.execute_live_fak(token_id, side, price, size).await
+            self.settle_submission(key, &result).await;
         }
+        result
     }
 
     /// Get current position
     pub async fn get_position(&self) -> Option<Position> {
-        self.paper_position.read().await.clone()
+        if self.config.paper_trade {
+            self.paper_position.read().await.clone()
+        } else {
+            self.live_position.read().await.clone()
+        }
+    }
+
+    /// Overwrite the tracked share count with the balance actually held
+    ///
+    /// A zero balance drops the position.
+    pub async fn correct_position_shares(&self, shares: Decimal) {
+        let slot = if self.config.paper_trade { &self.paper_position } else { &self.live_position };
+        let mut position = slot.write().await;
+        if shares <= Decimal::ZERO {
+            *position = None;
+        } else if let Some(pos) = position.as_mut() {
+            pos.shares = shares;
+        }
     }
 
     /// Get cash balance
@@ -124,54 +584,120 @@ impl TradingService {
         *self.paper_cash.read().await
     }
 
+    /// Paper cash committed to resting buy orders
+    pub async fn reserved_cash(&self) -> Decimal {
+        Self::reserved_by(&*self.paper_orders.read().await)
+    }
+
+    fn reserved_by(orders: &HashMap<String, Order>) -> Decimal {
+        orders
+            .values()
+            .filter(|order| order.side == OrderSide::BUY)
+            .map(|order| order.price * order.size)
+            .sum()
+    }
+
     /// Check if we have a position
     pub async fn has_position(&self) -> bool {
-        self.paper_position.read().await.is_some()
+        self.get_position().await.is_some()
+    }
+
+    /// IDs of our open orders
+    pub async fn open_order_ids(&self) -> Vec<String> {
+        if self.config.paper_trade {
+            self.paper_orders.read().await.keys().cloned().collect()
+        } else {
+            self.live_orders.read().await.keys().cloned().collect()
+        }
+    }
+
+    /// Store the latest book depth for a token, used to simulate paper market fills
+    pub async fn update_paper_book(&self, token_id: &str, book: OrderBook) {
+        self.paper_books.write().await.insert(token_id.to_string(), book);
+    }
+
+    /// Take closed trades recorded since the last call
+    pub async fn take_closed_trades(&self) -> Vec<ClosedTrade> {
+        std::mem::take(&mut *self.closed_trades.write().await)
+    }
+
+    /// Take limit order fill statistics recorded since the last call
+    pub async fn take_fill_stats(&self) -> FillStats {
+        std::mem::take(&mut *self.fill_stats.write().await)
+    }
+
+    /// Take the paper fills simulated since the last call (VALIDATE_PAPER_FILLS)
+    pub async fn take_paper_fills(&self) -> Vec<PaperFill> {
+        std::mem::take(&mut *self.paper_fills.write().await)
+    }
+
+    /// Keep a paper fill for checking against the live book
+    async fn record_paper_fill(&self, token_id: &str, side: OrderSide, price: Decimal, size: Decimal) {
+        if self.config.validate_paper_fills {
+            self.paper_fills.write().await.push(PaperFill {
+                token_id: token_id.to_string(),
+                side,
+                price,
+                size,
+            });
+        }
     }
 
     /// Check paper fills based on current market prices
+    ///
+    /// Called once per tick; orders become fillable `PAPER_FILL_LATENCY_TICKS`
+    /// checks after the one they were placed before.
     pub async fn check_paper_fills(
         &self,
         token_id: &str,
         best_ask: Decimal,
         best_bid: Decimal,
     ) -> Option<Position> {
-        let mut orders = self.paper_orders.write().await;
-        let mut filled_order_id: Option<String> = None;
-
-        for (order_id, order) in orders.iter() {
-            if order.token_id != token_id {
-                continue;
-            }
+        let tick = {
+            let mut tick = self.paper_tick.write().await;
+            *tick += 1;
+            *tick
+        };
 
-            let mut filled = false;
+        let mut orders = self.paper_orders.write().await;
+        let filled_order_id = orders
+            .values()
+            .find(|order| {
+                order.token_id == token_id
+                    && tick > order.placed_tick + self.config.paper_fill_latency_ticks
+                    && Self::is_marketable(order, best_bid, best_ask)
+            })
+            .map(|order| order.id.clone())?;
 
-            if order.side == OrderSide::BUY && best_ask <= order.price {
-                // Buy order filled - market came down to our price
-                filled = true;
-                let cost = order.price * order.size;
-                let mut cash = self.paper_cash.write().await;
-                *cash -= cost;
+        // Market reached our price
+        let order = orders.remove(&filled_order_id)?;
+        self.apply_paper_fill(&order).await;
+        self.paper_position.read().await.clone()
+    }
 
-                let position = Position {
-                    token_id: order.token_id.clone(),
-                    shares: order.size,
-                    entry_price: order.price,
-                    entry_time: chrono::Utc::now().timestamp_millis(),
-                };
+    /// Whether a resting limit order would trade against the given top of book
+    fn is_marketable(order: &Order, best_bid: Decimal, best_ask: Decimal) -> bool {
+        match order.side {
+            OrderSide::BUY => best_ask <= order.price,
+            OrderSide::SELL => best_bid >= order.price,
+        }
+    }
 
-                *self.paper_position.write().await = Some(position.clone());
+    /// Apply a filled paper limit order to cash and position
+    async fn apply_paper_fill(&self, order: &Order) {
+        let mut cash = self.paper_cash.write().await;
+        match order.side {
+            OrderSide::BUY => {
+                *cash -= order.price * order.size;
+                self.apply_paper_buy(&order.token_id, order.price, order.size, order.timestamp).await;
 
                 info!(
                     "[PAPER] 🔔 BUY ORDER FILLED @ {:.4}. Cash: ${:.2}",
                     order.price, *cash
                 );
-            } else if order.side == OrderSide::SELL && best_bid >= order.price {
-                // Sell order filled - market came up to our price
-                filled = true;
-                let proceeds = order.price * order.size;
-                let mut cash = self.paper_cash.write().await;
-                *cash += proceeds;
+            }
+            OrderSide::SELL => {
+                *cash += order.price * order.size;
 
                 if let Some(pos) = self.paper_position.read().await.as_ref() {
                     let pnl = pos.calculate_pnl(order.price);
@@ -179,23 +705,53 @@ impl TradingService {
                         "[PAPER] 🔔 SELL ORDER FILLED @ {:.4}. P&L: ${:.2}. Cash: ${:.2}",
                         order.price, pnl, *cash
                     );
-                }
 
-                *self.paper_position.write().await = None;
-            }
+                    let reason = order.exit_reason.unwrap_or(ExitReason::Manual);
+                    self.closed_trades.write().await.push(ClosedTrade::from_position(
+                        pos,
+                        order.price,
+                        order.size,
+                        reason,
+                    ));
+                }
 
-            if filled {
-                filled_order_id = Some(order_id.clone());
-                break;
+                self.apply_paper_sell(order.size).await;
             }
         }
+        self.fill_stats.write().await.record_filled(false);
+        self.record_paper_fill(&order.token_id, order.side, order.price, order.size).await;
+    }
 
-        if let Some(id) = filled_order_id {
-            orders.remove(&id);
-            return self.paper_position.read().await.clone();
-        }
+    /// Place an aggressive limit order, give it `SNIPE_WAIT_TIME` to fill, then cancel
+    ///
+    /// Returns whether the order filled. Paper orders are filled against the
+    /// cached book for the token once the wait is over.
+    pub async fn snipe(
+        &self,
+        token_id: &str,
+        side: OrderSide,
+        price: Decimal,
+        size: Decimal,
+        exit_reason: Option<ExitReason>,
+    ) -> TradingResult<bool> {
+        let order_id = self
+            .place_limit_order(token_id, side, price, size, OrderType::GTC, exit_reason)
+            .await?;
+
+        tokio::time::sleep(Duration::from_millis(self.config.snipe_wait_time)).await;
 
-        None
+        let filled = if self.config.paper_trade {
+            self.settle_paper_snipe(&order_id).await
+        } else {
+            self.settle_live_snipe(&order_id).await?
+        };
+
+        // Whatever is still resting, including the rest of a partial fill, is pulled
+        if self.open_order_ids().await.contains(&order_id) {
+            info!("⌛ {:?} snipe @ {:.4} not filled after {}ms - cancelling", side, price, self.config.snipe_wait_time);
+            self.cancel_order(&order_id).await?;
+        }
+        Ok(filled)
     }
 
     // ==========================================
@@ -208,7 +764,19 @@ impl TradingService {
         side: OrderSide,
         price: Decimal,
         size: Decimal,
-    ) -> Result<String> {
+        order_type: OrderType,
+        exit_reason: Option<ExitReason>,
+    ) -> TradingResult<String> {
+        // Hold the order book lock so concurrent buys can't both claim the same cash
+        let mut orders = self.paper_orders.write().await;
+        if side == OrderSide::BUY {
+            let cost = price * size;
+            let available = *self.paper_cash.read().await - Self::reserved_by(&orders);
+            if cost > available {
+                return Err(TradingError::InsufficientFunds { needed: cost, available });
+            }
+        }
+
         let mut counter = self.paper_order_counter.write().await;
         let order_id = format!("PAPER_{}", *counter);
         *counter += 1;
@@ -219,10 +787,14 @@ impl TradingService {
             side,
             price,
             size,
+            order_type,
             timestamp: chrono::Utc::now().timestamp_millis(),
+            exit_reason,
+            placed_tick: *self.paper_tick.read().await,
         };
 
-        self.paper_orders.write().await.insert(order_id.clone(), order);
+        orders.insert(order_id.clone(), order);
+        self.fill_stats.write().await.record_placed(false);
 
         info!(
             "[PAPER] 📝 {:?} LIMIT @ {:.4} | Token: {}... | Size: {}",
@@ -235,14 +807,45 @@ impl TradingService {
         Ok(order_id)
     }
 
-    async fn cancel_paper_order(&self, order_id: &str) -> Result<()> {
+    /// Fill a paper snipe if the cached book crosses it
+    async fn settle_paper_snipe(&self, order_id: &str) -> bool {
+        let mut orders = self.paper_orders.write().await;
+        let Some(order) = orders.get(order_id) else {
+            return true; // already filled by check_paper_fills
+        };
+
+        let top = self
+            .paper_books
+            .read()
+            .await
+            .get(&order.token_id)
+            .map(|book| (book.best_bid(), book.best_ask()));
+        let marketable = match top {
+            Some((bid, ask)) => Self::is_marketable(
+                order,
+                bid.unwrap_or(Decimal::ZERO),
+                ask.unwrap_or(Decimal::ONE),
+            ),
+            None => false,
+        };
+        if !marketable {
+            return false;
+        }
+
+        if let Some(order) = orders.remove(order_id) {
+            self.apply_paper_fill(&order).await;
+        }
+        true
+    }
+
+    async fn cancel_paper_order(&self, order_id: &str) -> TradingResult<()> {
         let mut orders = self.paper_orders.write().await;
         if orders.remove(order_id).is_some() {
             info!("[PAPER] 🗑️ Cancelled Order {}", order_id);
             Ok(())
         } else {
             warn!("[PAPER] ⚠️ Order {} not found", order_id);
-            anyhow::bail!("Order not found")
+            Err(TradingError::OrderNotFound(order_id.to_string()))
         }
     }
 
@@ -252,7 +855,8 @@ impl TradingService {
         side: OrderSide,
         price: Decimal,
         size: Decimal,
-    ) -> Result<bool> {
+        exit_reason: Option<ExitReason>,
+    ) -> TradingResult<bool> {
         info!(
             "[PAPER] 💥 MARKET ORDER: {:?} @ {:.4} | Token: {}... | Size: {}",
             side,
@@ -260,23 +864,41 @@ impl TradingService {
             &token_id[..8.min(token_id.len())],
             size
         );
+        self.fill_stats.write().await.record_placed(true);
+
+        // Walk the cached book for a realistic fill; without one, fill at `price`
+        let limit = price;
+        let (price, size) = match self.paper_books.read().await.get(token_id) {
+            Some(book) => match book.walk(side, size) {
+                Some(fill) => {
+                    if fill.filled < size {
+                        warn!(
+                            "[PAPER] ⚠️ Book depth only covers {} of {} shares - partial fill",
+                            fill.filled, size
+                        );
+                    }
+                    (fill.avg_price, fill.filled)
+                }
+                None => {
+                    error!("[PAPER] ❌ No depth in book - market order rejected");
+                    return Ok(false);
+                }
+            },
+            None => (price, size),
+        };
 
         match side {
             OrderSide::BUY => {
                 let cost = price * size;
+                let reserved = self.reserved_cash().await;
                 let mut cash = self.paper_cash.write().await;
 
-                if *cash >= cost {
+                if *cash - reserved >= cost {
                     *cash -= cost;
 
-                    let position = Position {
-                        token_id: token_id.to_string(),
-                        shares: size,
-                        entry_price: price,
-                        entry_time: chrono::Utc::now().timestamp_millis(),
-                    };
-
-                    *self.paper_position.write().await = Some(position);
+                    self.apply_paper_buy(token_id, price, size, chrono::Utc::now().timestamp_millis()).await;
+                    self.record_paper_fill(token_id, side, limit, size).await;
+                    self.fill_stats.write().await.record_filled(true);
 
                     info!(
                         "[PAPER] ✅ BOUGHT {} shares @ {:.4}. Cash: ${:.2}",
@@ -284,41 +906,68 @@ impl TradingService {
                     );
                     Ok(true)
                 } else {
-                    error!(
-                        "[PAPER] ❌ Insufficient cash. Need ${:.2}, have ${:.2}",
-                        cost, *cash
-                    );
-                    Ok(false)
+                    Err(TradingError::InsufficientFunds { needed: cost, available: *cash - reserved })
                 }
             }
             OrderSide::SELL => {
                 let position_guard = self.paper_position.read().await;
-                if let Some(pos) = position_guard.as_ref() {
-                    if pos.shares >= size && pos.token_id == token_id {
-                        let proceeds = price * size;
-                        let entry_price = pos.entry_price;
-                        drop(position_guard); // Release read lock
+                let Some(pos) = position_guard
+                    .as_ref()
+                    .filter(|pos| pos.shares >= size && pos.token_id == token_id)
+                else {
+                    return Err(TradingError::NoPosition(token_id.to_string()));
+                };
 
-                        let pnl = (price - entry_price) * size;
+                let proceeds = price * size;
+                let entry_price = pos.entry_price;
+                let trade = ClosedTrade::from_position(
+                    pos,
+                    price,
+                    size,
+                    exit_reason.unwrap_or(ExitReason::Manual),
+                );
+                drop(position_guard); // Release read lock
 
-                        let mut cash = self.paper_cash.write().await;
-                        *cash += proceeds;
+                let pnl = (price - entry_price) * size;
 
-                        info!(
-                            "[PAPER] ✅ SOLD {} shares @ {:.4}. P&L: ${:.2}. Cash: ${:.2}",
-                            size, price, pnl, *cash
-                        );
+                let mut cash = self.paper_cash.write().await;
+                *cash += proceeds;
 
-                        *self.paper_position.write().await = None;
-                        Ok(true)
-                    } else {
-                        error!("[PAPER] ❌ No position to sell or wrong token");
-                        Ok(false)
-                    }
-                } else {
-                    error!("[PAPER] ❌ No position to sell");
-                    Ok(false)
-                }
+                info!(
+                    "[PAPER] ✅ SOLD {} shares @ {:.4}. P&L: ${:.2}. Cash: ${:.2}",
+                    size, price, pnl, *cash
+                );
+
+                self.apply_paper_sell(size).await;
+                self.closed_trades.write().await.push(trade);
+                self.record_paper_fill(token_id, side, limit, size).await;
+                self.fill_stats.write().await.record_filled(true);
+                Ok(true)
+            }
+        }
+    }
+
+    /// Add a buy fill to the paper position, averaging into an existing one
+    async fn apply_paper_buy(&self, token_id: &str, price: Decimal, size: Decimal, placed_at: i64) {
+        let mut position = self.paper_position.write().await;
+        match position.as_mut() {
+            Some(pos) if pos.token_id == token_id => pos.add_fill(price, size),
+            _ => {
+                let mut pos = Position::new(token_id);
+                pos.add_fill(price, size);
+                pos.committed_since(placed_at);
+                *position = Some(pos);
+            }
+        }
+    }
+
+    /// Remove sold shares from the paper position, clearing it once flat
+    async fn apply_paper_sell(&self, size: Decimal) {
+        let mut position = self.paper_position.write().await;
+        if let Some(pos) = position.as_mut() {
+            pos.reduce(size);
+            if pos.is_flat() {
+                *position = None;
             }
         }
     }
@@ -327,63 +976,530 @@ impl TradingService {
     // LIVE TRADING METHODS (using polyfill-rs)
     // ==========================================
 
+    /// Map our order type onto the CLOB time-in-force flag.
+    /// Polymarket calls immediate-or-cancel "fill-and-kill" (FAK).
+    pub fn clob_order_type(order_type: OrderType) -> ClobOrderType {
+        match order_type {
+            OrderType::GTC => ClobOrderType::GTC,
+            OrderType::FOK => ClobOrderType::FOK,
+            OrderType::IOC => ClobOrderType::FAK,
+        }
+    }
+
+    /// Lead Polymarket requires on a GTD expiration, in seconds
+    const GTD_SECURITY_THRESHOLD: i64 = 60;
+
+    /// CLOB order type and expiration for an order posted at `now` (Unix seconds)
+    ///
+    /// With `ORDER_TTL_SECONDS` resting orders go out as GTD, so anything a
+    /// crash leaves on the book expires by itself. Immediate orders never
+    /// rest and are posted without an expiry.
+    fn time_in_force(&self, order_type: OrderType, now: i64) -> (ClobOrderType, Option<u64>) {
+        let ttl = self.config.order_ttl_seconds;
+        if order_type != OrderType::GTC || ttl == 0 {
+            return (Self::clob_order_type(order_type), None);
+        }
+        // The CLOB ignores the first minute of an expiration, so the TTL runs on top of it
+        let expiration = (now + Self::GTD_SECURITY_THRESHOLD) as u64 + ttl;
+        (ClobOrderType::GTD, Some(expiration))
+    }
+
     async fn place_live_order(
         &self,
         token_id: &str,
         side: OrderSide,
         price: Decimal,
         size: Decimal,
-    ) -> Result<String> {
-        info!(
-            "[LIVE] 💸 {:?} LIMIT @ {:.4} | Token: {}...",
-            side,
-            price,
-            &token_id[..8.min(token_id.len())]
-        );
-
-        let client = self.clob_client.as_ref()
-            .context("CLOB client not initialized")?;
+        order_type: OrderType,
+        exit_reason: Option<ExitReason>,
+    ) -> TradingResult<String> {
+        let (order_id, filled) = self
+            .submit_live_order(token_id, side, price, size, order_type, exit_reason)
+            .await?;
 
-        // Convert side to polyfill-rs Side
-        let clob_side = match side {
-            OrderSide::BUY => ClobSide::BUY,
-            OrderSide::SELL => ClobSide::SELL,
+        // Whatever the balance didn't confirm may still rest or fill later, so
+        // it stays tracked for the user feed or sync_live_state to settle
+        let unconfirmed = match filled {
+            Some(filled) if order_type == OrderType::GTC => size - filled,
+            Some(_) => Decimal::ZERO, // FOK/IOC: the rest was cancelled by the exchange
+            None => size,
         };
-
-        // Create order using polyfill-rs OrderArgs
-        let order_args = OrderArgs::new(
-            token_id,
+        if unconfirmed > Decimal::ZERO {
+            if filled.is_some() {
+                warn!(
+                    "[LIVE] ⚠️ Tracking {} unconfirmed shares of order {} until the exchange settles them",
+                    unconfirmed, order_id
+                );
+            }
+            self.live_orders.write().await.insert(
+                order_id.clone(),
+                Order {
+                    id: order_id.clone(),
+                    token_id: token_id.to_string(),
+                    side,
+                    price,
+                    size: unconfirmed,
+                    order_type,
+                    timestamp: chrono::Utc::now().timestamp_millis(),
+                    exit_reason,
+                    placed_tick: 0,
+                },
+            );
+        }
+        Ok(order_id)
+    }
+
+    /// Apply `PREVENT_SELF_TRADE` to our resting orders the new order would cross
+    ///
+    /// Only opposing orders on the same token at a crossing price count, so a
+    /// market maker's bid and ask resting either side of fair don't trip it.
+    async fn prevent_self_trade(
+        &self,
+        token_id: &str,
+        side: OrderSide,
+        price: Decimal,
+        exit_reason: Option<ExitReason>,
+    ) -> TradingResult<()> {
+        let mode = self.config.prevent_self_trade;
+        if mode == SelfTradePrevention::Off {
+            return Ok(());
+        }
+
+        let now = chrono::Utc::now().timestamp_millis();
+        let crossing: Vec<String> = self
+            .live_orders
+            .read()
+            .await
+            .values()
+            .filter(|resting| resting.token_id == token_id && resting.side != side)
+            .filter(|resting| !self.order_expired(resting, now))
+            .filter(|resting| match side {
+                OrderSide::BUY => price >= resting.price,
+                OrderSide::SELL => price <= resting.price,
+            })
+            .map(|resting| resting.id.clone())
+            .collect();
+        let Some(first) = crossing.first() else {
+            return Ok(());
+        };
+
+        // An exit has to go out whatever the mode, so the resting order gives way
+        if mode == SelfTradePrevention::Reject && exit_reason.is_none() {
+            warn!("[LIVE] 🪞 {:?} @ {:.4} would cross our order {} - rejected", side, price, first);
+            return Err(TradingError::SelfTrade(first.clone()));
+        }
+        for order_id in &crossing {
+            warn!("[LIVE] 🪞 {:?} @ {:.4} would cross our order {} - cancelling it first", side, price, order_id);
+            self.cancel_live_order(order_id).await?;
+        }
+        Ok(())
+    }
+
+    /// Whether a tracked live order's `ORDER_TTL_SECONDS` expiry has passed at `now` (Unix millis)
+    ///
+    /// The exchange has dropped it already, so it can't be crossed.
+    fn order_expired(&self, order: &Order, now: i64) -> bool {
+        let (_, expiration) = self.time_in_force(order.order_type, order.timestamp / 1000);
+        expiration.is_some_and(|expiration| now / 1000 >= expiration as i64)
+    }
+
+    /// Post a live order and, if the CLOB reports it filled, apply the fill
+    ///
+    /// Returns the order ID and the filled shares when the order is believed
+    /// filled (market orders, or a limit order matched on arrival). With
+    /// `VERIFY_LIVE_FILLS` the fill is taken from the token balance rather
+    /// than assumed, so `Some(0)` means the exchange shows no fill.
+    async fn submit_live_order(
+        &self,
+        token_id: &str,
+        side: OrderSide,
+        price: Decimal,
+        size: Decimal,
+        order_type: OrderType,
+        exit_reason: Option<ExitReason>,
+    ) -> TradingResult<(String, Option<Decimal>)> {
+        info!(
+            "[LIVE] 💸 {:?} {:?} @ {:.4} | Token: {}...",
+            side,
+            order_type,
+            price,
+            &token_id[..8.min(token_id.len())]
+        );
+
+        let client = self.clob_client.as_ref()
+            .ok_or(TradingError::ClientUnavailable)?;
+
+        // Convert side to polyfill-rs Side
+        let clob_side = match side {
+            OrderSide::BUY => ClobSide::BUY,
+            OrderSide::SELL => ClobSide::SELL,
+        };
+
+        // Create order using polyfill-rs OrderArgs
+        let order_args = OrderArgs::new(
+            token_id,
             price,
             size,
             clob_side,
         );
 
-        // Submit order - polyfill-rs handles EIP-712 signing automatically
-        let result = client.create_and_post_order(&order_args).await?;
+        // Snapshot the balance so the fill can be checked against the exchange
+        let balance_before = if self.config.verify_live_fills {
+            Some(client.get_token_balance(token_id).await?)
+        } else {
+            None
+        };
+
+        // Post with the requested time-in-force
+        let (clob_order_type, expiration) = self.time_in_force(order_type, chrono::Utc::now().timestamp());
+        let response = client
+            .post_order(&order_args, clob_order_type, expiration)
+            .await?;
+        let order_id = Self::parse_order_id(&response)?;
 
-        info!("[LIVE] ✅ Order placed");
-        Ok("live_order_id".to_string())
+        info!("[LIVE] ✅ Order placed: {}", order_id);
+        let market = order_type != OrderType::GTC;
+        self.fill_stats.write().await.record_placed(market);
+
+        let matched = response.get("status").and_then(|v| v.as_str()) == Some("matched");
+        if !market && !matched {
+            return Ok((order_id, None));
+        }
+
+        let filled = match balance_before {
+            Some(before) => self.verify_live_fill(token_id, side, size, before).await?,
+            None => size,
+        };
+        // An immediate order is assumed filled, but only a match or the balance confirms it
+        if filled > Decimal::ZERO && (matched || balance_before.is_some()) {
+            self.fill_stats.write().await.record_filled(market);
+        }
+
+        if filled.is_zero() {
+            warn!("[LIVE] ⚠️ Order {} not reflected in token balance - fill not confirmed", order_id);
+        } else {
+            if filled < size {
+                warn!("[LIVE] ⚠️ Order {} confirmed for {} of {} shares", order_id, filled, size);
+            }
+            let placed_at = chrono::Utc::now().timestamp_millis();
+            self.apply_live_fill(token_id, side, price, filled, exit_reason, placed_at).await;
+        }
+
+        Ok((order_id, Some(filled)))
+    }
+
+    /// Poll the token balance until it reflects a fill; returns the shares filled
+    async fn verify_live_fill(
+        &self,
+        token_id: &str,
+        side: OrderSide,
+        size: Decimal,
+        balance_before: Decimal,
+    ) -> TradingResult<Decimal> {
+        let client = self.clob_client.as_ref()
+            .ok_or(TradingError::ClientUnavailable)?;
+        let attempts = self.config.fill_verify_attempts.max(1);
+
+        let mut filled = Decimal::ZERO;
+        for attempt in 1..=attempts {
+            let balance = client.get_token_balance(token_id).await?;
+            filled = match side {
+                OrderSide::BUY => balance - balance_before,
+                OrderSide::SELL => balance_before - balance,
+            }
+            .max(Decimal::ZERO)
+            .min(size);
+
+            if filled >= size {
+                break;
+            }
+            if attempt < attempts {
+                tokio::time::sleep(Duration::from_millis(self.config.fill_verify_interval)).await;
+            }
+        }
+
+        Ok(filled)
+    }
+
+    /// Update the live position for a confirmed fill
+    async fn apply_live_fill(
+        &self,
+        token_id: &str,
+        side: OrderSide,
+        price: Decimal,
+        filled: Decimal,
+        exit_reason: Option<ExitReason>,
+        placed_at: i64,
+    ) {
+        let mut position = self.live_position.write().await;
+        match side {
+            OrderSide::BUY => match position.as_mut() {
+                Some(pos) if pos.token_id == token_id => pos.add_fill(price, filled),
+                _ => {
+                    let mut pos = Position::new(token_id);
+                    pos.add_fill(price, filled);
+                    pos.committed_since(placed_at);
+                    *position = Some(pos);
+                }
+            },
+            OrderSide::SELL => {
+                if let Some(pos) = position.as_mut().filter(|pos| pos.token_id == token_id) {
+                    self.closed_trades.write().await.push(ClosedTrade::from_position(
+                        pos,
+                        price,
+                        filled,
+                        exit_reason.unwrap_or(ExitReason::Manual),
+                    ));
+                    pos.reduce(filled);
+                    if pos.is_flat() {
+                        *position = None;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Extract the order ID from a CLOB order response
+    fn parse_order_id(response: &serde_json::Value) -> TradingResult<String> {
+        if response.get("success").and_then(|v| v.as_bool()) == Some(false) {
+            let message = response
+                .get("errorMsg")
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown error");
+            return Err(TradingError::Rejected(message.to_string()));
+        }
+
+        response
+            .get("orderID")
+            .or_else(|| response.get("orderId"))
+            .and_then(|v| v.as_str())
+            .filter(|id| !id.is_empty())
+            .map(str::to_string)
+            .ok_or_else(|| TradingError::Rejected(format!("no order ID in CLOB response: {}", response)))
     }
 
-    async fn cancel_live_order(&self, order_id: &str) -> Result<()> {
+    async fn cancel_live_order(&self, order_id: &str) -> TradingResult<()> {
         info!("[LIVE] 📡 Cancelling order {}", order_id);
 
         let client = self.clob_client.as_ref()
-            .context("CLOB client not initialized")?;
+            .ok_or(TradingError::ClientUnavailable)?;
 
         client.cancel_orders(&[order_id.to_string()]).await?;
+        self.live_orders.write().await.remove(order_id);
 
         info!("[LIVE] ✅ Order cancelled");
         Ok(())
     }
 
+    /// Book a live snipe from the CLOB's record of the order
+    ///
+    /// Only the size the exchange reports matched is applied, less whatever
+    /// user channel fills have applied already, so a cancelled or expired
+    /// order with nothing matched books nothing. Returns whether any of the
+    /// order filled; an order still resting stays tracked for `snipe` to cancel.
+    async fn settle_live_snipe(&self, order_id: &str) -> TradingResult<bool> {
+        let Some(order) = self.live_orders.read().await.get(order_id).cloned() else {
+            return Ok(true); // matched on arrival, or filled over the user channel
+        };
+
+        let client = self.clob_client.as_ref()
+            .ok_or(TradingError::ClientUnavailable)?;
+        let status = client.get_order(order_id).await?;
+
+        // User channel fills shrink the tracked size as they are applied
+        let applied = (status.original_size - order.size).max(Decimal::ZERO);
+        let unapplied = (status.size_matched - applied).min(order.size).max(Decimal::ZERO);
+        {
+            let mut orders = self.live_orders.write().await;
+            if status.open {
+                if let Some(tracked) = orders.get_mut(order_id) {
+                    tracked.size -= unapplied;
+                }
+            } else {
+                orders.remove(order_id);
+            }
+        }
+        if unapplied > Decimal::ZERO {
+            info!("[LIVE] 🎯 Snipe {} matched {} of {}", order_id, status.size_matched, status.original_size);
+            self.apply_live_fill(
+                &order.token_id,
+                order.side,
+                order.price,
+                unapplied,
+                order.exit_reason,
+                order.timestamp,
+            )
+            .await;
+        }
+
+        let filled = status.size_matched > Decimal::ZERO;
+        if filled {
+            self.fill_stats.write().await.record_filled(false);
+        }
+        Ok(filled)
+    }
+
+    /// Apply an order update from the CLOB user channel
+    ///
+    /// Fills of resting orders update the live position as they happen, so
+    /// it no longer depends on noticing the order has left the book. Events
+    /// for orders we don't track (including FAK orders, already applied when
+    /// posted) are ignored.
+    pub async fn apply_user_event(&self, event: UserEvent) {
+        match event {
+            UserEvent::Fill { order_id, price, size } => {
+                let (order, filled, done) = {
+                    let mut orders = self.live_orders.write().await;
+                    let Some(order) = orders.get_mut(&order_id) else {
+                        debug!("[LIVE] Fill for untracked order {} ignored", order_id);
+                        return;
+                    };
+                    let filled = size.min(order.size);
+                    order.size -= filled;
+                    let done = order.size <= Decimal::ZERO;
+                    let order = order.clone();
+                    if done {
+                        orders.remove(&order_id);
+                    }
+                    (order, filled, done)
+                };
+
+                info!(
+                    "[LIVE] 🔔 {:?} order {} filled {} @ {:.4}{}",
+                    order.side,
+                    order_id,
+                    filled,
+                    price,
+                    if done { String::new() } else { format!(" ({} left)", order.size) }
+                );
+                self.apply_live_fill(&order.token_id, order.side, price, filled, order.exit_reason, order.timestamp)
+                    .await;
+                if done {
+                    self.fill_stats.write().await.record_filled(false);
+                }
+            }
+            UserEvent::Cancelled { order_id } => {
+                if self.live_orders.write().await.remove(&order_id).is_some() {
+                    info!("[LIVE] 🚫 Order {} cancelled on the exchange", order_id);
+                }
+            }
+        }
+    }
+
+    /// Rebuild live orders and position for `market` from the CLOB
+    ///
+    /// Lets a restarted bot resume whatever a previous run left behind.
+    /// Orders and balances in tokens outside the market are reported but
+    /// not adopted.
+    pub async fn sync_live_state(&self, market: &MarketInfo) -> TradingResult<()> {
+        let client = self.clob_client.as_ref()
+            .ok_or(TradingError::ClientUnavailable)?;
+        let in_market = |token_id: &str| {
+            token_id == market.token_id_up || token_id == market.token_id_down
+        };
+
+        // Orders tracked but no longer open are dropped; ones still open keep their exit reason
+        let mut foreign_tokens = Vec::new();
+        let open_orders = client.get_open_orders().await?;
+        let mut tracked = std::mem::take(&mut *self.live_orders.write().await);
+        let mut orders = HashMap::new();
+        for order in open_orders {
+            if let Some(known) = tracked.remove(&order.id) {
+                orders.insert(order.id.clone(), Order { size: order.size, ..known });
+            } else if in_market(&order.token_id) {
+                info!(
+                    "[LIVE] 🔁 Adopting open {:?} order {} @ {:.4} (Size: {})",
+                    order.side, order.id, order.price, order.size
+                );
+                orders.insert(order.id.clone(), order);
+            } else {
+                warn!(
+                    "[LIVE] ⚠️ Open order {} is for token {}... outside the current market - not adopted",
+                    order.id,
+                    &order.token_id[..8.min(order.token_id.len())]
+                );
+                if !foreign_tokens.contains(&order.token_id) {
+                    foreign_tokens.push(order.token_id);
+                }
+            }
+        }
+        if !tracked.is_empty() {
+            info!("[LIVE] 🧹 Dropped {} tracked order(s) no longer open on the CLOB", tracked.len());
+        }
+        *self.live_orders.write().await = orders;
+
+        let mut position: Option<Position> = None;
+        for token_id in [&market.token_id_up, &market.token_id_down] {
+            let shares = client.get_token_balance(token_id).await?;
+            if shares <= Decimal::ZERO {
+                continue;
+            }
+            if let Some(existing) = &position {
+                warn!(
+                    "[LIVE] ⚠️ Holding both outcomes; tracking the larger of {} and {} shares",
+                    existing.shares, shares
+                );
+                if existing.shares >= shares {
+                    continue;
+                }
+            }
+
+            // The fill price isn't recoverable, so mark the entry at the current mid
+            let entry_price = match client.get_order_book(token_id).await?.mid() {
+                Some(mid) => mid,
+                None => Decimal::new(50, 2),
+            };
+            warn!(
+                "[LIVE] 🔁 Adopting position of {} shares, entry estimated @ {:.4}",
+                shares, entry_price
+            );
+
+            let mut pos = Position::new(token_id);
+            pos.add_fill(entry_price, shares);
+            position = Some(pos);
+        }
+        *self.live_position.write().await = position;
+
+        for token_id in foreign_tokens {
+            let shares = client.get_token_balance(&token_id).await?;
+            if shares > Decimal::ZERO {
+                warn!(
+                    "[LIVE] ⚠️ Holding {} shares of token {}... outside the current market - left untouched",
+                    shares,
+                    &token_id[..8.min(token_id.len())]
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Drop tracked live orders that are no longer open on the CLOB
+    ///
+    /// An order filled or cancelled without a user channel event would
+    /// otherwise hold a `MAX_OPEN_ORDERS` slot for the rest of the market.
+    async fn prune_live_orders(&self) -> TradingResult<()> {
+        let client = self.clob_client.as_ref()
+            .ok_or(TradingError::ClientUnavailable)?;
+        let open: Vec<String> = client.get_open_orders().await?.into_iter().map(|order| order.id).collect();
+
+        let mut orders = self.live_orders.write().await;
+        let before = orders.len();
+        orders.retain(|order_id, _| open.contains(order_id));
+        if orders.len() < before {
+            info!("[LIVE] 🧹 Dropped {} tracked order(s) no longer open on the CLOB", before - orders.len());
+        }
+        Ok(())
+    }
+
     async fn execute_live_fak(
         &self,
         token_id: &str,
         side: OrderSide,
         price: Decimal,
         size: Decimal,
-    ) -> Result<bool> {
+        exit_reason: Option<ExitReason>,
+    ) -> TradingResult<bool> {
         info!(
             "[LIVE] 💥 MARKET ORDER: {:?} @ {:.4} | Token: {}...",
             side,
@@ -391,26 +1507,983 @@ impl TradingService {
             &token_id[..8.min(token_id.len())]
         );
 
-        // For immediate execution, we just place a regular order
-        // The aggressive price will ensure immediate fill
-        let _order_id = self.place_live_order(token_id, side, price, size).await?;
+        // Post as immediate-or-cancel so any unfilled remainder never rests on the book
+        let (_order_id, filled) = self
+            .submit_live_order(token_id, side, price, size, Self::MARKET_ORDER_TYPE, exit_reason)
+            .await?;
+
+        if filled == Some(Decimal::ZERO) {
+            return Ok(false);
+        }
 
         info!("[LIVE] ✅ Market order executed");
         Ok(true)
     }
 
+    /// Best bid for a token: the cached paper book, or the live CLOB book
+    pub async fn best_bid(&self, token_id: &str) -> Option<Decimal> {
+        if self.config.paper_trade {
+            self.paper_books.read().await.get(token_id).and_then(|book| book.best_bid())
+        } else {
+            match self.fetch_order_book(token_id).await {
+                Ok(book) => book.best_bid(),
+                Err(e) => {
+                    warn!("⚠️ Order book fetch failed: {}", e);
+                    None
+                }
+            }
+        }
+    }
+
     /// Fetch order book from Polymarket using polyfill-rs
-    pub async fn fetch_order_book(&self, token_id: &str) -> Result<(Option<Decimal>, Option<Decimal>)> {
-        if let Some(client) = self.clob_client.as_ref() {
-            let book = client.get_order_book(token_id).await?;
+    pub async fn fetch_order_book(&self, token_id: &str) -> TradingResult<OrderBook> {
+        let client = self.clob_client.as_ref().ok_or(TradingError::ClientUnavailable)?;
+        Ok(client.get_order_book(token_id).await?)
+    }
+}
 
-            // Extract best bid and ask
-            let best_bid = book.bids.first().map(|level| level.price);
-            let best_ask = book.asks.first().map(|level| level.price);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
 
-            Ok((best_bid, best_ask))
-        } else {
-            anyhow::bail!("CLOB client not available in paper trading mode")
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    fn unlimited() -> Arc<RateLimiter> {
+        Arc::new(RateLimiter::new(0.0))
+    }
+
+    fn paper_service() -> TradingService {
+        TradingService::new(BotConfig::default(), unlimited()).unwrap()
+    }
+
+    /// CLOB client returning a canned order response and recording cancels
+    struct MockClob {
+        response: serde_json::Value,
+        cancelled: Arc<std::sync::Mutex<Vec<String>>>,
+        open_orders: Vec<Order>,
+        balances: std::sync::Mutex<HashMap<String, Decimal>>,
+        fill_on_post: bool,
+        posted: Arc<std::sync::Mutex<Vec<(ClobOrderType, Option<u64>)>>>, // order type and expiration of each post
+        statuses: HashMap<String, OrderStatus>,
+    }
+
+    impl MockClob {
+        /// Answers every post with `response`, with nothing resting or held
+        fn new(response: serde_json::Value) -> Self {
+            Self {
+                response,
+                cancelled: Arc::new(std::sync::Mutex::new(Vec::new())),
+                open_orders: Vec::new(),
+                balances: std::sync::Mutex::new(HashMap::new()),
+                fill_on_post: false,
+                posted: Arc::new(std::sync::Mutex::new(Vec::new())),
+                statuses: HashMap::new(),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl ClobApi for MockClob {
+        async fn post_order(
+            &self,
+            args: &OrderArgs,
+            order_type: ClobOrderType,
+            expiration: Option<u64>,
+        ) -> Result<serde_json::Value> {
+            self.posted.lock().unwrap().push((order_type, expiration));
+            if self.fill_on_post {
+                let mut balances = self.balances.lock().unwrap();
+                let balance = balances.entry(args.token_id.clone()).or_default();
+                match args.side {
+                    ClobSide::BUY => *balance += args.size,
+                    ClobSide::SELL => *balance -= args.size,
+                }
+            }
+            Ok(self.response.clone())
+        }
+
+        async fn cancel_orders(&self, order_ids: &[String]) -> Result<()> {
+            self.cancelled.lock().unwrap().extend_from_slice(order_ids);
+            Ok(())
+        }
+
+        async fn get_order_book(&self, token_id: &str) -> Result<OrderBook> {
+            let mut book = OrderBook::top("0.49", "0.51");
+            book.market = token_id.to_string();
+            Ok(book)
         }
+
+        async fn get_order(&self, order_id: &str) -> Result<OrderStatus> {
+            self.statuses.get(order_id).cloned().context("unknown order")
+        }
+
+        async fn get_open_orders(&self) -> Result<Vec<Order>> {
+            Ok(self.open_orders.clone())
+        }
+
+        async fn get_token_balance(&self, token_id: &str) -> Result<Decimal> {
+            Ok(self.balances.lock().unwrap().get(token_id).copied().unwrap_or_default())
+        }
+    }
+
+    fn live_config() -> BotConfig {
+        BotConfig {
+            paper_trade: false,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_live_round_trip_against_mock_clob() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let reply = |body: serde_json::Value| ResponseTemplate::new(200).set_body_json(body);
+        Mock::given(method("GET"))
+            .and(path("/tick-size"))
+            .respond_with(reply(serde_json::json!({ "minimum_tick_size": 0.01 })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/neg-risk"))
+            .respond_with(reply(serde_json::json!({ "neg_risk": false })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/order"))
+            .respond_with(reply(serde_json::json!({
+                "success": true,
+                "errorMsg": "",
+                "orderID": "0xmock",
+                "status": "live"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/orders"))
+            .respond_with(reply(serde_json::json!({ "canceled": ["0xmock"], "not_canceled": {} })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = BotConfig {
+            clob_base_url: server.uri(),
+            signer_private_key: format!("0x{}", "11".repeat(32)),
+            clob_api_key: "key".to_string(),
+            clob_api_secret: "c2VjcmV0".to_string(),
+            clob_api_passphrase: "passphrase".to_string(),
+            ..live_config()
+        };
+        let trading = TradingService::new(config, unlimited()).unwrap();
+
+        // Buy rests on the mock book, then the cancel takes it off
+        let order_id = trading.buy("token", dec("0.40"), dec("10")).await.unwrap();
+        assert_eq!(order_id, "0xmock");
+        assert_eq!(trading.open_order_ids().await, vec!["0xmock".to_string()]);
+        trading.cancel_order(&order_id).await.unwrap();
+        assert!(trading.open_order_ids().await.is_empty());
+
+        // Dropping the server verifies exactly one post and one cancel arrived
+        let requests = server.received_requests().await.unwrap();
+        assert!(requests.iter().any(|request| request.method.as_str() == "POST" && request.url.path() == "/order"));
+        assert!(requests.iter().any(|request| request.method.as_str() == "DELETE" && request.url.path() == "/orders"));
+    }
+
+    fn open_order(id: &str, token_id: &str) -> Order {
+        Order {
+            id: id.to_string(),
+            token_id: token_id.to_string(),
+            side: OrderSide::BUY,
+            price: dec("0.40"),
+            size: dec("25"),
+            order_type: OrderType::GTC,
+            timestamp: 0,
+            exit_reason: None,
+            placed_tick: 0,
+        }
+    }
+
+    /// Live service backed by a mock client; returns the mock's cancel log
+    fn live_service(response: serde_json::Value) -> (TradingService, Arc<std::sync::Mutex<Vec<String>>>) {
+        live_service_with(live_config(), MockClob::new(response))
+    }
+
+    fn live_service_with(config: BotConfig, mock: MockClob) -> (TradingService, Arc<std::sync::Mutex<Vec<String>>>) {
+        let cancelled = mock.cancelled.clone();
+        (TradingService::with_client(config, Some(Box::new(mock)), unlimited()), cancelled)
+    }
+
+    /// Give the service a position to exit from
+    async fn hold(trading: &TradingService, token_id: &str, shares: Decimal) {
+        let mut pos = Position::new(token_id);
+        pos.add_fill(dec("0.40"), shares);
+        let position = if trading.config.paper_trade { &trading.paper_position } else { &trading.live_position };
+        *position.write().await = Some(pos);
+    }
+
+    #[tokio::test]
+    async fn test_live_order_id_round_trip() {
+        let (trading, cancelled) = live_service(serde_json::json!({
+            "success": true,
+            "errorMsg": "",
+            "orderID": "0xabc123",
+            "status": "live"
+        }));
+
+        let order_id = trading.buy("token", dec("0.40"), dec("10")).await.unwrap();
+        assert_eq!(order_id, "0xabc123");
+        assert_eq!(trading.open_order_ids().await, vec!["0xabc123".to_string()]);
+
+        trading.cancel_order(&order_id).await.unwrap();
+        assert_eq!(*cancelled.lock().unwrap(), vec!["0xabc123".to_string()]);
+        assert!(trading.open_order_ids().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_self_trade_prevention() {
+        let resting = serde_json::json!({
+            "success": true,
+            "errorMsg": "",
+            "orderID": "0xabc123",
+            "status": "live"
+        });
+
+        // Default: our resting bid is cancelled before a sell that would hit it
+        let (trading, cancelled) = live_service(resting.clone());
+        hold(&trading, "token", dec("10")).await;
+        trading.buy("token", dec("0.40"), dec("10")).await.unwrap();
+        trading
+            .sell("token", dec("0.60"), dec("10"), ExitReason::TakeProfit)
+            .await
+            .unwrap();
+        assert!(cancelled.lock().unwrap().is_empty(), "a non-crossing ask leaves the bid alone");
+        trading.cancel_order("0xabc123").await.unwrap();
+        cancelled.lock().unwrap().clear();
+
+        trading.buy("token", dec("0.40"), dec("10")).await.unwrap();
+        assert!(trading
+            .execute_market_order("token", OrderSide::SELL, dec("0.38"), dec("10"), Some(ExitReason::StopLoss))
+            .await
+            .unwrap());
+        assert_eq!(*cancelled.lock().unwrap(), vec!["0xabc123".to_string()]);
+        assert!(trading.open_order_ids().await.is_empty());
+
+        // Reject: a new entry is refused and the resting exit stays
+        let config = BotConfig {
+            prevent_self_trade: SelfTradePrevention::Reject,
+            ..live_config()
+        };
+        let (trading, cancelled) = live_service_with(config.clone(), MockClob::new(resting.clone()));
+        hold(&trading, "token", dec("10")).await;
+        trading.sell("token", dec("0.40"), dec("10"), ExitReason::TakeProfit).await.unwrap();
+        let err = trading.buy("token", dec("0.45"), dec("10")).await.unwrap_err();
+        assert!(matches!(err, TradingError::SelfTrade(id) if id == "0xabc123"));
+        assert!(cancelled.lock().unwrap().is_empty());
+        assert_eq!(trading.open_order_ids().await, vec!["0xabc123".to_string()]);
+
+        // ...but an exit always goes out, cancelling the resting bid
+        let (trading, cancelled) = live_service_with(config, MockClob::new(resting.clone()));
+        hold(&trading, "token", dec("10")).await;
+        trading.buy("token", dec("0.40"), dec("10")).await.unwrap();
+        trading
+            .execute_market_order("token", OrderSide::SELL, dec("0.38"), dec("10"), Some(ExitReason::StopLoss))
+            .await
+            .unwrap();
+        assert_eq!(*cancelled.lock().unwrap(), vec!["0xabc123".to_string()]);
+
+        // An order past its TTL has left the book and can't be crossed
+        let config = BotConfig {
+            order_ttl_seconds: 30,
+            ..live_config()
+        };
+        let (trading, cancelled) = live_service_with(config, MockClob::new(resting));
+        hold(&trading, "token", dec("10")).await;
+        trading.buy("token", dec("0.40"), dec("10")).await.unwrap();
+        trading.live_orders.write().await.get_mut("0xabc123").unwrap().timestamp -= 100_000;
+        trading
+            .execute_market_order("token", OrderSide::SELL, dec("0.38"), dec("10"), Some(ExitReason::StopLoss))
+            .await
+            .unwrap();
+        assert!(cancelled.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_live_snipe_books_only_matched_size() {
+        let resting = serde_json::json!({ "success": true, "orderID": "0xsnipe", "status": "live" });
+        let config = BotConfig {
+            snipe_wait_time: 0,
+            ..live_config()
+        };
+        let snipe_with = |size_matched: &str, open: bool| {
+            let status = OrderStatus { original_size: dec("10"), size_matched: dec(size_matched), open };
+            let mock = MockClob {
+                statuses: HashMap::from([("0xsnipe".to_string(), status)]),
+                ..MockClob::new(resting.clone())
+            };
+            live_service_with(config.clone(), mock)
+        };
+
+        // Cancelled by the exchange with nothing matched: no fill booked
+        let (trading, cancelled) = snipe_with("0", false);
+        assert!(!trading.snipe("token", OrderSide::BUY, dec("0.40"), dec("10"), None).await.unwrap());
+        assert!(trading.get_position().await.is_none());
+        assert!(cancelled.lock().unwrap().is_empty());
+
+        // Partly matched and still resting: the match is booked and the rest cancelled
+        let (trading, cancelled) = snipe_with("4", true);
+        assert!(trading.snipe("token", OrderSide::BUY, dec("0.40"), dec("10"), None).await.unwrap());
+        assert_eq!(trading.get_position().await.unwrap().shares, dec("4"));
+        assert_eq!(*cancelled.lock().unwrap(), vec!["0xsnipe".to_string()]);
+
+        // Fills already applied over the user channel aren't booked twice
+        let (trading, _) = snipe_with("10", false);
+        let order_id = trading.buy("token", dec("0.40"), dec("10")).await.unwrap();
+        trading
+            .apply_user_event(UserEvent::Fill { order_id: order_id.clone(), price: dec("0.40"), size: dec("6") })
+            .await;
+        assert!(trading.settle_live_snipe(&order_id).await.unwrap());
+        assert_eq!(trading.get_position().await.unwrap().shares, dec("10"));
+        assert!(trading.open_order_ids().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_live_order_rejected() {
+        let (trading, _) = live_service(serde_json::json!({
+            "success": false,
+            "errorMsg": "not enough balance / allowance"
+        }));
+
+        let err = trading.buy("token", dec("0.40"), dec("10")).await.unwrap_err();
+        assert!(err.to_string().contains("not enough balance"));
+    }
+
+    #[test]
+    fn test_order_type_flags() {
+        assert_eq!(TradingService::clob_order_type(OrderType::GTC), ClobOrderType::GTC);
+        assert_eq!(TradingService::clob_order_type(OrderType::FOK), ClobOrderType::FOK);
+        assert_eq!(TradingService::clob_order_type(OrderType::IOC), ClobOrderType::FAK);
+
+        // Market orders must never rest on the book
+        assert_eq!(TradingService::MARKET_ORDER_TYPE, OrderType::IOC);
+    }
+
+    #[tokio::test]
+    async fn test_order_ttl_sets_expiration() {
+        let mock = MockClob::new(serde_json::json!({ "success": true, "orderID": "0xgtd", "status": "live" }));
+        let posted = mock.posted.clone();
+        let config = BotConfig {
+            order_ttl_seconds: 300,
+            ..live_config()
+        };
+        let (trading, _) = live_service_with(config, mock);
+        hold(&trading, "token", dec("10")).await;
+
+        let before = chrono::Utc::now().timestamp() as u64;
+        trading.buy("token", dec("0.40"), dec("10")).await.unwrap();
+        trading
+            .execute_market_order("token", OrderSide::SELL, dec("0.30"), dec("10"), None)
+            .await
+            .unwrap();
+        let after = chrono::Utc::now().timestamp() as u64;
+
+        // The resting order expires a minute of CLOB lead plus the TTL out; the market order never rests
+        let posted = posted.lock().unwrap().clone();
+        let (order_type, expiration) = posted[0];
+        assert_eq!(order_type, ClobOrderType::GTD);
+        let expiration = expiration.expect("limit order should carry an expiration");
+        assert!((before + 360..=after + 360).contains(&expiration));
+        assert_eq!(posted[1], (ClobOrderType::FAK, None));
+
+        // Without a TTL limit orders stay plain GTC
+        let trading = paper_service();
+        assert_eq!(trading.time_in_force(OrderType::GTC, 1_000), (ClobOrderType::GTC, None));
+    }
+
+    #[tokio::test]
+    async fn test_limit_orders_are_gtc() {
+        let trading = paper_service();
+        hold(&trading, "token", dec("10")).await;
+        let buy_id = trading.buy("token", dec("0.40"), dec("10")).await.unwrap();
+        let sell_id = trading
+            .sell("token", dec("0.60"), dec("10"), ExitReason::TakeProfit)
+            .await
+            .unwrap();
+
+        let orders = trading.paper_orders.read().await;
+        assert_eq!(orders[&buy_id].order_type, OrderType::GTC);
+        assert_eq!(orders[&sell_id].order_type, OrderType::GTC);
+    }
+
+    #[tokio::test]
+    async fn test_paper_buys_reserve_cash() {
+        let trading = paper_service(); // $100
+
+        let first = trading.buy("token", dec("0.50"), dec("150")).await.unwrap();
+        assert_eq!(trading.reserved_cash().await, dec("75"));
+
+        // $30 more would over-commit the remaining $25
+        let err = trading.buy("token", dec("0.50"), dec("60")).await.unwrap_err();
+        assert!(err.to_string().contains("Insufficient paper cash"));
+        assert!(matches!(
+            trading
+                .execute_market_order("token", OrderSide::BUY, dec("0.50"), dec("60"), None)
+                .await,
+            Err(TradingError::InsufficientFunds { .. })
+        ));
+
+        // Sells don't need cash
+        hold(&trading, "token", dec("10")).await;
+        trading.sell("token", dec("0.60"), dec("10"), ExitReason::TakeProfit).await.unwrap();
+
+        // Cancelling releases the reservation
+        trading.cancel_order(&first).await.unwrap();
+        assert_eq!(trading.reserved_cash().await, Decimal::ZERO);
+        trading.buy("token", dec("0.50"), dec("60")).await.unwrap();
+        assert_eq!(trading.get_cash_balance().await, dec("100"));
+    }
+
+    #[tokio::test]
+    async fn test_sells_clamped_to_position() {
+        let trading = paper_service();
+        trading
+            .execute_market_order("token", OrderSide::BUY, dec("0.50"), dec("10"), None)
+            .await
+            .unwrap();
+
+        // A resting exit for more than is held rests for the held shares
+        let order_id = trading
+            .sell("token", dec("0.60"), dec("25"), ExitReason::TakeProfit)
+            .await
+            .unwrap();
+        assert_eq!(trading.paper_orders.read().await[&order_id].size, dec("10"));
+        trading.cancel_order(&order_id).await.unwrap();
+
+        // A market exit for more than is held sells the position and no more
+        assert!(trading
+            .execute_market_order("token", OrderSide::SELL, dec("0.55"), dec("25"), Some(ExitReason::StopLoss))
+            .await
+            .unwrap());
+        let trades = trading.take_closed_trades().await;
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].size, dec("10"));
+        assert!(!trading.has_position().await);
+
+        // Nothing held: even a resting exit is refused rather than going short
+        let err = trading.sell("token", dec("0.60"), dec("10"), ExitReason::TakeProfit).await.unwrap_err();
+        assert!(matches!(err, TradingError::NoPosition(ref token) if token == "token"));
+        assert!(trading.open_order_ids().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sells_net_of_resting_exits() {
+        let trading = paper_service();
+        hold(&trading, "token", dec("10")).await;
+
+        // Shares already offered by a resting sell aren't available again
+        trading.sell("token", dec("0.60"), dec("6"), ExitReason::TakeProfit).await.unwrap();
+        let second = trading.sell("token", dec("0.65"), dec("10"), ExitReason::TakeProfit).await.unwrap();
+        assert_eq!(trading.paper_orders.read().await[&second].size, dec("4"));
+        let err = trading
+            .execute_market_order("token", OrderSide::SELL, dec("0.55"), dec("10"), Some(ExitReason::StopLoss))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, TradingError::NoPosition(_)));
+
+        // Cancelling a resting exit frees its shares
+        trading.cancel_order(&second).await.unwrap();
+        let third = trading.sell("token", dec("0.65"), dec("10"), ExitReason::TakeProfit).await.unwrap();
+        assert_eq!(trading.paper_orders.read().await[&third].size, dec("4"));
+
+        // Live orders count the same way
+        let (trading, _) = live_service(serde_json::json!({ "success": true, "orderID": "0xexit", "status": "live" }));
+        let err = trading.sell("token", dec("0.60"), dec("10"), ExitReason::TakeProfit).await.unwrap_err();
+        assert!(matches!(err, TradingError::NoPosition(_)));
+        hold(&trading, "token", dec("10")).await;
+        trading.sell("token", dec("0.60"), dec("10"), ExitReason::TakeProfit).await.unwrap();
+        let err = trading.sell("token", dec("0.65"), dec("5"), ExitReason::TakeProfit).await.unwrap_err();
+        assert!(matches!(err, TradingError::NoPosition(_)));
+    }
+
+    #[tokio::test]
+    async fn test_paper_failure_variants() {
+        let trading = paper_service(); // $100
+
+        let err = trading.buy("token", dec("0.50"), dec("300")).await.unwrap_err();
+        assert!(matches!(
+            err,
+            TradingError::InsufficientFunds { needed, available } if needed == dec("150") && available == dec("100")
+        ));
+
+        // Nothing held, then the wrong token held
+        let err = trading
+            .execute_market_order("token", OrderSide::SELL, dec("0.50"), dec("10"), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, TradingError::NoPosition(ref token) if token == "token"));
+        trading
+            .execute_market_order("other", OrderSide::BUY, dec("0.50"), dec("10"), None)
+            .await
+            .unwrap();
+        let err = trading
+            .execute_market_order("token", OrderSide::SELL, dec("0.50"), dec("10"), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, TradingError::NoPosition(_)));
+
+        let err = trading.cancel_order("PAPER_99").await.unwrap_err();
+        assert!(matches!(err, TradingError::OrderNotFound(ref id) if id == "PAPER_99"));
+
+        // Paper mode has no CLOB client to fetch books from
+        let err = trading.fetch_order_book("token").await.unwrap_err();
+        assert!(matches!(err, TradingError::ClientUnavailable));
+        assert!(!err.is_transient());
+    }
+
+    #[test]
+    fn test_network_errors_are_transient() {
+        let request_err = reqwest::Client::new().get("not a url").build().unwrap_err();
+        let err = TradingError::from(anyhow::Error::new(request_err).context("posting order"));
+        assert!(matches!(err, TradingError::Network(_)));
+        assert!(err.is_transient());
+
+        let err = TradingError::from(anyhow::anyhow!("unexpected response"));
+        assert!(matches!(err, TradingError::Other(_)));
+        assert!(!err.is_transient());
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_orders_rejected() {
+        let config = BotConfig {
+            order_dedup_window_ms: 60_000,
+            ..BotConfig::default()
+        };
+        let trading = TradingService::new(config, unlimited()).unwrap();
+
+        trading.buy("token", dec("0.45"), dec("10")).await.unwrap();
+        let err = trading.buy("token", dec("0.451"), dec("10")).await.unwrap_err();
+        assert!(matches!(err, TradingError::Duplicate(_)));
+        assert_eq!(trading.open_order_ids().await.len(), 1);
+
+        // A different price or side is a different signal
+        trading.buy("token", dec("0.44"), dec("10")).await.unwrap();
+        hold(&trading, "token", dec("10")).await;
+        trading.sell("token", dec("0.45"), dec("10"), ExitReason::TakeProfit).await.unwrap();
+
+        // A submission refused before reaching the book doesn't hold the key
+        let err = trading
+            .execute_market_order("token", OrderSide::SELL, dec("0.50"), dec("10"), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, TradingError::NoPosition(_)));
+        trading
+            .execute_market_order("token", OrderSide::BUY, dec("0.50"), dec("10"), None)
+            .await
+            .unwrap();
+        trading
+            .execute_market_order("token", OrderSide::SELL, dec("0.50"), dec("10"), None)
+            .await
+            .unwrap();
+
+        // Neither does a market order that found nothing to fill
+        trading.update_paper_book("token", OrderBook::from_levels(&[], &[])).await;
+        for _ in 0..2 {
+            assert!(!trading
+                .execute_market_order("token", OrderSide::BUY, dec("0.60"), dec("10"), None)
+                .await
+                .unwrap());
+        }
+    }
+
+
+    #[tokio::test]
+    async fn test_duplicate_key_follows_tick_size() {
+        let config = BotConfig {
+            order_dedup_window_ms: 60_000,
+            price_tick_size: dec("0.001"),
+            ..BotConfig::default()
+        };
+        let trading = TradingService::new(config, unlimited()).unwrap();
+
+        // Distinct ticks on a 0.001 grid are distinct signals
+        trading.buy("token", dec("0.451"), dec("10")).await.unwrap();
+        trading.buy("token", dec("0.454"), dec("10")).await.unwrap();
+        assert_eq!(trading.open_order_ids().await.len(), 2);
+
+        // The same tick written differently, or snapped onto it, is not
+        let err = trading.buy("token", dec("0.4540"), dec("10")).await.unwrap_err();
+        assert!(matches!(err, TradingError::Duplicate(_)));
+        let err = trading.buy("token", dec("0.4512"), dec("10")).await.unwrap_err();
+        assert!(matches!(err, TradingError::Duplicate(_)));
+    }
+    #[tokio::test]
+    async fn test_paper_fill_latency() {
+        let config = BotConfig {
+            paper_fill_latency_ticks: 2,
+            ..BotConfig::default()
+        };
+        let trading = TradingService::new(config, unlimited()).unwrap();
+        trading.buy("token", dec("0.45"), dec("10")).await.unwrap();
+
+        // Marketable from the start, but the order is still "in flight" for two ticks
+        for _ in 0..2 {
+            assert!(trading.check_paper_fills("token", dec("0.44"), dec("0.43")).await.is_none());
+            assert_eq!(trading.open_order_ids().await.len(), 1);
+        }
+        let pos = trading.check_paper_fills("token", dec("0.44"), dec("0.43")).await.unwrap();
+        assert_eq!(pos.shares, dec("10"));
+        assert!(trading.open_order_ids().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_paper_fills_average_entry() {
+        let trading = paper_service();
+
+        // One market fill and one resting limit fill at different prices
+        trading
+            .execute_market_order("token", OrderSide::BUY, dec("0.40"), dec("10"), None)
+            .await
+            .unwrap();
+        trading.buy("token", dec("0.50"), dec("30")).await.unwrap();
+        trading.check_paper_fills("token", dec("0.50"), dec("0.49")).await;
+
+        // (0.40 * 10 + 0.50 * 30) / 40 = 0.475
+        let pos = trading.get_position().await.unwrap();
+        assert_eq!(pos.shares, dec("40"));
+        assert_eq!(pos.entry_price, dec("0.475"));
+        assert_eq!(pos.calculate_pnl(dec("0.50")), dec("1.0"));
+    }
+
+    #[tokio::test]
+    async fn test_paper_market_fills_average_entry() {
+        let trading = paper_service();
+        for price in ["0.40", "0.50"] {
+            trading
+                .execute_market_order("token", OrderSide::BUY, dec(price), dec("100"), None)
+                .await
+                .unwrap();
+        }
+
+        // The second fill adds to the position rather than replacing it
+        let pos = trading.get_position().await.unwrap();
+        assert_eq!(pos.shares, dec("200"));
+        assert_eq!(pos.entry_price, dec("0.45"));
+    }
+
+    #[tokio::test]
+    async fn test_fill_stats_split_limit_and_market_orders() {
+        let trading = paper_service();
+
+        // Three resting bids; the ask only comes down to the highest
+        for price in ["0.40", "0.42", "0.45"] {
+            trading.buy("token", dec(price), dec("10")).await.unwrap();
+        }
+        trading.check_paper_fills("token", dec("0.45"), dec("0.43")).await;
+        trading.check_paper_fills("token", dec("0.46"), dec("0.44")).await;
+
+        // Market orders are counted apart and leave the limit fill rate alone
+        trading.update_paper_book("token", OrderBook::top("0.44", "0.46")).await;
+        trading
+            .execute_market_order("token", OrderSide::SELL, dec("0.44"), dec("10"), None)
+            .await
+            .unwrap();
+
+        let stats = trading.take_fill_stats().await;
+        assert_eq!(stats, FillStats { placed: 3, filled: 1, market_placed: 1, market_filled: 1 });
+        assert!((stats.fill_rate() - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(stats.market_fill_rate(), 1.0);
+
+        // Live, an immediate order the exchange doesn't report matched isn't a confirmed fill
+        let (live, _) = live_service(serde_json::json!({ "success": true, "orderID": "0xfak", "status": "live" }));
+        live.execute_market_order("token", OrderSide::BUY, dec("0.45"), dec("10"), None)
+            .await
+            .unwrap();
+        assert_eq!(live.take_fill_stats().await, FillStats { market_placed: 1, ..FillStats::default() });
+
+        // Taking resets the counters for the next market
+        assert_eq!(trading.take_fill_stats().await, FillStats::default());
+    }
+
+    #[tokio::test]
+    async fn test_paper_starting_cash() {
+        let config = BotConfig {
+            paper_starting_cash: dec("2500"),
+            ..BotConfig::default()
+        };
+        let trading = TradingService::new(config, unlimited()).unwrap();
+        assert_eq!(trading.get_cash_balance().await, dec("2500"));
+
+        // Round trip: final cash is the starting bankroll plus realized P&L
+        trading.update_paper_book("token", OrderBook::top("0.39", "0.40")).await;
+        trading
+            .execute_market_order("token", OrderSide::BUY, dec("0.40"), dec("100"), None)
+            .await
+            .unwrap();
+        trading.update_paper_book("token", OrderBook::top("0.45", "0.46")).await;
+        trading
+            .execute_market_order("token", OrderSide::SELL, dec("0.45"), dec("100"), Some(ExitReason::TakeProfit))
+            .await
+            .unwrap();
+        let pnl: Decimal = trading.take_closed_trades().await.iter().map(|t| t.pnl).sum();
+        assert_eq!(pnl, dec("5"));
+        assert_eq!(trading.get_cash_balance().await, dec("2505"));
+    }
+
+    #[tokio::test]
+    async fn test_order_prices_rounded_to_tick() {
+        let trading = paper_service();
+
+        // Resting orders land on the grid: buys down, sells up
+        let bid = trading.buy("token", dec("0.4567"), dec("10")).await.unwrap();
+        assert_eq!(trading.paper_orders.read().await[&bid].price, dec("0.45"));
+
+        // A market buy fills at the rounded-down price, a market sell at the rounded-up one
+        trading
+            .execute_market_order("token", OrderSide::BUY, dec("0.509"), dec("10"), None)
+            .await
+            .unwrap();
+        assert_eq!(trading.get_position().await.unwrap().entry_price, dec("0.50"));
+        trading
+            .execute_market_order("token", OrderSide::SELL, dec("0.541"), dec("10"), Some(ExitReason::Manual))
+            .await
+            .unwrap();
+        assert_eq!(trading.take_closed_trades().await[0].exit_price, dec("0.55"));
+    }
+
+    #[tokio::test]
+    async fn test_max_open_orders() {
+        let config = BotConfig {
+            max_open_orders: 2,
+            ..BotConfig::default()
+        };
+        let trading = TradingService::new(config, unlimited()).unwrap();
+
+        let first = trading.buy("token", dec("0.40"), dec("10")).await.unwrap();
+        trading.buy("token", dec("0.45"), dec("10")).await.unwrap();
+        let err = trading.buy("token", dec("0.42"), dec("10")).await.unwrap_err();
+        assert!(matches!(err, TradingError::OrderLimit(2)));
+
+        // Cancelling frees a slot
+        trading.cancel_order(&first).await.unwrap();
+        trading.buy("token", dec("0.42"), dec("10")).await.unwrap();
+        assert!(trading.buy("token", dec("0.41"), dec("10")).await.is_err());
+
+        // So does a fill
+        trading.check_paper_fills("token", dec("0.45"), dec("0.44")).await;
+        trading.buy("token", dec("0.41"), dec("10")).await.unwrap();
+
+        // Market orders never rest, so they aren't limited
+        assert!(trading
+            .execute_market_order("token", OrderSide::BUY, dec("0.45"), dec("1"), None)
+            .await
+            .unwrap());
+
+        // Live, an order that left the CLOB unnoticed doesn't hold its slot
+        let config = BotConfig {
+            max_open_orders: 1,
+            ..live_config()
+        };
+        let resting = serde_json::json!({ "success": true, "orderID": "0xgone", "status": "live" });
+        let (trading, _) = live_service_with(config, MockClob::new(resting));
+        trading.buy("token", dec("0.40"), dec("10")).await.unwrap();
+        trading.buy("token", dec("0.41"), dec("10")).await.unwrap();
+        assert_eq!(trading.open_order_ids().await, vec!["0xgone".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_sync_live_state_adopts_orders_and_position() {
+        let market = MarketInfo {
+            slug: "btc-updown-15m-test".to_string(),
+            token_id_up: "up-token".to_string(),
+            token_id_down: "down-token".to_string(),
+            strike_price: Decimal::from(98500),
+            expiry_timestamp: 0,
+            strike_finalized: true,
+        };
+        let mock = MockClob {
+            open_orders: vec![open_order("0xresting", "up-token"), open_order("0xold", "old-token")],
+            balances: std::sync::Mutex::new(HashMap::from([
+                ("down-token".to_string(), dec("30")),
+                ("old-token".to_string(), dec("12")),
+            ])),
+            ..MockClob::new(serde_json::Value::Null)
+        };
+        let (trading, _) = live_service_with(live_config(), mock);
+        assert!(!trading.has_position().await);
+
+        trading.sync_live_state(&market).await.unwrap();
+
+        // Only the current market's order is adopted
+        assert_eq!(trading.open_order_ids().await, vec!["0xresting".to_string()]);
+
+        // The balance becomes a position marked at the mid
+        let pos = trading.get_position().await.unwrap();
+        assert_eq!(pos.token_id, "down-token");
+        assert_eq!(pos.shares, dec("30"));
+        assert_eq!(pos.entry_price, dec("0.50"));
+
+        // Adopted orders can be cancelled
+        trading.cancel_order("0xresting").await.unwrap();
+        assert!(trading.open_order_ids().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_paper_market_order_walks_book() {
+        let trading = paper_service();
+        let book = OrderBook::from_levels(
+            &[("0.45", "100")],
+            &[("0.10", "100"), ("0.12", "100"), ("0.15", "100")],
+        );
+        trading.update_paper_book("token", book).await;
+
+        // 250 shares span three levels: (10 + 12 + 7.5) / 250 = 0.118
+        let filled = trading
+            .execute_market_order("token", OrderSide::BUY, dec("0.10"), dec("250"), None)
+            .await
+            .unwrap();
+        assert!(filled);
+
+        let pos = trading.get_position().await.unwrap();
+        assert_eq!(pos.shares, dec("250"));
+        assert_eq!(pos.entry_price, dec("0.118"));
+        assert_eq!(trading.get_cash_balance().await, dec("70.5"));
+    }
+
+    #[tokio::test]
+    async fn test_paper_snipe_place_wait_cancel() {
+        let config = BotConfig {
+            snipe_wait_time: 20,
+            ..BotConfig::default()
+        };
+        let trading = TradingService::new(config, unlimited()).unwrap();
+
+        // The ask ran away from our snipe: cancelled after the wait, nothing bought
+        trading.update_paper_book("token", OrderBook::top("0.48", "0.50")).await;
+        let started = std::time::Instant::now();
+        let filled = trading
+            .snipe("token", OrderSide::BUY, dec("0.47"), dec("10"), None)
+            .await
+            .unwrap();
+        assert!(!filled);
+        assert!(started.elapsed() >= Duration::from_millis(20));
+        assert!(trading.open_order_ids().await.is_empty());
+        assert!(!trading.has_position().await);
+
+        // A snipe through the ask fills at its limit
+        trading.update_paper_book("token", OrderBook::top("0.43", "0.45")).await;
+        assert!(trading
+            .snipe("token", OrderSide::BUY, dec("0.47"), dec("10"), None)
+            .await
+            .unwrap());
+        assert!(trading.open_order_ids().await.is_empty());
+        assert_eq!(trading.get_position().await.unwrap().entry_price, dec("0.47"));
+
+        // The dump mirrors it under the bid
+        assert!(trading
+            .snipe("token", OrderSide::SELL, dec("0.41"), dec("10"), Some(ExitReason::StopLoss))
+            .await
+            .unwrap());
+        assert!(!trading.has_position().await);
+        let trades = trading.take_closed_trades().await;
+        assert_eq!(trades[0].exit_price, dec("0.41"));
+        assert_eq!(trading.take_fill_stats().await, FillStats { placed: 3, filled: 2, ..FillStats::default() });
+    }
+
+    #[tokio::test]
+    async fn test_user_channel_fills_update_position() {
+        let (trading, _) = live_service(serde_json::json!({
+            "success": true,
+            "orderID": "0xresting",
+            "status": "live"
+        }));
+        let order_id = trading.buy("token", dec("0.40"), dec("10")).await.unwrap();
+        assert!(!trading.has_position().await);
+
+        // A partial fill opens the position and leaves the rest resting
+        trading
+            .apply_user_event(UserEvent::Fill { order_id: order_id.clone(), price: dec("0.40"), size: dec("4") })
+            .await;
+        let pos = trading.get_position().await.unwrap();
+        assert_eq!(pos.shares, dec("4"));
+        assert_eq!(trading.live_orders.read().await[&order_id].size, dec("6"));
+
+        // Fills for orders we don't track change nothing
+        trading
+            .apply_user_event(UserEvent::Fill { order_id: "0xother".to_string(), price: dec("0.50"), size: dec("100") })
+            .await;
+        assert_eq!(trading.get_position().await.unwrap().shares, dec("4"));
+
+        // The remainder fills at a better price and the order leaves the book
+        trading
+            .apply_user_event(UserEvent::Fill { order_id: order_id.clone(), price: dec("0.39"), size: dec("6") })
+            .await;
+        let pos = trading.get_position().await.unwrap();
+        assert_eq!(pos.shares, dec("10"));
+        assert_eq!(pos.entry_price, dec("0.394"));
+        assert!(trading.open_order_ids().await.is_empty());
+
+        // A resting exit cancelled on the exchange is forgotten
+        let exit_id = trading.sell("token", dec("0.45"), dec("10"), ExitReason::TakeProfit).await.unwrap();
+        trading.apply_user_event(UserEvent::Cancelled { order_id: exit_id }).await;
+        assert!(trading.open_order_ids().await.is_empty());
+        assert!(trading.has_position().await);
+    }
+
+    fn verifying_service(fill_on_post: bool) -> TradingService {
+        let mock = MockClob {
+            fill_on_post,
+            ..MockClob::new(serde_json::json!({ "success": true, "orderID": "0xfill", "status": "matched" }))
+        };
+        let config = BotConfig {
+            verify_live_fills: true,
+            fill_verify_attempts: 2,
+            fill_verify_interval: 0,
+            ..live_config()
+        };
+        live_service_with(config, mock).0
+    }
+
+    #[tokio::test]
+    async fn test_unverified_fill_is_not_applied() {
+        // The exchange says "matched" but the balance never moves
+        let trading = verifying_service(false);
+
+        let filled = trading
+            .execute_market_order("token", OrderSide::BUY, dec("0.40"), dec("10"), None)
+            .await
+            .unwrap();
+        assert!(!filled);
+        assert!(!trading.has_position().await);
+
+        // A matched limit order the balance doesn't show may still rest or fill later: keep tracking it
+        let order_id = trading.buy("token", dec("0.40"), dec("10")).await.unwrap();
+        assert!(!trading.has_position().await);
+        let tracked = trading.live_orders.read().await.get(&order_id).cloned().unwrap();
+        assert_eq!(tracked.size, dec("10"));
+    }
+
+    #[tokio::test]
+    async fn test_verified_fill_updates_live_position() {
+        let trading = verifying_service(true);
+
+        trading.buy("token", dec("0.40"), dec("10")).await.unwrap();
+        let pos = trading.get_position().await.unwrap();
+        assert_eq!(pos.shares, dec("10"));
+        assert_eq!(pos.entry_price, dec("0.40"));
+
+        // A verified exit closes the position and records the trade
+        let filled = trading
+            .execute_market_order("token", OrderSide::SELL, dec("0.45"), dec("10"), Some(ExitReason::StopLoss))
+            .await
+            .unwrap();
+        assert!(filled);
+        assert!(!trading.has_position().await);
+        let trades = trading.take_closed_trades().await;
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].pnl, dec("0.50"));
     }
 }
@@ -0,0 +1,183 @@
+/// CLOB user-channel WebSocket: authenticated order and trade updates for live fills
+use anyhow::{Context, Result};
+use futures_util::{SinkExt, StreamExt};
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::time::{interval, Duration};
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tracing::{debug, error, info, warn};
+
+use crate::trading::TradingService;
+
+const USER_WS_URL: &str = "wss://ws-subscriptions-clob.polymarket.com/ws/user";
+const PING_INTERVAL_SECS: u64 = 10; // the CLOB drops sockets that go quiet
+const RECONNECT_DELAY_SECS: u64 = 5;
+
+/// L2 API credentials authenticating the user channel
+#[derive(Debug, Clone)]
+pub struct UserCredentials {
+    pub api_key: String,
+    pub secret: String,
+    pub passphrase: String,
+}
+
+/// A change to one of our orders reported by the exchange
+#[derive(Debug, Clone, PartialEq)]
+pub enum UserEvent {
+    /// `size` shares of the order matched at `price`
+    Fill {
+        order_id: String,
+        price: Decimal,
+        size: Decimal,
+    },
+    /// The order left the book without filling further
+    Cancelled { order_id: String },
+}
+
+/// Parse a user-channel message, which may carry one event or an array of them
+///
+/// A trade is reported again as it is mined and confirmed; only the initial
+/// MATCHED report is turned into fills so each match is applied once.
+pub fn parse_user_message(text: &str) -> Vec<UserEvent> {
+    let Ok(value) = serde_json::from_str::<Value>(text) else {
+        return Vec::new();
+    };
+    match value {
+        Value::Array(events) => events.iter().flat_map(parse_event).collect(),
+        event => parse_event(&event),
+    }
+}
+
+fn parse_event(event: &Value) -> Vec<UserEvent> {
+    let field = |value: &Value, key: &str| value.get(key).and_then(Value::as_str).map(str::to_string);
+    let decimal = |value: &Value, key: &str| field(value, key).and_then(|raw| Decimal::from_str(&raw).ok());
+
+    match event.get("event_type").and_then(Value::as_str) {
+        Some("trade") => {
+            if field(event, "status").as_deref() != Some("MATCHED") {
+                return Vec::new();
+            }
+
+            // Our order may be the taker, any of the makers, or both
+            let mut fills = Vec::new();
+            if let (Some(order_id), Some(price), Some(size)) =
+                (field(event, "taker_order_id"), decimal(event, "price"), decimal(event, "size"))
+            {
+                fills.push(UserEvent::Fill { order_id, price, size });
+            }
+            for maker in event.get("maker_orders").and_then(Value::as_array).into_iter().flatten() {
+                if let (Some(order_id), Some(price), Some(size)) =
+                    (field(maker, "order_id"), decimal(maker, "price"), decimal(maker, "matched_amount"))
+                {
+                    fills.push(UserEvent::Fill { order_id, price, size });
+                }
+            }
+            fills
+        }
+        Some("order") if field(event, "type").as_deref() == Some("CANCELLATION") => field(event, "id")
+            .map(|order_id| vec![UserEvent::Cancelled { order_id }])
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+/// Keep a user-channel subscription open, applying fills to `trading`
+///
+/// Reconnects and re-authenticates whenever the socket drops.
+pub fn start(credentials: UserCredentials, trading: Arc<TradingService>) {
+    tokio::spawn(async move {
+        loop {
+            match run(&credentials, &trading).await {
+                Ok(()) => info!("User channel closed, reconnecting in {}s...", RECONNECT_DELAY_SECS),
+                Err(e) => error!("User channel error: {:#}. Reconnecting in {}s...", e, RECONNECT_DELAY_SECS),
+            }
+            tokio::time::sleep(Duration::from_secs(RECONNECT_DELAY_SECS)).await;
+        }
+    });
+}
+
+/// One connection: authenticate, then apply events until the socket closes
+async fn run(credentials: &UserCredentials, trading: &TradingService) -> Result<()> {
+    info!("🔌 Connecting to CLOB user channel: {}", USER_WS_URL);
+    let (ws_stream, _) = connect_async(USER_WS_URL)
+        .await
+        .context("Failed to connect to CLOB user channel")?;
+    let (mut write, mut read) = ws_stream.split();
+
+    let subscribe = json!({
+        "type": "user",
+        "markets": [],
+        "auth": {
+            "apiKey": credentials.api_key,
+            "secret": credentials.secret,
+            "passphrase": credentials.passphrase,
+        },
+    });
+    write
+        .send(Message::Text(subscribe.to_string()))
+        .await
+        .context("Failed to authenticate CLOB user channel")?;
+    info!("✅ Subscribed to CLOB user channel");
+
+    let mut ping = interval(Duration::from_secs(PING_INTERVAL_SECS));
+    loop {
+        tokio::select! {
+            _ = ping.tick() => {
+                write.send(Message::Text("PING".to_string())).await.context("Failed to ping CLOB user channel")?;
+            }
+            msg = read.next() => match msg {
+                Some(Ok(Message::Text(text))) => {
+                    let events = parse_user_message(&text);
+                    if events.is_empty() {
+                        debug!("User channel: {}", text);
+                    }
+                    for event in events {
+                        trading.apply_user_event(event).await;
+                    }
+                }
+                Some(Ok(Message::Close(_))) | None => {
+                    warn!("User channel closed by server");
+                    return Ok(());
+                }
+                Some(Err(e)) => return Err(e).context("User channel read failed"),
+                Some(Ok(_)) => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_trade_and_cancel() {
+        let trade = r#"{
+            "event_type": "trade", "status": "MATCHED", "id": "t1",
+            "taker_order_id": "0xtaker", "price": "0.57", "size": "12",
+            "maker_orders": [
+                {"order_id": "0xmaker1", "price": "0.57", "matched_amount": "5"},
+                {"order_id": "0xmaker2", "price": "0.56", "matched_amount": "7"}
+            ]
+        }"#;
+        let events = parse_user_message(trade);
+        assert_eq!(events.len(), 3);
+        assert_eq!(
+            events[2],
+            UserEvent::Fill {
+                order_id: "0xmaker2".to_string(),
+                price: Decimal::from_str("0.56").unwrap(),
+                size: Decimal::from(7),
+            }
+        );
+
+        // Later status reports of the same trade are not fills again
+        assert!(parse_user_message(&trade.replace("MATCHED", "CONFIRMED")).is_empty());
+
+        let cancel = r#"[{"event_type": "order", "type": "CANCELLATION", "id": "0xabc"}]"#;
+        assert_eq!(parse_user_message(cancel), vec![UserEvent::Cancelled { order_id: "0xabc".to_string() }]);
+        assert!(parse_user_message("PONG").is_empty());
+    }
+}
@@ -0,0 +1,98 @@
+/// Realized volatility of the BTC spot feed
+use rust_decimal::prelude::*;
+use rust_decimal::Decimal;
+use std::collections::VecDeque;
+
+use crate::quant::MINUTES_PER_YEAR;
+
+/// Rolling estimator of annualized realized volatility from spot samples
+pub struct VolatilityEstimator {
+    window: usize,
+    samples: VecDeque<(i64, f64)>, // (timestamp ms, price)
+}
+
+impl VolatilityEstimator {
+    /// Create an estimator over the last `window` spot samples
+    pub fn new(window: usize) -> Self {
+        Self {
+            window,
+            samples: VecDeque::with_capacity(window),
+        }
+    }
+
+    /// Record a spot price observed at `timestamp_ms`
+    pub fn record(&mut self, timestamp_ms: i64, price: Decimal) {
+        let price = match price.to_f64() {
+            Some(price) if price > 0.0 => price,
+            _ => return,
+        };
+
+        // Keep at most one sample per timestamp
+        if let Some(&(last_ts, _)) = self.samples.back() {
+            if timestamp_ms <= last_ts {
+                return;
+            }
+        }
+
+        if self.samples.len() == self.window {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((timestamp_ms, price));
+    }
+
+    /// Annualized realized volatility, or None until enough samples exist
+    ///
+    /// Sums squared log returns over the window and scales by the elapsed
+    /// time, so uneven tick spacing is handled.
+    pub fn realized_vol(&self) -> Option<f64> {
+        if self.samples.len() < 3 {
+            return None;
+        }
+
+        let sum_squared: f64 = self
+            .samples
+            .iter()
+            .zip(self.samples.iter().skip(1))
+            .map(|((_, prev), (_, next))| (next / prev).ln().powi(2))
+            .sum();
+
+        let first_ts = self.samples.front()?.0;
+        let last_ts = self.samples.back()?.0;
+        let elapsed_years = (last_ts - first_ts) as f64 / 60_000.0 / MINUTES_PER_YEAR;
+
+        let vol = (sum_squared / elapsed_years).sqrt();
+        if vol.is_finite() && vol > 0.0 {
+            Some(vol)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_realized_vol() {
+        let mut estimator = VolatilityEstimator::new(4);
+
+        // Not enough data yet, and a flat price has no volatility
+        estimator.record(0, Decimal::from(100_000));
+        estimator.record(1_000, Decimal::from(100_000));
+        assert_eq!(estimator.realized_vol(), None);
+        estimator.record(2_000, Decimal::from(100_000));
+        assert_eq!(estimator.realized_vol(), None);
+
+        // Moves push the estimate up; the window caps the sample count
+        estimator.record(3_000, Decimal::from(100_050));
+        estimator.record(4_000, Decimal::from(99_980));
+        assert_eq!(estimator.samples.len(), 4);
+        let vol = estimator.realized_vol().unwrap();
+        assert!(vol > 0.0);
+
+        // Out-of-order samples are ignored
+        estimator.record(4_000, Decimal::from(90_000));
+        assert_eq!(estimator.realized_vol(), Some(vol));
+    }
+}
@@ -9,6 +9,9 @@ use tracing::{error, info};
 const USDC_ADDRESS: &str = "0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174";
 const USDC_DECIMALS: u32 = 6;
 
+/// Conditional Tokens (ERC-1155) contract holding Polymarket outcome shares
+const CTF_ADDRESS: &str = "0x4D97DCd97eC945f40cF65F87097ACe5EA0476045";
+
 /// Wallet service for checking balances
 pub struct WalletService {
     provider: Arc<Provider<Http>>,
@@ -76,7 +79,7 @@ impl WalletService {
     }
 
     /// Get USDC balance
-    async fn get_usdc_balance(&self) -> Result<Decimal> {
+    pub async fn get_usdc_balance(&self) -> Result<Decimal> {
         let usdc_address: H160 = USDC_ADDRESS.parse()?;
 
         // ERC20 balanceOf(address) function signature
@@ -87,29 +90,58 @@ impl WalletService {
             bytes
         };
 
-        let call = ethers::types::transaction::eip2718::TypedTransaction::Legacy(
-            ethers::types::TransactionRequest {
-                to: Some(ethers::types::NameOrAddress::Address(usdc_address)),
-                data: Some(data.into()),
-                ..Default::default()
-            },
-        );
-
-        let result = self
-            .provider
-            .call(&call, None)
+        let balance = self
+            .call_balance_of(usdc_address, data)
             .await
             .context("Failed to call USDC balanceOf")?;
 
-        // Parse result as U256
-        let balance = U256::from_big_endian(&result);
-
         // Convert from USDC (6 decimals)
         let usdc = Decimal::from_str(&balance.to_string())? / Decimal::from(1_000_000u64);
 
         Ok(usdc)
     }
 
+    /// Get the proxy wallet's balance of an outcome token, in shares
+    pub async fn get_outcome_token_balance(&self, token_id: &str) -> Result<Decimal> {
+        let ctf_address: H160 = CTF_ADDRESS.parse()?;
+        let id = U256::from_dec_str(token_id).context("Invalid outcome token ID")?;
+
+        // ERC1155 balanceOf(address,uint256) function signature
+        let data = {
+            let mut bytes = vec![0x00, 0xfd, 0xd5, 0x8e]; // balanceOf selector
+            bytes.extend_from_slice(&[0u8; 12]); // Padding
+            bytes.extend_from_slice(self.proxy_address.as_bytes());
+            let mut id_word = [0u8; 32];
+            id.to_big_endian(&mut id_word);
+            bytes.extend_from_slice(&id_word);
+            bytes
+        };
+
+        let balance = self
+            .call_balance_of(ctf_address, data)
+            .await
+            .context("Failed to call outcome token balanceOf")?;
+
+        // Outcome shares use 6 decimals, like USDC
+        let shares = Decimal::from_str(&balance.to_string())? / Decimal::from(1_000_000u64);
+
+        Ok(shares)
+    }
+
+    /// Run a read-only `balanceOf` call and parse the result as U256
+    async fn call_balance_of(&self, contract: H160, data: Vec<u8>) -> Result<U256> {
+        let call = ethers::types::transaction::eip2718::TypedTransaction::Legacy(
+            ethers::types::TransactionRequest {
+                to: Some(ethers::types::NameOrAddress::Address(contract)),
+                data: Some(data.into()),
+                ..Default::default()
+            },
+        );
+
+        let result = self.provider.call(&call, None).await?;
+        Ok(U256::from_big_endian(&result))
+    }
+
     /// Validate sufficient balance for trading
     pub async fn validate_trading_balance(&self, min_usdc: Decimal) -> Result<bool> {
         let (_matic, usdc) = self.check_balances().await?;